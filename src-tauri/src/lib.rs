@@ -5,15 +5,23 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, State};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, reload, Registry};
 use uuid::Uuid;
 use md5;
 
+// set_log_level에서 런타임에 로그 레벨을 바꾸기 위한 핸들
+static LOG_LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
 #[derive(Default)]
 struct AppState {
     db_path: Mutex<Option<PathBuf>>,
+    // 잠금 해제된 가계부 계정 ID → 마지막 접근 시각
+    unlocked_ledger_accounts: Mutex<HashMap<String, Instant>>,
 }
 
 #[derive(Serialize)]
@@ -33,6 +41,37 @@ struct ProxyResponse {
     final_url: Option<String>,
     response_headers: Vec<String>,
     request_headers: Vec<String>,
+    redirect_count: u32,
+    body_was_lossy: bool,
+    // verbose 요청일 때만 채움
+    timing: Option<ProxyTiming>,
+}
+
+// libcurl 단계별 누적 시간 (요청 시작 기준, 밀리초)
+#[derive(Serialize)]
+struct ProxyTiming {
+    namelookup_ms: f64,
+    connect_ms: f64,
+    pretransfer_ms: f64,
+    starttransfer_ms: f64,
+    total_ms: f64,
+}
+
+// ProxyResponse + 응답 바디 JSON 파싱 결과
+#[derive(Serialize)]
+struct JsonProxyResponse {
+    #[serde(flatten)]
+    response: ProxyResponse,
+    json_body: Option<Value>,
+    // 바디가 있지만 JSON이 아닐 때의 파싱 오류
+    json_error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DownloadResponse {
+    status: u32,
+    bytes_written: u64,
+    final_url: Option<String>,
 }
 
 fn set_db_path(state: &AppState, path: PathBuf) {
@@ -102,9 +141,32 @@ fn ensure_parent(path: &Path) -> Result<(), String> {
     Ok(())
 }
 
+// 목록 조회 limit 상한 (잘못된 호출로 테이블 전체를 메모리에 올리는 것을 방지)
+const MAX_LIST_LIMIT: i64 = 1000;
+
+// 미지정이거나 0 이하이면 기본값, 상한을 넘으면 상한으로 보정
+fn clamp_limit(requested: Option<i64>, default: i64, max: i64) -> i64 {
+    match requested {
+        Some(limit) if limit > 0 => limit.min(max),
+        _ => default,
+    }
+}
+
 fn run_migrations(path: &Path) -> Result<(), String> {
+    let _span = tracing::info_span!("run_migrations", path = %path.display()).entered();
     ensure_parent(path)?;
     let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    apply_migrations(&conn)?;
+
+    tracing::debug!("migrations completed");
+    Ok(())
+}
+
+// 마이그레이션 완료 시 PRAGMA user_version에 기록하는 스키마 버전 (스키마를 바꾸면 올림)
+const SCHEMA_VERSION: i64 = 2;
+
+// 테이블 생성 및 마이그레이션 (verify_schema의 기대 스키마도 이 함수로 생성)
+fn apply_migrations(conn: &Connection) -> Result<(), String> {
     conn.execute_batch(
         r#"
         PRAGMA foreign_keys = ON;
@@ -189,12 +251,14 @@ fn run_migrations(path: &Path) -> Result<(), String> {
             merchant_country_code       TEXT,
             merchant_country_name       TEXT,
             application_completed       BOOLEAN,
+            raw_json                    TEXT,
             created_at              TEXT NOT NULL DEFAULT (datetime('now')),
             updated_at              TEXT NOT NULL DEFAULT (datetime('now')),
             FOREIGN KEY(user_id) REFERENCES tbl_user(id) ON DELETE CASCADE
         );
         
         CREATE UNIQUE INDEX IF NOT EXISTS ux_naver_payment_user_pay ON tbl_naver_payment (user_id, pay_id);
+        CREATE INDEX IF NOT EXISTS idx_naver_payment_external_id ON tbl_naver_payment(user_id, external_id);
         
         -- 네이버 페이 결제 상세 항목 테이블
         CREATE TABLE IF NOT EXISTS tbl_naver_payment_item (
@@ -267,6 +331,9 @@ fn run_migrations(path: &Path) -> Result<(), String> {
             wow_instant_discount        INTEGER DEFAULT 0,     -- WOW 즉시 할인 금액
             reward_cash_amount          INTEGER DEFAULT 0,     -- 적립 예정 캐시
 
+            -- 원본 수집 JSON (선택 저장)
+            raw_json                    TEXT,
+
             -- 타임스탬프
             created_at                  TEXT NOT NULL DEFAULT (datetime('now')),
             updated_at                  TEXT NOT NULL DEFAULT (datetime('now')),
@@ -313,6 +380,8 @@ fn run_migrations(path: &Path) -> Result<(), String> {
         
         CREATE UNIQUE INDEX IF NOT EXISTS ux_coupang_payment_item_payment_line 
             ON tbl_coupang_payment_item (payment_id, line_no);
+        CREATE INDEX IF NOT EXISTS idx_coupang_item_product_id ON tbl_coupang_payment_item(product_id);
+        CREATE INDEX IF NOT EXISTS idx_coupang_item_vendor_item_id ON tbl_coupang_payment_item(vendor_item_id);
         
         -- 가계부 계정 테이블
         CREATE TABLE IF NOT EXISTS tbl_ledger_account (
@@ -321,7 +390,8 @@ fn run_migrations(path: &Path) -> Result<(), String> {
             password_hash TEXT,
             password_expires_at TEXT,
             created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            currency_symbol TEXT NOT NULL DEFAULT '₩'
         );
         
         -- 가계부 항목 테이블
@@ -339,6 +409,8 @@ fn run_migrations(path: &Path) -> Result<(), String> {
             payment_method TEXT,
             memo TEXT,
             color TEXT,
+            source_provider TEXT,           -- 결제에서 변환된 항목의 출처 (naver/coupang)
+            source_payment_id INTEGER,      -- 변환 원본 결제의 내부 ID
             created_at TEXT NOT NULL DEFAULT (datetime('now')),
             updated_at TEXT NOT NULL DEFAULT (datetime('now')),
             FOREIGN KEY(account_id) REFERENCES tbl_ledger_account(id) ON DELETE CASCADE
@@ -366,17 +438,63 @@ fn run_migrations(path: &Path) -> Result<(), String> {
             action TEXT NOT NULL CHECK(action IN ('create', 'update', 'delete')),
             snapshot_before TEXT,
             snapshot_after TEXT,
-            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            FOREIGN KEY(entry_id) REFERENCES tbl_ledger_entry(id) ON DELETE CASCADE
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
         );
         
         CREATE INDEX IF NOT EXISTS idx_ledger_history_entry_id ON tbl_ledger_history(entry_id);
         
+        -- 가계부 패스워드 확인 시도 기록 (무차별 대입 방지)
+        CREATE TABLE IF NOT EXISTS tbl_auth_attempt (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id TEXT NOT NULL,
+            success BOOLEAN NOT NULL,
+            attempt_at TEXT NOT NULL
+        );
+        
+        CREATE INDEX IF NOT EXISTS idx_auth_attempt_account_id ON tbl_auth_attempt(account_id, attempt_at);
+        
+        -- 가계부 자동 분류 규칙 테이블 (set_tags는 JSON 배열)
+        CREATE TABLE IF NOT EXISTS tbl_ledger_rule (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            match_field TEXT NOT NULL CHECK(match_field IN ('merchant', 'title')),
+            match_type TEXT NOT NULL CHECK(match_type IN ('exact', 'contains')),
+            match_value TEXT NOT NULL,
+            set_category TEXT,
+            set_tags TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(account_id) REFERENCES tbl_ledger_account(id) ON DELETE CASCADE
+        );
+        
+        CREATE INDEX IF NOT EXISTS idx_ledger_rule_account_id ON tbl_ledger_rule(account_id);
+        
+        -- 저장 요청 중복 방지 키 테이블 (재시도 시 같은 결과 반환, 하루 후 만료)
+        CREATE TABLE IF NOT EXISTS tbl_idempotency_key (
+            key TEXT NOT NULL,
+            scope TEXT NOT NULL,
+            account_id TEXT NOT NULL,
+            result_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (scope, account_id, key)
+        );
+        
+        -- 가맹점 이름 기반 카테고리 추천 규칙 (priority가 클수록 우선)
+        CREATE TABLE IF NOT EXISTS tbl_merchant_rule (
+            id TEXT PRIMARY KEY,
+            pattern TEXT NOT NULL,
+            match_type TEXT NOT NULL CHECK(match_type IN ('exact', 'prefix', 'contains')),
+            category TEXT NOT NULL,
+            priority INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        
         -- 상품 카테고리 마스터 테이블 (미리 정의된 카테고리)
         CREATE TABLE IF NOT EXISTS tbl_category (
             id TEXT PRIMARY KEY,
             name TEXT UNIQUE NOT NULL,
             color TEXT,
+            parent_id TEXT REFERENCES tbl_category(id) ON DELETE SET NULL,
             created_at TEXT NOT NULL DEFAULT (datetime('now'))
         );
         
@@ -426,10 +544,19 @@ fn run_migrations(path: &Path) -> Result<(), String> {
     .map_err(|e| e.to_string())?;
 
     // 기존 테이블에 새 컬럼 추가 (마이그레이션)
-    migrate_coupang_tables(&conn)?;
+    migrate_coupang_tables(conn)?;
+    migrate_ledger_history_table(conn)?;
+    migrate_ledger_entry_table(conn)?;
+    migrate_ledger_account_table(conn)?;
+    migrate_category_table(conn)?;
+    migrate_payment_raw_json(conn)?;
+    migrate_idempotency_key_table(conn)?;
     
     // 기본 카테고리 추가
-    seed_default_categories(&conn)?;
+    seed_default_categories(conn)?;
+    
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -481,6 +608,143 @@ fn migrate_coupang_tables(conn: &Connection) -> Result<(), String> {
     Ok(())
 }
 
+// 결제 테이블 마이그레이션: 원본 수집 JSON 컬럼 추가
+fn migrate_payment_raw_json(conn: &Connection) -> Result<(), String> {
+    for table in ["tbl_naver_payment", "tbl_coupang_payment"] {
+        let sql = format!("ALTER TABLE {} ADD COLUMN raw_json TEXT", table);
+        // 컬럼이 이미 존재하면 에러가 발생하지만 무시
+        let _ = conn.execute(&sql, []);
+    }
+    Ok(())
+}
+
+// 가계부 히스토리 마이그레이션: 항목 삭제 시 이력이 함께 지워지지 않도록 FK 제거
+fn migrate_ledger_history_table(conn: &Connection) -> Result<(), String> {
+    let fk_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_foreign_key_list('tbl_ledger_history')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if fk_count == 0 {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        r#"
+        BEGIN;
+        CREATE TABLE tbl_ledger_history_new (
+            id TEXT PRIMARY KEY,
+            entry_id TEXT NOT NULL,
+            action TEXT NOT NULL CHECK(action IN ('create', 'update', 'delete')),
+            snapshot_before TEXT,
+            snapshot_after TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        INSERT INTO tbl_ledger_history_new (id, entry_id, action, snapshot_before, snapshot_after, created_at)
+            SELECT id, entry_id, action, snapshot_before, snapshot_after, created_at FROM tbl_ledger_history;
+        DROP TABLE tbl_ledger_history;
+        ALTER TABLE tbl_ledger_history_new RENAME TO tbl_ledger_history;
+        CREATE INDEX IF NOT EXISTS idx_ledger_history_entry_id ON tbl_ledger_history(entry_id);
+        COMMIT;
+    "#,
+    )
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!("tbl_ledger_history rebuilt without entry foreign key");
+    Ok(())
+}
+
+// 중복 방지 키 마이그레이션: 키를 계정별로 구분하도록 account_id 추가
+fn migrate_idempotency_key_table(conn: &Connection) -> Result<(), String> {
+    let has_account_id: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info('tbl_idempotency_key') WHERE name = 'account_id')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if has_account_id {
+        return Ok(());
+    }
+
+    // 기존 키는 결과 항목의 계정으로 옮기고, 항목이 없어진 키는 버림
+    // created_at은 만료 비교(RFC3339 문자열)와 맞도록 datetime('now') 형식도 UTC RFC3339로 변환
+    conn.execute_batch(
+        r#"
+        BEGIN;
+        CREATE TABLE tbl_idempotency_key_new (
+            key TEXT NOT NULL,
+            scope TEXT NOT NULL,
+            account_id TEXT NOT NULL,
+            result_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (scope, account_id, key)
+        );
+        INSERT INTO tbl_idempotency_key_new (key, scope, account_id, result_id, created_at)
+            SELECT k.key, k.scope, e.account_id, k.result_id,
+                   COALESCE(strftime('%Y-%m-%dT%H:%M:%S+00:00', k.created_at), k.created_at)
+            FROM tbl_idempotency_key k
+            JOIN tbl_ledger_entry e ON e.id = k.result_id;
+        DROP TABLE tbl_idempotency_key;
+        ALTER TABLE tbl_idempotency_key_new RENAME TO tbl_idempotency_key;
+        COMMIT;
+    "#,
+    )
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!("tbl_idempotency_key rebuilt with account scope");
+    Ok(())
+}
+
+// 가계부 항목 마이그레이션: 결제 변환 출처 컬럼 추가
+fn migrate_ledger_entry_table(conn: &Connection) -> Result<(), String> {
+    let columns = vec![("source_provider", "TEXT"), ("source_payment_id", "INTEGER")];
+
+    for (col_name, col_type) in &columns {
+        let sql = format!(
+            "ALTER TABLE tbl_ledger_entry ADD COLUMN {} {}",
+            col_name, col_type
+        );
+        // 컬럼이 이미 존재하면 에러가 발생하지만 무시
+        let _ = conn.execute(&sql, []);
+    }
+
+    // 같은 결제를 한 계정에 중복 변환하지 않도록 보장
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS ux_ledger_entry_source
+            ON tbl_ledger_entry (account_id, source_provider, source_payment_id)
+            WHERE source_payment_id IS NOT NULL",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 가계부 계정 마이그레이션: 표시 통화 기호 컬럼 추가 (기존 계정은 기본값 ₩)
+fn migrate_ledger_account_table(conn: &Connection) -> Result<(), String> {
+    // 컬럼이 이미 존재하면 에러가 발생하지만 무시
+    let _ = conn.execute(
+        "ALTER TABLE tbl_ledger_account ADD COLUMN currency_symbol TEXT NOT NULL DEFAULT '₩'",
+        [],
+    );
+
+    Ok(())
+}
+
+// 카테고리 마이그레이션: 상위 카테고리 컬럼 추가
+fn migrate_category_table(conn: &Connection) -> Result<(), String> {
+    // 컬럼이 이미 존재하면 에러가 발생하지만 무시
+    let _ = conn.execute(
+        "ALTER TABLE tbl_category ADD COLUMN parent_id TEXT REFERENCES tbl_category(id) ON DELETE SET NULL",
+        [],
+    );
+
+    Ok(())
+}
+
 // 기본 카테고리 시드 데이터 추가
 fn seed_default_categories(conn: &Connection) -> Result<(), String> {
     let default_categories = vec![
@@ -582,85 +846,459 @@ fn get_table_stats(app_handle: AppHandle, state: State<AppState>) -> Result<Vec<
     Ok(stats)
 }
 
+// 현재 DB 스키마를 CREATE 문으로 반환 (참조되는 테이블이 먼저 오도록 정렬, 인덱스는 각 테이블 뒤에 배치)
 #[tauri::command]
-fn truncate_table(app_handle: AppHandle, state: State<AppState>, table_name: String) -> Result<(), String> {
+fn get_schema_sql(app_handle: AppHandle, state: State<AppState>) -> Result<String, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
+        return Ok(String::new());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    // 안전을 위해 테이블 이름 검증 (SQL Injection 방지 - 간단히 공백/특수문자 체크)
-    if table_name.contains(' ') || table_name.contains(';') {
-        return Err("유효하지 않은 테이블 이름입니다.".to_string());
+    let mut table_stmt = conn
+        .prepare(
+            "SELECT name, sql FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL
+             ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let tables = table_stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    
+    let mut parent_stmt = conn
+        .prepare("SELECT DISTINCT \"table\" FROM pragma_foreign_key_list(?1)")
+        .map_err(|e| e.to_string())?;
+    let mut pending = Vec::new();
+    for (name, sql) in tables {
+        let parents = parent_stmt
+            .query_map([&name], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        pending.push((name, sql, parents));
+    }
+    
+    // 부모 테이블이 모두 나온 테이블부터 순서대로 배치 (순환 참조가 있으면 남은 순서 그대로)
+    let mut ordered: Vec<(String, String)> = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|(name, _, parents)| {
+            parents.iter().all(|parent| {
+                parent == name
+                    || ordered.iter().any(|(done, _)| done == parent)
+                    || !pending.iter().any(|(other, _, _)| other == parent)
+            })
+        });
+        let (name, sql, _) = pending.remove(ready.unwrap_or(0));
+        ordered.push((name, sql));
+    }
+    
+    let mut index_stmt = conn
+        .prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE type = 'index' AND tbl_name = ?1 AND sql IS NOT NULL
+             ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut statements = Vec::new();
+    for (name, sql) in ordered {
+        statements.push(sql);
+        let indexes = index_stmt
+            .query_map([&name], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        for index_sql in indexes {
+            statements.push(index_sql.map_err(|e| e.to_string())?);
+        }
+    }
+    
+    // 뷰/트리거는 테이블 뒤에 추가
+    let mut other_stmt = conn
+        .prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE type IN ('view', 'trigger') AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL
+             ORDER BY type DESC, name",
+        )
+        .map_err(|e| e.to_string())?;
+    let others = other_stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    for other_sql in others {
+        statements.push(other_sql.map_err(|e| e.to_string())?);
     }
+    
+    Ok(statements
+        .iter()
+        .map(|sql| format!("{};", sql.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ColumnInfo {
+    name: String,
+    data_type: String,
+    not_null: bool,
+    default_value: Option<String>,
+    is_pk: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexInfo {
+    name: String,
+    unique: bool,
+    // 표현식 인덱스의 컬럼은 "<expr>"
+    columns: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableSchema {
+    name: String,
+    sql: String,
+    columns: Vec<ColumnInfo>,
+    indexes: Vec<IndexInfo>,
+}
 
-    conn.execute(&format!("DELETE FROM {}", table_name), [])
+// 스키마 뷰어용 테이블별 컬럼/인덱스 정보 (UNIQUE 제약으로 생긴 자동 인덱스 포함)
+#[tauri::command]
+fn get_db_schema(app_handle: AppHandle, state: State<AppState>) -> Result<Vec<TableSchema>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut table_stmt = conn
+        .prepare(
+            "SELECT name, sql FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL
+             ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let tables = table_stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    
+    let mut column_stmt = conn
+        .prepare(
+            "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1) ORDER BY cid",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut index_stmt = conn
+        .prepare("SELECT name, \"unique\" FROM pragma_index_list(?1) ORDER BY name")
         .map_err(|e| e.to_string())?;
+    let mut index_column_stmt = conn
+        .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")
+        .map_err(|e| e.to_string())?;
+    
+    let mut schemas = Vec::new();
+    for (name, sql) in tables {
+        let columns = column_stmt
+            .query_map([&name], |row| {
+                Ok(ColumnInfo {
+                    name: row.get(0)?,
+                    data_type: row.get(1)?,
+                    not_null: row.get(2)?,
+                    default_value: row.get(3)?,
+                    is_pk: row.get::<_, i64>(4)? > 0,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
         
-    // VACUUM은 선택사항이지만 용량 확보를 위해 실행 가능 (오래 걸릴 수 있음)
-    // conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
+        let index_list = index_stmt
+            .query_map([&name], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        let mut indexes = Vec::new();
+        for (index_name, unique) in index_list {
+            let columns = index_column_stmt
+                .query_map([&index_name], |row| {
+                    Ok(row.get::<_, Option<String>>(0)?.unwrap_or_else(|| "<expr>".to_string()))
+                })
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            indexes.push(IndexInfo {
+                name: index_name,
+                unique,
+                columns,
+            });
+        }
+        
+        schemas.push(TableSchema {
+            name,
+            sql,
+            columns,
+            indexes,
+        });
+    }
     
-    Ok(())
+    Ok(schemas)
 }
 
+// truncate_table dry_run 결과 (삭제 예정 행 수와 CASCADE로 함께 지워지는 자식 테이블)
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct TableDataResponse {
-    columns: Vec<String>,
-    rows: Vec<Vec<serde_json::Value>>,
-    total_count: i64,
+struct TruncatePreview {
+    table_name: String,
+    row_count: i64,
+    would_cascade: Vec<String>,
 }
 
 #[tauri::command]
-fn get_table_data(
+fn truncate_table(
     app_handle: AppHandle,
     state: State<AppState>,
     table_name: String,
-    limit: i64,
-    offset: i64,
-) -> Result<TableDataResponse, String> {
+    dry_run: Option<bool>,
+) -> Result<Option<TruncatePreview>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-
+    
+    // 안전을 위해 테이블 이름 검증 (SQL Injection 방지 - 간단히 공백/특수문자 체크)
     if table_name.contains(' ') || table_name.contains(';') {
         return Err("유효하지 않은 테이블 이름입니다.".to_string());
     }
 
-    // 컬럼명 조회
-    let stmt = conn
-        .prepare(&format!("SELECT * FROM {} LIMIT 0", table_name))
-        .map_err(|e| e.to_string())?;
-    let columns: Vec<String> = stmt
-        .column_names()
-        .into_iter()
-        .map(|s| s.to_string())
-        .collect();
-
-    // 전체 개수 조회
-    let total_count: i64 = conn
-        .query_row(&format!("SELECT COUNT(*) FROM {}", table_name), [], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
+    // 실제로 삭제하지 않고 영향 범위만 조회
+    if dry_run.unwrap_or(false) {
+        let row_count: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table_name), [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        
+        // 대상 테이블을 ON DELETE CASCADE로 참조하는 자식 테이블 목록
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT m.name
+                 FROM sqlite_master m, pragma_foreign_key_list(m.name) f
+                 WHERE m.type = 'table' AND f.\"table\" = ?1 AND f.on_delete = 'CASCADE'
+                 ORDER BY m.name",
+            )
+            .map_err(|e| e.to_string())?;
+        let would_cascade = stmt
+            .query_map([&table_name], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        
+        return Ok(Some(TruncatePreview {
+            table_name,
+            row_count,
+            would_cascade,
+        }));
+    }
 
-    // 데이터 조회
-    // JSON Value로 변환하기 위해 rusqlite의 dynamic value 처리가 필요함.
-    // 여기서는 간단히 serde_json::Value로 변환하는 로직 구현
-    let mut stmt = conn
-        .prepare(&format!("SELECT * FROM {} LIMIT ?1 OFFSET ?2", table_name))
+    let deleted = conn
+        .execute(&format!("DELETE FROM {}", table_name), [])
         .map_err(|e| e.to_string())?;
+    tracing::info!(table = %table_name, deleted, "table truncated");
+        
+    // VACUUM은 선택사항이지만 용량 확보를 위해 실행 가능 (오래 걸릴 수 있음)
+    // conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
     
-    let column_count = columns.len();
-    let rows = stmt
-        .query_map(rusqlite::params![limit, offset], |row| {
-            let mut record = Vec::new();
-            for i in 0..column_count {
-                let val = row.get_ref(i)?;
-                let json_val = match val {
+    Ok(None)
+}
+
+// reset_data에서 테이블별 삭제된 행 수
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableResetCount {
+    table_name: String,
+    deleted: usize,
+}
+
+// 데이터 초기화 대상 테이블 (자식 테이블부터 삭제하도록 정렬)
+const RESET_DATA_TABLES: [&str; 8] = [
+    "tbl_naver_payment_item",
+    "tbl_naver_payment",
+    "tbl_coupang_payment_item",
+    "tbl_coupang_payment",
+    "tbl_product_category",
+    "tbl_product_tag",
+    "tbl_product_meta",
+    "tbl_idempotency_key",
+];
+const RESET_USER_TABLES: [&str; 2] = ["tbl_credential", "tbl_user"];
+// 카테고리/가맹점 규칙은 가계부 항목이 이름으로 참조하므로 가계부와 함께만 삭제
+const RESET_LEDGER_TABLES: [&str; 8] = [
+    "tbl_ledger_tag",
+    "tbl_ledger_history",
+    "tbl_ledger_rule",
+    "tbl_auth_attempt",
+    "tbl_ledger_entry",
+    "tbl_ledger_account",
+    "tbl_merchant_rule",
+    "tbl_category",
+];
+
+// 결제/상품 메타데이터를 비우고 기본 카테고리를 다시 생성 (계정/가계부는 선택적으로 유지, 설정은 항상 유지)
+// 유지한 가계부 항목의 결제 연결(source_payment_id)은 남지만 결제 원본은 삭제됨
+// 가계부까지 비울 때는 패스워드가 있는 계정이 모두 잠금 해제되어 있어야 함
+#[tauri::command]
+async fn reset_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    keep_users: bool,
+    keep_ledger: bool,
+) -> Result<Vec<TableResetCount>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    
+    if !keep_ledger {
+        let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+        check_and_reset_expired_passwords(&conn)?;
+        let mut stmt = conn
+            .prepare("SELECT id FROM tbl_ledger_account WHERE password_hash IS NOT NULL")
+            .map_err(|e| e.to_string())?;
+        let protected_ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        for account_id in protected_ids {
+            if !check_ledger_unlock(&conn, &state, &account_id, false)? {
+                return Err("잠긴 가계부 계정이 있습니다. 모든 계정을 잠금 해제한 뒤 다시 시도해주세요.".to_string());
+            }
+        }
+    }
+    
+    let counts = tauri::async_runtime::spawn_blocking(move || {
+        let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        
+        let mut tables: Vec<&str> = RESET_DATA_TABLES.to_vec();
+        if !keep_users {
+            tables.extend(RESET_USER_TABLES);
+        }
+        if !keep_ledger {
+            tables.extend(RESET_LEDGER_TABLES);
+        }
+        
+        let mut counts = Vec::new();
+        for table_name in tables {
+            let deleted = tx
+                .execute(&format!("DELETE FROM {}", table_name), [])
+                .map_err(|e| e.to_string())?;
+            counts.push(TableResetCount {
+                table_name: table_name.to_string(),
+                deleted,
+            });
+        }
+        seed_default_categories(&tx)?;
+        tx.commit().map_err(|e| e.to_string())?;
+        
+        tracing::info!(keep_users, keep_ledger, "data reset");
+        Ok::<_, String>(counts)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    
+    if !keep_ledger {
+        state
+            .unlocked_ledger_accounts
+            .lock()
+            .expect("failed to lock unlocked_ledger_accounts")
+            .clear();
+    }
+    Ok(counts)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableDataResponse {
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+    total_count: i64,
+    limit: i64,
+}
+
+#[tauri::command]
+fn get_table_data(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    table_name: String,
+    limit: i64,
+    offset: i64,
+    sort_column: Option<String>,
+    sort_direction: Option<String>,
+) -> Result<TableDataResponse, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+
+    if table_name.contains(' ') || table_name.contains(';') {
+        return Err("유효하지 않은 테이블 이름입니다.".to_string());
+    }
+
+    let limit = clamp_limit(Some(limit), 100, MAX_LIST_LIMIT);
+
+    // 컬럼명 조회
+    let stmt = conn
+        .prepare(&format!("SELECT * FROM {} LIMIT 0", table_name))
+        .map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    // 전체 개수 조회
+    let total_count: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table_name), [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    // 정렬 조건 (컬럼명은 실제 컬럼 목록에 있는 경우에만 허용)
+    let order_by = match (sort_column, sort_direction) {
+        (None, None) => String::new(),
+        (column, direction) => {
+            let direction = match direction.as_deref().map(|d| d.to_ascii_uppercase()) {
+                None => "ASC".to_string(),
+                Some(d) if d == "ASC" || d == "DESC" => d,
+                Some(d) => return Err(format!("유효하지 않은 정렬 방향입니다: {}", d)),
+            };
+            let column = column.ok_or_else(|| "정렬할 컬럼을 지정해주세요.".to_string())?;
+            if !columns.contains(&column) {
+                return Err(format!("존재하지 않는 컬럼입니다: {}", column));
+            }
+            format!(" ORDER BY \"{}\" {}", column, direction)
+        }
+    };
+
+    // 데이터 조회
+    // JSON Value로 변환하기 위해 rusqlite의 dynamic value 처리가 필요함.
+    // 여기서는 간단히 serde_json::Value로 변환하는 로직 구현
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {}{} LIMIT ?1 OFFSET ?2", table_name, order_by))
+        .map_err(|e| e.to_string())?;
+    
+    let column_count = columns.len();
+    let rows = stmt
+        .query_map(rusqlite::params![limit, offset], |row| {
+            let mut record = Vec::new();
+            for i in 0..column_count {
+                let val = row.get_ref(i)?;
+                let json_val = match val {
                     rusqlite::types::ValueRef::Null => serde_json::Value::Null,
                     rusqlite::types::ValueRef::Integer(i) => json!(i),
                     rusqlite::types::ValueRef::Real(f) => json!(f),
@@ -682,6 +1320,7 @@ fn get_table_data(
         columns,
         rows: result_rows,
         total_count,
+        limit,
     })
 }
 
@@ -691,7 +1330,7 @@ fn get_db_status(app_handle: AppHandle, state: State<AppState>) -> Result<DbStat
         // DB가 존재하면 마이그레이션 실행하여 스키마 최신화
         if path.exists() {
             if let Err(e) = run_migrations(&path) {
-                eprintln!("Migration failed: {}", e);
+                tracing::error!(error = %e, "migration failed");
                 // 마이그레이션 실패해도 상태는 반환 (에러 로그만 출력)
             }
         }
@@ -707,6 +1346,365 @@ fn get_db_status(app_handle: AppHandle, state: State<AppState>) -> Result<DbStat
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadonlyDbStatus {
+    #[serde(flatten)]
+    status: DbStatus,
+    schema_version: Option<i64>,
+    migration_pending: bool,
+}
+
+// 마이그레이션 없이 DB 상태만 조회 (읽기 전용으로 열어 스키마 누락 여부를 migration_pending으로 보고)
+#[tauri::command]
+fn get_db_status_readonly(app_handle: AppHandle, state: State<AppState>) -> Result<ReadonlyDbStatus, String> {
+    let Some(path) = configured_db_path(&app_handle, &state)? else {
+        return Ok(ReadonlyDbStatus {
+            status: DbStatus {
+                configured: false,
+                path: String::new(),
+                exists: false,
+                size_bytes: None,
+                tables: Vec::new(),
+            },
+            schema_version: None,
+            migration_pending: false,
+        });
+    };
+    if !path.exists() {
+        return Ok(ReadonlyDbStatus {
+            status: build_status(&path, true)?,
+            schema_version: None,
+            migration_pending: false,
+        });
+    }
+    
+    readonly_db_status(&path)
+}
+
+// 존재하는 DB 파일을 읽기 전용으로 열어 상태 조회 (마이그레이션/파일 생성 없음)
+fn readonly_db_status(path: &Path) -> Result<ReadonlyDbStatus, String> {
+    let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+    let schema_version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let migration_pending = schema_version < SCHEMA_VERSION || !schema_report(&conn)?.ok;
+    
+    Ok(ReadonlyDbStatus {
+        status: build_status(path, true)?,
+        schema_version: Some(schema_version),
+        migration_pending,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthStatus {
+    db_configured: bool,
+    db_path: Option<String>,
+    db_exists: bool,
+    // PRAGMA user_version (마이그레이션 완료 시 SCHEMA_VERSION, 이전 버전에서 만든 DB는 0)
+    schema_version: Option<i64>,
+    integrity_ok: Option<bool>,
+    user_count: Option<i64>,
+    journal_mode: Option<String>,
+    foreign_keys_on: Option<bool>,
+    db_error: Option<String>,
+    app_data_dir: Option<String>,
+    config_path: Option<String>,
+    config_exists: bool,
+    config_parsed: bool,
+    config_error: Option<String>,
+}
+
+// 진단 화면용 상태 요약 (개별 항목 실패는 에러 필드로 보고하고 명령 자체는 실패하지 않음)
+#[tauri::command]
+fn get_health(app_handle: AppHandle, state: State<AppState>) -> Result<HealthStatus, String> {
+    let mut health = HealthStatus {
+        db_configured: false,
+        db_path: None,
+        db_exists: false,
+        schema_version: None,
+        integrity_ok: None,
+        user_count: None,
+        journal_mode: None,
+        foreign_keys_on: None,
+        db_error: None,
+        app_data_dir: app_handle
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().to_string()),
+        config_path: None,
+        config_exists: false,
+        config_parsed: false,
+        config_error: None,
+    };
+    
+    match config_file(&app_handle) {
+        Ok(file) => {
+            health.config_path = Some(file.to_string_lossy().to_string());
+            health.config_exists = file.exists();
+            if health.config_exists {
+                match fs::read_to_string(&file)
+                    .map_err(|e| e.to_string())
+                    .and_then(|data| serde_json::from_str::<Value>(&data).map_err(|e| e.to_string()))
+                {
+                    Ok(_) => health.config_parsed = true,
+                    Err(e) => health.config_error = Some(e),
+                }
+            }
+        }
+        Err(e) => health.config_error = Some(e),
+    }
+    
+    // config가 깨져 있어도 진단은 계속되어야 하므로 configured_db_path 대신 직접 조회
+    let db_path = state
+        .db_path
+        .lock()
+        .expect("failed to lock db_path")
+        .clone()
+        .or_else(|| load_config_path(&app_handle).ok().flatten());
+    let path = match db_path {
+        Some(path) => path,
+        None => return Ok(health),
+    };
+    health.db_configured = true;
+    health.db_path = Some(path.to_string_lossy().to_string());
+    health.db_exists = path.exists();
+    if !health.db_exists {
+        return Ok(health);
+    }
+    
+    let result = (|| -> Result<(), String> {
+        let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+        health.schema_version = Some(
+            conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+                .map_err(|e| e.to_string())?,
+        );
+        health.journal_mode = Some(
+            conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))
+                .map_err(|e| e.to_string())?,
+        );
+        let foreign_keys: i64 = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        health.foreign_keys_on = Some(foreign_keys == 1);
+        // 전체 integrity_check 대신 빠른 quick_check 사용
+        let quick_check: String = conn
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        health.integrity_ok = Some(quick_check == "ok");
+        health.user_count = Some(
+            conn.query_row("SELECT COUNT(*) FROM tbl_user", [], |row| row.get(0))
+                .map_err(|e| e.to_string())?,
+        );
+        Ok(())
+    })();
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "health check failed");
+        health.db_error = Some(e);
+    }
+    
+    Ok(health)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MissingColumns {
+    table: String,
+    columns: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaReport {
+    // 누락 항목이 하나도 없으면 true
+    ok: bool,
+    missing_tables: Vec<String>,
+    // 테이블은 있지만 컬럼이 빠진 경우 (누락 테이블은 제외)
+    missing_columns: Vec<MissingColumns>,
+    missing_indexes: Vec<String>,
+}
+
+// (테이블별 컬럼 목록, 인덱스 이름 목록)
+type SchemaLayout = (Vec<(String, Vec<String>)>, Vec<String>);
+
+// 사용자 테이블별 컬럼 목록과 인덱스 이름 조회
+fn load_schema_layout(conn: &Connection) -> Result<SchemaLayout, String> {
+    let table_names = {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        let mut names = Vec::new();
+        for row in rows {
+            names.push(row.map_err(|e| e.to_string())?);
+        }
+        names
+    };
+    
+    let mut tables = Vec::new();
+    let mut column_stmt = conn
+        .prepare("SELECT name FROM pragma_table_info(?1) ORDER BY cid")
+        .map_err(|e| e.to_string())?;
+    for table in table_names {
+        let rows = column_stmt
+            .query_map([&table], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        let mut columns = Vec::new();
+        for row in rows {
+            columns.push(row.map_err(|e| e.to_string())?);
+        }
+        tables.push((table, columns));
+    }
+    
+    let mut index_stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='index' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let rows = index_stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    let mut indexes = Vec::new();
+    for row in rows {
+        indexes.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok((tables, indexes))
+}
+
+// 현재 DB 스키마를 코드 기준 스키마(메모리 DB에 마이그레이션 적용)와 비교해 누락된 테이블/컬럼/인덱스 보고
+// 마이그레이션의 ALTER 오류는 무시되므로 반쯤 적용된 DB를 찾는 용도 (load_existing_db로 마이그레이션 재실행 가능)
+#[tauri::command]
+fn verify_schema(app_handle: AppHandle, state: State<AppState>) -> Result<SchemaReport, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    schema_report(&conn)
+}
+
+fn schema_report(conn: &Connection) -> Result<SchemaReport, String> {
+    let expected_conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    apply_migrations(&expected_conn)?;
+    
+    let (expected_tables, expected_indexes) = load_schema_layout(&expected_conn)?;
+    let (live_tables, live_indexes) = load_schema_layout(conn)?;
+    
+    let mut missing_tables = Vec::new();
+    let mut missing_columns = Vec::new();
+    for (table, expected_columns) in expected_tables {
+        match live_tables.iter().find(|(name, _)| *name == table) {
+            None => missing_tables.push(table),
+            Some((_, live_columns)) => {
+                let columns: Vec<String> = expected_columns
+                    .into_iter()
+                    .filter(|column| !live_columns.contains(column))
+                    .collect();
+                if !columns.is_empty() {
+                    missing_columns.push(MissingColumns { table, columns });
+                }
+            }
+        }
+    }
+    let missing_indexes: Vec<String> = expected_indexes
+        .into_iter()
+        .filter(|index| !live_indexes.contains(index))
+        .collect();
+    
+    Ok(SchemaReport {
+        ok: missing_tables.is_empty() && missing_columns.is_empty() && missing_indexes.is_empty(),
+        missing_tables,
+        missing_columns,
+        missing_indexes,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigNormalization {
+    // notConfigured | unchanged | normalized | relocateNeeded
+    status: String,
+    original_path: Option<String>,
+    normalized_path: Option<String>,
+}
+
+// 저장된 dbPath를 절대 경로/현재 OS 구분자로 정리해 config.json에 다시 기록
+// 상대 경로는 앱 데이터 폴더 기준, 파일이 없으면 config를 건드리지 않고 relocateNeeded 반환
+#[tauri::command]
+fn normalize_config(app_handle: AppHandle, state: State<AppState>) -> Result<ConfigNormalization, String> {
+    let Some(original) = load_config_path(&app_handle)? else {
+        return Ok(ConfigNormalization {
+            status: "notConfigured".to_string(),
+            original_path: None,
+            normalized_path: None,
+        });
+    };
+    let original_str = original.to_string_lossy().to_string();
+    
+    // 다른 OS에서 저장된 구분자(/, \)를 현재 OS 구분자로 통일
+    let native: PathBuf = original_str
+        .split(['/', '\\'])
+        .enumerate()
+        .filter(|(i, part)| *i == 0 || !part.is_empty())
+        .map(|(_, part)| if part.is_empty() { std::path::MAIN_SEPARATOR_STR } else { part })
+        .collect();
+    let candidate = if native.is_absolute() {
+        native
+    } else {
+        app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join(native)
+    };
+    
+    if !candidate.exists() {
+        tracing::warn!(path = %candidate.display(), "configured db path no longer exists");
+        return Ok(ConfigNormalization {
+            status: "relocateNeeded".to_string(),
+            original_path: Some(original_str),
+            normalized_path: Some(candidate.to_string_lossy().to_string()),
+        });
+    }
+    
+    let canonical = fs::canonicalize(&candidate).map_err(|e| e.to_string())?;
+    // Windows의 canonicalize는 \\?\ 접두어를 붙이므로 일반 경로로 되돌림
+    let canonical = match canonical.to_string_lossy().strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => canonical,
+    };
+    let normalized_str = canonical.to_string_lossy().to_string();
+    if normalized_str == original_str {
+        return Ok(ConfigNormalization {
+            status: "unchanged".to_string(),
+            original_path: Some(original_str),
+            normalized_path: Some(normalized_str),
+        });
+    }
+    
+    save_config_path(&app_handle, &canonical)?;
+    {
+        // 메모리 상태가 예전 경로를 가리키고 있으면 함께 갱신
+        let mut guard = state.db_path.lock().expect("failed to lock db_path");
+        if guard.is_none() || guard.as_ref() == Some(&original) {
+            *guard = Some(canonical);
+        }
+    }
+    tracing::info!(from = %original_str, to = %normalized_str, "config db path normalized");
+    
+    Ok(ConfigNormalization {
+        status: "normalized".to_string(),
+        original_path: Some(original_str),
+        normalized_path: Some(normalized_str),
+    })
+}
+
 // 로그아웃: config에서 DB 경로 제거 및 메모리 상태 초기화
 #[tauri::command]
 fn logout(app_handle: AppHandle, state: State<AppState>) -> Result<(), String> {
@@ -715,6 +1713,11 @@ fn logout(app_handle: AppHandle, state: State<AppState>) -> Result<(), String> {
         let mut guard = state.db_path.lock().expect("failed to lock db_path");
         *guard = None;
     }
+    state
+        .unlocked_ledger_accounts
+        .lock()
+        .expect("failed to lock unlocked_ledger_accounts")
+        .clear();
     
     // config 파일에서 dbPath 제거
     let file = config_file(&app_handle)?;
@@ -738,6 +1741,7 @@ fn init_db(
     } else {
         default_db_path(&app_handle)?
     };
+    tracing::info!(path = %target_path.display(), "initializing database");
     run_migrations(&target_path)?;
     save_config_path(&app_handle, &target_path)?;
     set_db_path(&state, target_path.clone());
@@ -750,6 +1754,7 @@ fn load_existing_db(app_handle: AppHandle, state: State<AppState>, path: String)
     if !path_buf.exists() {
         return Err("지정한 경로에 DB 파일이 없습니다.".into());
     }
+    tracing::info!(path = %path_buf.display(), "loading existing database");
     run_migrations(&path_buf)?;
     save_config_path(&app_handle, &path_buf)?;
     set_db_path(&state, path_buf.clone());
@@ -826,6 +1831,9 @@ struct NaverPayment {
     merchant_country_name: Option<String>,
     application_completed: Option<bool>,
     items: Vec<NaverPaymentItem>,
+    // 수집 원본 JSON (지정한 경우에만 저장, 조회 응답에는 포함하지 않음)
+    #[serde(default, skip_serializing)]
+    raw: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, serde::Deserialize)]
@@ -881,6 +1889,9 @@ struct CoupangPayment {
     wow_instant_discount: Option<i64>,
     reward_cash_amount: Option<i64>,
     items: Vec<CoupangPaymentItem>,
+    // 수집 원본 JSON (지정한 경우에만 저장, 조회 응답에는 포함하지 않음)
+    #[serde(default, skip_serializing)]
+    raw: Option<serde_json::Value>,
 }
 
 #[derive(Serialize)]
@@ -897,6 +1908,18 @@ struct CoupangLatestPayment {
     ordered_at: String,
 }
 
+// 사용자별 최근 결제 (네이버/쿠팡 통합)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserLatestPayment {
+    user_id: String,
+    user_alias: String,
+    provider: String,
+    last_payment_date: String,
+    last_payment_id: String,
+    last_payment_amount: i64,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct User {
@@ -939,7 +1962,7 @@ fn list_users(app_handle: AppHandle, state: State<AppState>) -> Result<UserListR
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     let mut stmt = conn
-        .prepare("SELECT id, provider, alias, curl, created_at, updated_at FROM tbl_user ORDER BY created_at DESC")
+        .prepare("SELECT id, provider, alias, curl, created_at, updated_at FROM tbl_user ORDER BY created_at DESC, id")
         .map_err(|e| e.to_string())?;
     let rows = stmt
         .query_map([], |row| {
@@ -960,37 +1983,298 @@ fn list_users(app_handle: AppHandle, state: State<AppState>) -> Result<UserListR
     Ok(UserListResponse { users })
 }
 
-#[tauri::command]
-fn save_account(
-    app_handle: AppHandle,
-    state: State<AppState>,
-    provider: String,
-    alias: String,
-    curl: String,
+// 계정 cURL에서 자격 증명으로 저장할 헤더
+const CREDENTIAL_HEADERS: [&str; 2] = ["Cookie", "Authorization"];
+
+struct ParsedCurl {
+    url: Option<String>,
+    method: String,
+    // 헤더 이름은 normalize_header_name으로 정규화됨
     headers: HashMap<String, String>,
-) -> Result<String, String> {
-    let path = configured_db_path(&app_handle, &state)?
-        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
-    if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
-    }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    let user_id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    
-    conn.execute(
-        "INSERT INTO tbl_user (id, provider, alias, curl, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![user_id, provider, alias, curl, now, now],
-    )
-    .map_err(|e| e.to_string())?;
-    
-    // 헤더 정보를 tbl_credential에 저장
-    for (key, value) in headers {
-        let cred_id = Uuid::new_v4().to_string();
-        conn.execute(
-            "INSERT OR REPLACE INTO tbl_credential (id, user_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![cred_id, user_id, key, value, now],
-        )
+    body: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CurlValidation {
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    // 저장은 가능하지만 확인이 필요한 항목
+    warnings: Vec<String>,
+}
+
+// 헤더 이름을 Content-Type 형태로 정규화
+fn normalize_header_name(name: &str) -> String {
+    name.trim()
+        .split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// 셸 인용 규칙('...', "...", $'...', 백슬래시 줄바꿈)에 따라 cURL 명령을 인자 단위로 분리
+fn split_curl_args(curl: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = curl.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err("닫히지 않은 작은따옴표가 있습니다.".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(ch) => current.push(ch),
+                            None => return Err("닫히지 않은 작은따옴표가 있습니다.".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("닫히지 않은 작은따옴표가 있습니다.".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err("닫히지 않은 큰따옴표가 있습니다.".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("닫히지 않은 큰따옴표가 있습니다.".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // 줄 이어쓰기
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some('\r') => {}
+                Some(ch) => {
+                    in_arg = true;
+                    current.push(ch);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+// cURL 명령에서 URL, 메서드, 헤더, 바디 추출
+fn parse_curl(curl: &str) -> Result<ParsedCurl, String> {
+    let mut args = split_curl_args(curl)?.into_iter();
+    if args.next().as_deref() != Some("curl") {
+        return Err("cURL 명령은 curl로 시작해야 합니다.".to_string());
+    }
+    
+    let mut url = None;
+    let mut method = None;
+    let mut headers = HashMap::new();
+    let mut body_parts = Vec::new();
+    
+    while let Some(arg) = args.next() {
+        // --option=value 형식 분리
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut take_value = |flag: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} 옵션에 값이 없습니다.", flag))
+        };
+        
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(take_value(&flag)?.to_ascii_uppercase()),
+            "-H" | "--header" => {
+                let header = take_value(&flag)?;
+                if let Some((name, value)) = header.split_once(':') {
+                    let value = value.trim();
+                    if !name.trim().is_empty() && !value.is_empty() {
+                        headers.insert(normalize_header_name(name), value.to_string());
+                    }
+                }
+            }
+            "-b" | "--cookie" => {
+                // '='가 없으면 쿠키 파일 경로이므로 무시
+                let cookie = take_value(&flag)?;
+                if cookie.contains('=') {
+                    headers.insert("Cookie".to_string(), cookie);
+                }
+            }
+            "-A" | "--user-agent" => {
+                headers.insert("User-Agent".to_string(), take_value(&flag)?);
+            }
+            "-e" | "--referer" => {
+                headers.insert("Referer".to_string(), take_value(&flag)?);
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" | "--data-urlencode" => {
+                body_parts.push(take_value(&flag)?);
+            }
+            "--url" => url = Some(take_value(&flag)?),
+            // 값을 받지만 요청 내용과 무관한 옵션
+            "-o" | "--output" | "-m" | "--max-time" | "--connect-timeout" | "-x" | "--proxy" | "-u"
+            | "--user" | "-w" | "--write-out" | "--retry" | "-T" | "--upload-file" | "-F" | "--form"
+            | "--cacert" | "--cert" | "--key" | "-c" | "--cookie-jar" | "--resolve" | "--max-redirs"
+            | "-r" | "--range" | "-K" | "--config" => {
+                take_value(&flag)?;
+            }
+            _ if flag.starts_with("-X") && flag.len() > 2 => method = Some(flag[2..].to_ascii_uppercase()),
+            _ if flag.starts_with('-') => {}
+            _ => {
+                if url.is_none() {
+                    url = Some(arg);
+                }
+            }
+        }
+    }
+    
+    let body = if body_parts.is_empty() {
+        None
+    } else {
+        Some(body_parts.join("&"))
+    };
+    let method = method.unwrap_or_else(|| if body.is_some() { "POST" } else { "GET" }.to_string());
+    Ok(ParsedCurl {
+        url,
+        method,
+        headers,
+        body,
+    })
+}
+
+// cURL 검증: URL이 없거나 http(s) 외 스킴이면 오류, 의심스러운 부분은 경고로 반환
+fn check_curl(curl: &str) -> Result<CurlValidation, String> {
+    let parsed = parse_curl(curl)?;
+    let url = parsed
+        .url
+        .ok_or_else(|| "cURL에 URL이 없습니다.".to_string())?;
+    
+    let mut warnings = Vec::new();
+    match url.split_once("://") {
+        Some((scheme, _)) => match scheme.to_ascii_lowercase().as_str() {
+            "https" => {}
+            "http" => warnings.push("암호화되지 않은 http URL입니다.".to_string()),
+            other => return Err(format!("지원하지 않는 URL 스킴입니다: {}", other)),
+        },
+        None => warnings.push("URL에 스킴이 없어 http로 간주됩니다.".to_string()),
+    }
+    if !parsed.headers.contains_key("User-Agent") {
+        warnings.push("User-Agent 헤더가 없습니다.".to_string());
+    }
+    if !CREDENTIAL_HEADERS.iter().any(|name| parsed.headers.contains_key(*name)) {
+        warnings.push("Cookie/Authorization 헤더가 없어 인증이 필요한 요청은 실패할 수 있습니다.".to_string());
+    }
+    
+    Ok(CurlValidation {
+        url,
+        method: parsed.method,
+        headers: parsed.headers,
+        body: parsed.body,
+        warnings,
+    })
+}
+
+// 계정 저장 전에 cURL을 미리 검증 (경고 확인용)
+#[tauri::command]
+fn validate_curl(curl: String) -> Result<CurlValidation, String> {
+    check_curl(&curl)
+}
+
+#[tauri::command]
+fn save_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: String,
+    alias: String,
+    curl: String,
+    headers: HashMap<String, String>,
+) -> Result<String, String> {
+    let validation = check_curl(&curl)?;
+    
+    // 헤더 이름 정규화, 호출자가 인증 헤더를 넘기지 않았으면 cURL에서 채움
+    let mut headers: HashMap<String, String> = headers
+        .into_iter()
+        .map(|(key, value)| (normalize_header_name(&key), value))
+        .collect();
+    for name in CREDENTIAL_HEADERS {
+        if !headers.contains_key(name) {
+            if let Some(value) = validation.headers.get(name) {
+                headers.insert(name.to_string(), value.clone());
+            }
+        }
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let user_id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    
+    conn.execute(
+        "INSERT INTO tbl_user (id, provider, alias, curl, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![user_id, provider, alias, curl, now, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 헤더 정보를 tbl_credential에 저장
+    for (key, value) in headers {
+        let cred_id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT OR REPLACE INTO tbl_credential (id, user_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![cred_id, user_id, key, value, now],
+        )
         .map_err(|e| e.to_string())?;
     }
     
@@ -1085,6 +2369,81 @@ fn get_user_credentials(
     Ok(credentials)
 }
 
+// 사용자 행이 없는 인증 정보 (값은 포함하지 않음)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrphanedCredentials {
+    count: i64,
+    credential_ids: Vec<String>,
+    user_ids: Vec<String>,
+}
+
+fn collect_orphaned_credentials(conn: &Connection) -> Result<OrphanedCredentials, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, c.user_id FROM tbl_credential c
+             WHERE NOT EXISTS (SELECT 1 FROM tbl_user u WHERE u.id = c.user_id)
+             ORDER BY c.user_id, c.key",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?;
+    
+    let mut orphaned = OrphanedCredentials { count: 0, credential_ids: Vec::new(), user_ids: Vec::new() };
+    for row in rows {
+        let (credential_id, user_id) = row.map_err(|e| e.to_string())?;
+        orphaned.credential_ids.push(credential_id);
+        if !orphaned.user_ids.contains(&user_id) {
+            orphaned.user_ids.push(user_id);
+        }
+    }
+    orphaned.count = orphaned.credential_ids.len() as i64;
+    Ok(orphaned)
+}
+
+// 외래키가 꺼진 상태에서 사용자가 삭제되어 남은 인증 정보 조회
+#[tauri::command]
+fn find_orphaned_credentials(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<OrphanedCredentials, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(OrphanedCredentials { count: 0, credential_ids: Vec::new(), user_ids: Vec::new() });
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    collect_orphaned_credentials(&conn)
+}
+
+// 고아 인증 정보 삭제 후 삭제된 항목 반환
+#[tauri::command]
+fn purge_orphaned_credentials(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<OrphanedCredentials, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    let orphaned = collect_orphaned_credentials(&tx)?;
+    tx.execute(
+        "DELETE FROM tbl_credential
+         WHERE NOT EXISTS (SELECT 1 FROM tbl_user u WHERE u.id = tbl_credential.user_id)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    tracing::info!(deleted = orphaned.count, "orphaned credentials purged");
+    Ok(orphaned)
+}
+
 #[tauri::command]
 fn update_account_credentials(
     app_handle: AppHandle,
@@ -1128,13 +2487,109 @@ fn update_account_credentials(
     Ok(())
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldChange {
+    field: String,
+    old: Value,
+    new: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaymentItemChange {
+    line_no: i64,
+    product_name: Option<String>,
+    changes: Vec<FieldChange>,
+}
+
+// 결제 재저장 시 이전 저장본과 비교한 변경 내역
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaymentChangeSet {
+    is_new: bool,
+    header_changes: Vec<FieldChange>,
+    items_added: Vec<PaymentItemChange>,
+    items_removed: Vec<PaymentItemChange>,
+    items_changed: Vec<PaymentItemChange>,
+}
+
+// JSON 객체 간 필드 비교 (skip에 포함된 필드는 제외)
+fn diff_json_fields(old: &Value, new: &Value, skip: &[&str]) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if let Some(new_fields) = new.as_object() {
+        for (field, new_value) in new_fields {
+            if skip.contains(&field.as_str()) {
+                continue;
+            }
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            if &old_value != new_value {
+                changes.push(FieldChange {
+                    field: field.clone(),
+                    old: old_value,
+                    new: new_value.clone(),
+                });
+            }
+        }
+    }
+    changes
+}
+
+// 직렬화된 결제(camelCase JSON)를 비교하여 헤더 필드와 상세 항목(lineNo 기준) 변경 내역 생성
+fn diff_payment_json(old: Option<&Value>, new: &Value) -> PaymentChangeSet {
+    let items_of = |payment: Option<&Value>| -> Vec<Value> {
+        payment
+            .and_then(|p| p.get("items"))
+            .and_then(|items| items.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let item_ref = |item: &Value, changes: Vec<FieldChange>| PaymentItemChange {
+        line_no: item.get("lineNo").and_then(|v| v.as_i64()).unwrap_or_default(),
+        product_name: item.get("productName").and_then(|v| v.as_str()).map(|v| v.to_string()),
+        changes,
+    };
+    
+    let old_items = items_of(old);
+    let new_items = items_of(Some(new));
+    let mut change_set = PaymentChangeSet {
+        is_new: old.is_none(),
+        header_changes: old
+            .map(|old| diff_json_fields(old, new, &["items"]))
+            .unwrap_or_default(),
+        items_added: Vec::new(),
+        items_removed: Vec::new(),
+        items_changed: Vec::new(),
+    };
+    
+    for new_item in &new_items {
+        match old_items.iter().find(|old_item| old_item.get("lineNo") == new_item.get("lineNo")) {
+            Some(old_item) => {
+                let changes = diff_json_fields(old_item, new_item, &["id"]);
+                if !changes.is_empty() {
+                    change_set.items_changed.push(item_ref(new_item, changes));
+                }
+            }
+            None => change_set.items_added.push(item_ref(new_item, Vec::new())),
+        }
+    }
+    for old_item in &old_items {
+        if !new_items.iter().any(|new_item| new_item.get("lineNo") == old_item.get("lineNo")) {
+            change_set.items_removed.push(item_ref(old_item, Vec::new()));
+        }
+    }
+    
+    change_set
+}
+
 #[tauri::command]
 fn save_naver_payment(
     app_handle: AppHandle,
     state: State<AppState>,
     user_id: String,
     payment: NaverPayment,
-) -> Result<(), String> {
+    report_changes: Option<bool>,
+) -> Result<Option<PaymentChangeSet>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -1143,6 +2598,18 @@ fn save_naver_payment(
     let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
 
+    // 요청 시 덮어쓰기 전에 이전 저장본과 비교
+    let change_set = if report_changes.unwrap_or(false) {
+        let previous = load_naver_payment_detail(&tx, &user_id, &payment.pay_id)?
+            .map(|detail| serde_json::to_value(&detail.payment))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let current = serde_json::to_value(&payment).map_err(|e| e.to_string())?;
+        Some(diff_payment_json(previous.as_ref(), &current))
+    } else {
+        None
+    };
+
     {
         let now = Utc::now().to_rfc3339();
         
@@ -1159,11 +2626,11 @@ fn save_naver_payment(
                 benefit_type, has_plus_membership, benefit_waiting_period, benefit_expected_amount,
                 benefit_amount, is_membership, is_branch, is_last_subscription_round,
                 is_cafe_safe_payment, merchant_country_code, merchant_country_name,
-                application_completed, created_at, updated_at
+                application_completed, created_at, updated_at, raw_json
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
                 ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34,
-                ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45, ?46, ?47
+                ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45, ?46, ?47, ?48
             )
             ON CONFLICT(user_id, pay_id) DO UPDATE SET
                 external_id = excluded.external_id,
@@ -1173,7 +2640,8 @@ fn save_naver_payment(
                 status_color = excluded.status_color,
                 updated_at = excluded.updated_at,
                 merchant_name = excluded.merchant_name,
-                total_amount = excluded.total_amount",
+                total_amount = excluded.total_amount,
+                raw_json = COALESCE(excluded.raw_json, raw_json)",
             rusqlite::params![
                 user_id, payment.pay_id, payment.external_id, payment.service_type, payment.status_code,
                 payment.status_text, payment.status_color, payment.paid_at, payment.purchaser_name,
@@ -1189,7 +2657,8 @@ fn save_naver_payment(
                 payment.benefit_amount, payment.is_membership, payment.is_branch,
                 payment.is_last_subscription_round, payment.is_cafe_safe_payment,
                 payment.merchant_country_code, payment.merchant_country_name,
-                payment.application_completed, now, now
+                payment.application_completed, now, now,
+                payment.raw.as_ref().map(|raw| raw.to_string())
             ],
         ).map_err(|e| e.to_string())?;
 
@@ -1228,7 +2697,7 @@ fn save_naver_payment(
     }
 
     tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(change_set)
 }
 
 #[derive(Serialize)]
@@ -1251,13 +2720,124 @@ struct NaverPaymentListItem {
     items: Vec<NaverPaymentItem>,
 }
 
+// 결제의 상세 항목 조회
+// SELECT id, line_no, product_name, image_url, info_url, quantity,
+//        unit_price, line_amount, rest_amount, memo 순서의 행을 NaverPaymentItem으로 변환
+fn naver_payment_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<NaverPaymentItem> {
+    Ok(NaverPaymentItem {
+        id: row.get(0)?,
+        line_no: row.get(1)?,
+        product_name: row.get(2)?,
+        image_url: row.get(3)?,
+        info_url: row.get(4)?,
+        quantity: row.get(5)?,
+        unit_price: row.get(6)?,
+        line_amount: row.get(7)?,
+        rest_amount: row.get(8)?,
+        memo: row.get(9)?,
+    })
+}
+
+fn load_naver_payment_items(conn: &Connection, payment_id: i64) -> Result<Vec<NaverPaymentItem>, String> {
+    let mut item_stmt = conn
+        .prepare(
+            "SELECT id, line_no, product_name, image_url, info_url, quantity,
+                    unit_price, line_amount, rest_amount, memo
+             FROM tbl_naver_payment_item
+             WHERE payment_id = ?1
+             ORDER BY line_no, id"
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let item_rows = item_stmt
+        .query_map([payment_id], naver_payment_item_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for item_result in item_rows {
+        items.push(item_result.map_err(|e| e.to_string())?);
+    }
+    Ok(items)
+}
+
+// NAVER_PAYMENT_LIST_COLUMNS 순서의 행을 NaverPaymentListItem으로 변환 (상세 항목 제외)
+fn naver_payment_list_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<NaverPaymentListItem> {
+    Ok(NaverPaymentListItem {
+        id: row.get(0)?,
+        pay_id: row.get(1)?,
+        external_id: row.get(2)?,
+        service_type: row.get(3)?,
+        status_code: row.get(4)?,
+        status_text: row.get(5)?,
+        status_color: row.get(6)?,
+        paid_at: row.get(7)?,
+        purchaser_name: row.get(8)?,
+        merchant_name: row.get(9)?,
+        product_name: row.get(10)?,
+        product_count: row.get(11)?,
+        total_amount: row.get(12)?,
+        discount_amount: row.get(13)?,
+        items: Vec::new(),
+    })
+}
+
+const NAVER_PAYMENT_LIST_COLUMNS: &str = "id, pay_id, external_id, service_type, status_code, status_text, status_color,
+                    paid_at, purchaser_name, merchant_name, product_name, product_count,
+                    total_amount, discount_amount";
+
+// 목록 조회 결과에 상세 항목을 채워서 반환
+fn collect_naver_payments<P: rusqlite::Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+) -> Result<Vec<NaverPaymentListItem>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params, naver_payment_list_item_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut payments = Vec::new();
+    for row_result in rows {
+        let mut payment = row_result.map_err(|e| e.to_string())?;
+        payment.items = load_naver_payment_items(conn, payment.id)?;
+        payments.push(payment);
+    }
+    Ok(payments)
+}
+
+// 결제 목록 정렬 조건 (컬럼/방향은 화이트리스트로만 허용, 기본값은 결제일 내림차순)
+fn payment_list_order_by(
+    sort_by: Option<String>,
+    sort_direction: Option<String>,
+    date_column: &str,
+) -> Result<String, String> {
+    let direction = match sort_direction.as_deref().map(|d| d.to_ascii_uppercase()) {
+        None => "DESC".to_string(),
+        Some(d) if d == "ASC" || d == "DESC" => d,
+        Some(d) => return Err(format!("유효하지 않은 정렬 방향입니다: {}", d)),
+    };
+    let column = match sort_by.as_deref() {
+        None | Some("paid_at") => return Ok(format!("{} {}, id {}", date_column, direction, direction)),
+        Some("total_amount") => "total_amount",
+        Some("merchant_name") => "merchant_name",
+        Some("status_code") => "status_code",
+        Some(other) => return Err(format!("정렬할 수 없는 컬럼입니다: {}", other)),
+    };
+    // 동일 값은 결제일 내림차순, 같은 결제일은 id 내림차순으로 정렬
+    Ok(format!("{} {}, {} DESC, id DESC", column, direction, date_column))
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn list_naver_payments(
     app_handle: AppHandle,
     state: State<AppState>,
     user_id: String,
     limit: Option<i64>,
     offset: Option<i64>,
+    sort_by: Option<String>,
+    sort_direction: Option<String>,
+    merchant_country_code: Option<String>,
 ) -> Result<Vec<NaverPaymentListItem>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
@@ -1266,103 +2846,98 @@ fn list_naver_payments(
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    let limit = limit.unwrap_or(100);
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
     let offset = offset.unwrap_or(0);
+    let order_by = payment_list_order_by(sort_by, sort_direction, "paid_at")?;
+    let country_code = match merchant_country_code {
+        Some(code) if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(code.to_ascii_uppercase())
+        }
+        Some(code) => return Err(format!("국가 코드는 두 글자여야 합니다: {}", code)),
+        None => None,
+    };
     
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, pay_id, external_id, service_type, status_code, status_text, status_color,
-                    paid_at, purchaser_name, merchant_name, product_name, product_count,
-                    total_amount, discount_amount
+    // 국가 코드가 없는 결제는 국내(KR)로 간주
+    collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {}
              FROM tbl_naver_payment
              WHERE user_id = ?1
                AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
                AND (service_type IS NULL OR service_type NOT IN ('BOOKING', 'CONTENTS'))
-             ORDER BY paid_at DESC
-             LIMIT ?2 OFFSET ?3"
-        )
-        .map_err(|e| e.to_string())?;
+               AND (?4 IS NULL OR COALESCE(merchant_country_code, 'KR') = ?4)
+             ORDER BY {}
+             LIMIT ?2 OFFSET ?3",
+            NAVER_PAYMENT_LIST_COLUMNS, order_by
+        ),
+        rusqlite::params![user_id, limit, offset, country_code],
+    )
+}
+
+// 기본 목록에서 제외되는 예약(BOOKING)/콘텐츠(CONTENTS) 결제만 조회
+#[tauri::command]
+fn list_naver_bookings(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<NaverPaymentListItem>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    let rows = stmt
-        .query_map(rusqlite::params![user_id, limit, offset], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, Option<String>>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, String>(7)?,
-                row.get::<_, Option<String>>(8)?,
-                row.get::<_, String>(9)?,
-                row.get::<_, Option<String>>(10)?,
-                row.get::<_, Option<i32>>(11)?,
-                row.get::<_, i64>(12)?,
-                row.get::<_, Option<i64>>(13)?,
-            ))
-        })
-        .map_err(|e| e.to_string())?;
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
     
-    let mut payments = Vec::new();
-    for row_result in rows {
-        let (id, pay_id, external_id, service_type, status_code, status_text, status_color,
-             paid_at, purchaser_name, merchant_name, product_name, product_count,
-             total_amount, discount_amount) = row_result.map_err(|e| e.to_string())?;
-        
-        // 상세 항목 조회
-        let mut item_stmt = conn
-            .prepare(
-                "SELECT id, line_no, product_name, image_url, info_url, quantity,
-                        unit_price, line_amount, rest_amount, memo
-                 FROM tbl_naver_payment_item
-                 WHERE payment_id = ?1
-                 ORDER BY line_no"
-            )
-            .map_err(|e| e.to_string())?;
-        
-        let item_rows = item_stmt
-            .query_map([id], |row| {
-                Ok(NaverPaymentItem {
-                    id: row.get(0)?,
-                    line_no: row.get(1)?,
-                    product_name: row.get(2)?,
-                    image_url: row.get(3)?,
-                    info_url: row.get(4)?,
-                    quantity: row.get(5)?,
-                    unit_price: row.get(6)?,
-                    line_amount: row.get(7)?,
-                    rest_amount: row.get(8)?,
-                    memo: row.get(9)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-        
-        let mut items = Vec::new();
-        for item_result in item_rows {
-            items.push(item_result.map_err(|e| e.to_string())?);
-        }
-        
-        payments.push(NaverPaymentListItem {
-            id,
-            pay_id,
-            external_id,
-            service_type,
-            status_code,
-            status_text,
-            status_color,
-            paid_at,
-            purchaser_name,
-            merchant_name,
-            product_name,
-            product_count,
-            total_amount,
-            discount_amount,
-            items,
-        });
+    collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {}
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND service_type IN ('BOOKING', 'CONTENTS')
+             ORDER BY paid_at DESC, id DESC
+             LIMIT ?2 OFFSET ?3",
+            NAVER_PAYMENT_LIST_COLUMNS
+        ),
+        rusqlite::params![user_id, limit, offset],
+    )
+}
+
+// 외부 식별자(external_id)로 네이버 결제 조회 (상태와 무관)
+#[tauri::command]
+fn get_naver_payment_by_external_id(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    external_id: String,
+) -> Result<Option<NaverPaymentListItem>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
     }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    Ok(payments)
+    let payments = collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {}
+             FROM tbl_naver_payment
+             WHERE user_id = ?1 AND external_id = ?2
+             ORDER BY id
+             LIMIT 1",
+            NAVER_PAYMENT_LIST_COLUMNS
+        ),
+        rusqlite::params![user_id, external_id],
+    )?;
+    Ok(payments.into_iter().next())
 }
 
 // 쿠팡 결제 목록 조회용 구조체
@@ -1392,631 +2967,7382 @@ struct CoupangPaymentListItem {
     items: Vec<CoupangPaymentItem>,
 }
 
-#[tauri::command]
-fn list_coupang_payments(
-    app_handle: AppHandle,
-    state: State<AppState>,
-    user_id: String,
-    limit: Option<i64>,
-    offset: Option<i64>,
-) -> Result<Vec<CoupangPaymentListItem>, String> {
-    let path = configured_db_path(&app_handle, &state)?
-        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
-    if !path.exists() {
-        return Ok(Vec::new());
-    }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    let limit = limit.unwrap_or(100);
-    let offset = offset.unwrap_or(0);
-    
-    let mut stmt = conn
+// 주문의 상세 항목 조회
+// SELECT id, line_no, product_id, vendor_item_id, product_name, image_url, info_url,
+//        brand_name, quantity, unit_price, discounted_unit_price, combined_unit_price,
+//        line_amount, rest_amount, memo 순서의 행을 CoupangPaymentItem으로 변환
+fn coupang_payment_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<CoupangPaymentItem> {
+    Ok(CoupangPaymentItem {
+        id: row.get(0)?,
+        line_no: row.get(1)?,
+        product_id: row.get(2)?,
+        vendor_item_id: row.get(3)?,
+        product_name: row.get(4)?,
+        image_url: row.get(5)?,
+        info_url: row.get(6)?,
+        brand_name: row.get(7)?,
+        quantity: row.get(8)?,
+        unit_price: row.get(9)?,
+        discounted_unit_price: row.get(10)?,
+        combined_unit_price: row.get(11)?,
+        line_amount: row.get(12)?,
+        rest_amount: row.get(13)?,
+        memo: row.get(14)?,
+    })
+}
+
+fn load_coupang_payment_items(conn: &Connection, payment_id: i64) -> Result<Vec<CoupangPaymentItem>, String> {
+    let mut item_stmt = conn
         .prepare(
-            "SELECT id, order_id, external_id, status_code, status_text, status_color,
-                    ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
-                    product_name, product_count, total_amount, total_order_amount, total_cancel_amount,
-                    discount_amount, rest_amount, main_pay_type
-             FROM tbl_coupang_payment
-             WHERE user_id = ?1
-               AND (status_code IS NULL OR status_code != 'CANCELED')
-             ORDER BY ordered_at DESC
-             LIMIT ?2 OFFSET ?3"
+            "SELECT id, line_no, product_id, vendor_item_id, product_name, image_url, info_url,
+                    brand_name, quantity, unit_price, discounted_unit_price, combined_unit_price,
+                    line_amount, rest_amount, memo
+             FROM tbl_coupang_payment_item
+             WHERE payment_id = ?1
+             ORDER BY line_no, id"
         )
         .map_err(|e| e.to_string())?;
     
+    let item_rows = item_stmt
+        .query_map([payment_id], coupang_payment_item_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for item_result in item_rows {
+        items.push(item_result.map_err(|e| e.to_string())?);
+    }
+    Ok(items)
+}
+
+// COUPANG_PAYMENT_LIST_COLUMNS 순서의 행을 CoupangPaymentListItem으로 변환 (상세 항목 제외)
+fn coupang_payment_list_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<CoupangPaymentListItem> {
+    Ok(CoupangPaymentListItem {
+        id: row.get(0)?,
+        order_id: row.get(1)?,
+        external_id: row.get(2)?,
+        status_code: row.get(3)?,
+        status_text: row.get(4)?,
+        status_color: row.get(5)?,
+        ordered_at: row.get(6)?,
+        paid_at: row.get(7)?,
+        merchant_name: row.get(8)?,
+        merchant_tel: row.get(9)?,
+        merchant_url: row.get(10)?,
+        merchant_image_url: row.get(11)?,
+        product_name: row.get(12)?,
+        product_count: row.get(13)?,
+        total_amount: row.get(14)?,
+        total_order_amount: row.get(15)?,
+        total_cancel_amount: row.get(16)?,
+        discount_amount: row.get(17)?,
+        rest_amount: row.get(18)?,
+        main_pay_type: row.get(19)?,
+        items: Vec::new(),
+    })
+}
+
+const COUPANG_PAYMENT_LIST_COLUMNS: &str = "id, order_id, external_id, status_code, status_text, status_color,
+                    ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
+                    product_name, product_count, total_amount, total_order_amount, total_cancel_amount,
+                    discount_amount, rest_amount, main_pay_type";
+
+// 목록 조회 결과에 상세 항목을 채워서 반환
+fn collect_coupang_payments<P: rusqlite::Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+) -> Result<Vec<CoupangPaymentListItem>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
     let rows = stmt
-        .query_map(rusqlite::params![user_id, limit, offset], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, Option<String>>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, Option<String>>(7)?,
-                row.get::<_, String>(8)?,
-                row.get::<_, Option<String>>(9)?,
-                row.get::<_, Option<String>>(10)?,
-                row.get::<_, Option<String>>(11)?,
-                row.get::<_, Option<String>>(12)?,
-                row.get::<_, Option<i32>>(13)?,
-                row.get::<_, i64>(14)?,
-                row.get::<_, Option<i64>>(15)?,
-                row.get::<_, Option<i64>>(16)?,
-                row.get::<_, Option<i64>>(17)?,
-                row.get::<_, Option<i64>>(18)?,
-                row.get::<_, Option<String>>(19)?,
-            ))
-        })
+        .query_map(params, coupang_payment_list_item_from_row)
         .map_err(|e| e.to_string())?;
     
     let mut payments = Vec::new();
     for row_result in rows {
-        let (id, order_id, external_id, status_code, status_text, status_color,
-             ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
-             product_name, product_count, total_amount, total_order_amount, total_cancel_amount,
-             discount_amount, rest_amount, main_pay_type) = row_result.map_err(|e| e.to_string())?;
-        
-        // 상세 항목 조회
-        let mut item_stmt = conn
-            .prepare(
-                "SELECT id, line_no, product_id, vendor_item_id, product_name, image_url, info_url,
-                        brand_name, quantity, unit_price, discounted_unit_price, combined_unit_price,
-                        line_amount, rest_amount, memo
-                 FROM tbl_coupang_payment_item
-                 WHERE payment_id = ?1
-                 ORDER BY line_no"
-            )
-            .map_err(|e| e.to_string())?;
-        
-        let item_rows = item_stmt
-            .query_map([id], |row| {
-                Ok(CoupangPaymentItem {
-                    id: row.get(0)?,
-                    line_no: row.get(1)?,
-                    product_id: row.get(2)?,
-                    vendor_item_id: row.get(3)?,
-                    product_name: row.get(4)?,
-                    image_url: row.get(5)?,
-                    info_url: row.get(6)?,
-                    brand_name: row.get(7)?,
-                    quantity: row.get(8)?,
-                    unit_price: row.get(9)?,
-                    discounted_unit_price: row.get(10)?,
-                    combined_unit_price: row.get(11)?,
-                    line_amount: row.get(12)?,
-                    rest_amount: row.get(13)?,
-                    memo: row.get(14)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
-        
-        let mut items = Vec::new();
-        for item_result in item_rows {
-            items.push(item_result.map_err(|e| e.to_string())?);
-        }
-        
-        payments.push(CoupangPaymentListItem {
-            id,
-            order_id,
-            external_id,
-            status_code,
-            status_text,
-            status_color,
-            ordered_at,
-            paid_at,
-            merchant_name,
-            merchant_tel,
-            merchant_url,
-            merchant_image_url,
-            product_name,
-            product_count,
-            total_amount,
-            total_order_amount,
-            total_cancel_amount,
-            discount_amount,
-            rest_amount,
-            main_pay_type,
-            items,
-        });
+        let mut payment = row_result.map_err(|e| e.to_string())?;
+        payment.items = load_coupang_payment_items(conn, payment.id)?;
+        payments.push(payment);
     }
-    
     Ok(payments)
 }
 
 #[tauri::command]
-fn save_coupang_payment(
+fn list_coupang_payments(
     app_handle: AppHandle,
     state: State<AppState>,
     user_id: String,
-    payment: CoupangPayment,
-) -> Result<(), String> {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort_by: Option<String>,
+    sort_direction: Option<String>,
+) -> Result<Vec<CoupangPaymentListItem>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
-    }
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-
-    {
-        let now = Utc::now().to_rfc3339();
-        
-        // 1. 결제 정보 저장 (UPSERT)
-        tx.execute(
-            "INSERT INTO tbl_coupang_payment (
-                user_id, order_id, external_id, status_code, status_text, status_color,
-                ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
-                product_name, product_count, product_detail_url, order_detail_url,
-                total_amount, total_order_amount, total_cancel_amount, discount_amount, rest_amount,
-                main_pay_type, pay_rocket_balance_amount, pay_card_amount, pay_coupon_amount,
-                pay_coupang_cash_amount, pay_rocket_bank_amount, wow_instant_discount, reward_cash_amount,
-                created_at, updated_at
-            ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31
-            )
-            ON CONFLICT(user_id, order_id) DO UPDATE SET
-                external_id = excluded.external_id,
-                status_code = excluded.status_code,
-                status_text = excluded.status_text,
-                status_color = excluded.status_color,
-                ordered_at = excluded.ordered_at,
-                paid_at = excluded.paid_at,
-                merchant_name = excluded.merchant_name,
-                merchant_tel = excluded.merchant_tel,
-                merchant_url = excluded.merchant_url,
-                merchant_image_url = excluded.merchant_image_url,
-                product_name = excluded.product_name,
-                product_count = excluded.product_count,
-                product_detail_url = excluded.product_detail_url,
-                order_detail_url = excluded.order_detail_url,
-                total_amount = excluded.total_amount,
-                total_order_amount = excluded.total_order_amount,
-                total_cancel_amount = excluded.total_cancel_amount,
-                discount_amount = excluded.discount_amount,
-                rest_amount = excluded.rest_amount,
-                main_pay_type = excluded.main_pay_type,
-                pay_rocket_balance_amount = excluded.pay_rocket_balance_amount,
-                pay_card_amount = excluded.pay_card_amount,
-                pay_coupon_amount = excluded.pay_coupon_amount,
-                pay_coupang_cash_amount = excluded.pay_coupang_cash_amount,
-                pay_rocket_bank_amount = excluded.pay_rocket_bank_amount,
-                wow_instant_discount = excluded.wow_instant_discount,
-                reward_cash_amount = excluded.reward_cash_amount,
-                updated_at = excluded.updated_at",
-            rusqlite::params![
-                user_id, payment.order_id, payment.external_id, payment.status_code,
-                payment.status_text, payment.status_color, payment.ordered_at, payment.paid_at,
-                payment.merchant_name, payment.merchant_tel, payment.merchant_url,
-                payment.merchant_image_url, payment.product_name, payment.product_count,
-                payment.product_detail_url, payment.order_detail_url, payment.total_amount,
-                payment.total_order_amount, payment.total_cancel_amount, payment.discount_amount,
-                payment.rest_amount, payment.main_pay_type, payment.pay_rocket_balance_amount,
-                payment.pay_card_amount, payment.pay_coupon_amount, payment.pay_coupang_cash_amount,
-                payment.pay_rocket_bank_amount, payment.wow_instant_discount, payment.reward_cash_amount,
-                now, now
-            ],
-        ).map_err(|e| e.to_string())?;
-
-        // 저장된 결제의 ID 조회
-        let payment_pk: i64 = tx.query_row(
-            "SELECT id FROM tbl_coupang_payment WHERE user_id = ?1 AND order_id = ?2",
-            rusqlite::params![&user_id, &payment.order_id],
-            |row| row.get(0),
-        ).map_err(|e| e.to_string())?;
-
-        // 2. 결제 항목 UPSERT
-        for item in payment.items {
-            tx.execute(
-                "INSERT INTO tbl_coupang_payment_item (
-                    payment_id, line_no, product_id, vendor_item_id, product_name, image_url, info_url,
-                    brand_name, quantity, unit_price, discounted_unit_price, combined_unit_price,
-                    line_amount, rest_amount, memo, created_at, updated_at
-                ) VALUES (
-                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17
-                )
-                ON CONFLICT(payment_id, line_no) DO UPDATE SET
-                    product_id = excluded.product_id,
-                    vendor_item_id = excluded.vendor_item_id,
-                    product_name = excluded.product_name,
-                    image_url = excluded.image_url,
-                    info_url = excluded.info_url,
-                    brand_name = excluded.brand_name,
-                    quantity = excluded.quantity,
-                    unit_price = excluded.unit_price,
-                    discounted_unit_price = excluded.discounted_unit_price,
-                    combined_unit_price = excluded.combined_unit_price,
-                    line_amount = excluded.line_amount,
-                    rest_amount = excluded.rest_amount,
-                    memo = excluded.memo,
-                    updated_at = excluded.updated_at",
-                rusqlite::params![
-                    payment_pk, item.line_no, item.product_id, item.vendor_item_id, item.product_name,
-                    item.image_url, item.info_url, item.brand_name, item.quantity, item.unit_price,
-                    item.discounted_unit_price, item.combined_unit_price, item.line_amount,
-                    item.rest_amount, item.memo, now, now
-                ],
-            ).map_err(|e| e.to_string())?;
-        }
+        return Ok(Vec::new());
     }
-
-    tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
+    // 쿠팡은 주문일(ordered_at) 기준으로 결제일 정렬
+    let order_by = payment_list_order_by(sort_by, sort_direction, "ordered_at")?;
+    
+    collect_coupang_payments(
+        &conn,
+        &format!(
+            "SELECT {}
+             FROM tbl_coupang_payment
+             WHERE user_id = ?1
+               AND (status_code IS NULL OR status_code != 'CANCELED')
+             ORDER BY {}
+             LIMIT ?2 OFFSET ?3",
+            COUPANG_PAYMENT_LIST_COLUMNS, order_by
+        ),
+        rusqlite::params![user_id, limit, offset],
+    )
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct SearchResultItem {
-    id: i64,
+struct PaymentExportMetadata {
+    exported_at: String,
+    record_count: usize,
     provider: String,
-    product_name: String,
-    image_url: Option<String>,
-    merchant_name: String,
-    paid_at: String,
-    quantity: i64,
-    unit_price: Option<i64>,
-    line_amount: Option<i64>,
+    user_id: String,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct SearchResponse {
-    items: Vec<SearchResultItem>,
-    total: i64,
+struct PaymentExport<T: Serialize> {
+    metadata: PaymentExportMetadata,
+    payments: Vec<T>,
+}
+
+fn payment_export_json<T: Serialize>(provider: &str, user_id: String, payments: Vec<T>) -> Result<String, String> {
+    let export = PaymentExport {
+        metadata: PaymentExportMetadata {
+            exported_at: Utc::now().to_rfc3339(),
+            record_count: payments.len(),
+            provider: provider.to_string(),
+            user_id,
+        },
+        payments,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
 }
 
+// 네이버 결제 목록을 JSON으로 내보내기 (list_naver_payments와 같은 상태/서비스 필터, 날짜는 YYYY-MM-DD 양 끝 포함)
 #[tauri::command]
-fn search_products(
+fn export_naver_payments_json(
     app_handle: AppHandle,
     state: State<AppState>,
-    query: String,
-    limit: Option<i64>,
-) -> Result<SearchResponse, String> {
+    user_id: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    include_items: bool,
+) -> Result<String, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Ok(SearchResponse { items: vec![], total: 0 });
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
-    
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    let search_term = format!("%{}%", query);
-    let result_limit = limit.unwrap_or(50);
     
-    let mut items = Vec::new();
+    let sql = format!(
+        "SELECT {}
+         FROM tbl_naver_payment
+         WHERE user_id = ?1
+           AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+           AND (service_type IS NULL OR service_type NOT IN ('BOOKING', 'CONTENTS'))
+           AND (?2 IS NULL OR substr(paid_at, 1, 10) >= ?2)
+           AND (?3 IS NULL OR substr(paid_at, 1, 10) <= ?3)
+         ORDER BY paid_at DESC, id DESC",
+        NAVER_PAYMENT_LIST_COLUMNS
+    );
+    let params = rusqlite::params![user_id, from_date, to_date];
+    let payments = if include_items {
+        collect_naver_payments(&conn, &sql, params)?
+    } else {
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params, naver_payment_list_item_from_row)
+            .map_err(|e| e.to_string())?;
+        let mut payments = Vec::new();
+        for row in rows {
+            payments.push(row.map_err(|e| e.to_string())?);
+        }
+        payments
+    };
     
-    // 네이버 결제 항목 검색 (실제 거래만: 구매확정, 결제완료, 배송완료, 구매확정연장)
-    let mut naver_stmt = conn.prepare(
-        "SELECT i.id, i.product_name, i.image_url, p.merchant_name, p.paid_at, 
-                i.quantity, i.unit_price, i.line_amount
-         FROM tbl_naver_payment_item i
-         JOIN tbl_naver_payment p ON i.payment_id = p.id
-         WHERE i.product_name LIKE ?1
-           AND p.status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
-         ORDER BY p.paid_at DESC
-         LIMIT ?2"
-    ).map_err(|e| e.to_string())?;
-    
-    let naver_rows = naver_stmt.query_map(rusqlite::params![&search_term, result_limit], |row| {
-        Ok(SearchResultItem {
-            id: row.get(0)?,
-            provider: "naver".to_string(),
-            product_name: row.get(1)?,
-            image_url: row.get(2)?,
-            merchant_name: row.get(3)?,
-            paid_at: row.get(4)?,
-            quantity: row.get(5)?,
-            unit_price: row.get(6)?,
-            line_amount: row.get(7)?,
-        })
-    }).map_err(|e| e.to_string())?;
-    
-    for row in naver_rows {
-        items.push(row.map_err(|e| e.to_string())?);
+    payment_export_json("naver", user_id, payments)
+}
+
+// 쿠팡 결제 목록을 JSON으로 내보내기 (list_coupang_payments와 같은 상태 필터, 날짜는 주문일 기준)
+#[tauri::command]
+fn export_coupang_payments_json(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    include_items: bool,
+) -> Result<String, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    // 쿠팡 결제 항목 검색 (CANCELED 상태 제외)
-    let mut coupang_stmt = conn.prepare(
-        "SELECT i.id, i.product_name, i.image_url, p.merchant_name, p.ordered_at,
-                i.quantity, i.unit_price, i.line_amount
-         FROM tbl_coupang_payment_item i
-         JOIN tbl_coupang_payment p ON i.payment_id = p.id
-         WHERE i.product_name LIKE ?1
-           AND (p.status_code IS NULL OR p.status_code != 'CANCELED')
-         ORDER BY p.ordered_at DESC
-         LIMIT ?2"
-    ).map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT {}
+         FROM tbl_coupang_payment
+         WHERE user_id = ?1
+           AND (status_code IS NULL OR status_code != 'CANCELED')
+           AND (?2 IS NULL OR substr(ordered_at, 1, 10) >= ?2)
+           AND (?3 IS NULL OR substr(ordered_at, 1, 10) <= ?3)
+         ORDER BY ordered_at DESC, id DESC",
+        COUPANG_PAYMENT_LIST_COLUMNS
+    );
+    let params = rusqlite::params![user_id, from_date, to_date];
+    let payments = if include_items {
+        collect_coupang_payments(&conn, &sql, params)?
+    } else {
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params, coupang_payment_list_item_from_row)
+            .map_err(|e| e.to_string())?;
+        let mut payments = Vec::new();
+        for row in rows {
+            payments.push(row.map_err(|e| e.to_string())?);
+        }
+        payments
+    };
     
-    let coupang_rows = coupang_stmt.query_map(rusqlite::params![&search_term, result_limit], |row| {
-        Ok(SearchResultItem {
-            id: row.get(0)?,
-            provider: "coupang".to_string(),
-            product_name: row.get(1)?,
-            image_url: row.get(2)?,
-            merchant_name: row.get(3)?,
-            paid_at: row.get(4)?,
-            quantity: row.get(5)?,
-            unit_price: row.get(6)?,
-            line_amount: row.get(7)?,
-        })
-    }).map_err(|e| e.to_string())?;
+    payment_export_json("coupang", user_id, payments)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchDeleteResult {
+    deleted: u32,
+    not_found: u32,
+}
+
+// 사용자 소유 결제를 키 목록으로 일괄 삭제 (상세 항목은 FK CASCADE로 함께 삭제)
+fn delete_payments_batch(
+    path: &Path,
+    table: &str,
+    key_column: &str,
+    user_id: String,
+    keys: Vec<String>,
+) -> Result<BatchDeleteResult, String> {
+    let mut keys = keys;
+    keys.sort();
+    keys.dedup();
+    if keys.is_empty() {
+        return Ok(BatchDeleteResult { deleted: 0, not_found: 0 });
+    }
     
-    for row in coupang_rows {
-        items.push(row.map_err(|e| e.to_string())?);
+    let mut params: Vec<rusqlite::types::Value> = vec![user_id.into()];
+    let mut placeholders = Vec::new();
+    for key in &keys {
+        params.push(key.clone().into());
+        placeholders.push(format!("?{}", params.len()));
     }
     
-    // 날짜순 정렬
-    items.sort_by(|a, b| b.paid_at.cmp(&a.paid_at));
+    let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let deleted = tx
+        .execute(
+            &format!(
+                "DELETE FROM {} WHERE user_id = ?1 AND {} IN ({})",
+                table,
+                key_column,
+                placeholders.join(", ")
+            ),
+            rusqlite::params_from_iter(params),
+        )
+        .map_err(|e| e.to_string())? as u32;
+    tx.commit().map_err(|e| e.to_string())?;
     
-    let total = items.len() as i64;
+    tracing::info!(table, deleted, "payments batch deleted");
+    Ok(BatchDeleteResult {
+        deleted,
+        not_found: keys.len() as u32 - deleted,
+    })
+}
+
+#[tauri::command]
+fn delete_naver_payments_batch(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    pay_ids: Vec<String>,
+) -> Result<BatchDeleteResult, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    delete_payments_batch(&path, "tbl_naver_payment", "pay_id", user_id, pay_ids)
+}
+
+#[tauri::command]
+fn delete_coupang_payments_batch(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    order_ids: Vec<String>,
+) -> Result<BatchDeleteResult, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    delete_payments_batch(&path, "tbl_coupang_payment", "order_id", user_id, order_ids)
+}
+
+// 네이버 결제 상세 (테이블의 모든 컬럼 + 상세 항목)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NaverPaymentDetail {
+    id: i64,
+    #[serde(flatten)]
+    payment: NaverPayment,
+    created_at: String,
+    updated_at: String,
+}
+
+fn load_naver_payment_detail(conn: &Connection, user_id: &str, pay_id: &str) -> Result<Option<NaverPaymentDetail>, String> {
+    let result = conn.query_row(
+        "SELECT id, pay_id, external_id, service_type, status_code, status_text,
+                    status_color, paid_at, purchaser_name, merchant_no, merchant_name, merchant_tel,
+                    merchant_url, merchant_image_url, merchant_payment_id, sub_merchant_name, sub_merchant_url, sub_merchant_payment_id,
+                    is_tax_type, is_oversea_transfer, product_name, product_count, product_detail_url, order_detail_url,
+                    total_amount, discount_amount, cup_deposit_amount, rest_amount, pay_easycard_amount, pay_easybank_amount,
+                    pay_reward_point_amount, pay_charge_point_amount, pay_giftcard_amount, benefit_type, has_plus_membership, benefit_waiting_period,
+                    benefit_expected_amount, benefit_amount, is_membership, is_branch, is_last_subscription_round, is_cafe_safe_payment,
+                    merchant_country_code, merchant_country_name, application_completed, created_at, updated_at
+         FROM tbl_naver_payment
+         WHERE user_id = ?1 AND pay_id = ?2",
+        rusqlite::params![user_id, pay_id],
+        |row| {
+            Ok(NaverPaymentDetail {
+                id: row.get(0)?,
+                payment: NaverPayment {
+                    pay_id: row.get(1)?,
+                    external_id: row.get(2)?,
+                    service_type: row.get(3)?,
+                    status_code: row.get(4)?,
+                    status_text: row.get(5)?,
+                    status_color: row.get(6)?,
+                    paid_at: row.get(7)?,
+                    purchaser_name: row.get(8)?,
+                    merchant_no: row.get(9)?,
+                    merchant_name: row.get(10)?,
+                    merchant_tel: row.get(11)?,
+                    merchant_url: row.get(12)?,
+                    merchant_image_url: row.get(13)?,
+                    merchant_payment_id: row.get(14)?,
+                    sub_merchant_name: row.get(15)?,
+                    sub_merchant_url: row.get(16)?,
+                    sub_merchant_payment_id: row.get(17)?,
+                    is_tax_type: row.get(18)?,
+                    is_oversea_transfer: row.get(19)?,
+                    product_name: row.get(20)?,
+                    product_count: row.get(21)?,
+                    product_detail_url: row.get(22)?,
+                    order_detail_url: row.get(23)?,
+                    total_amount: row.get(24)?,
+                    discount_amount: row.get(25)?,
+                    cup_deposit_amount: row.get(26)?,
+                    rest_amount: row.get(27)?,
+                    pay_easycard_amount: row.get(28)?,
+                    pay_easybank_amount: row.get(29)?,
+                    pay_reward_point_amount: row.get(30)?,
+                    pay_charge_point_amount: row.get(31)?,
+                    pay_giftcard_amount: row.get(32)?,
+                    benefit_type: row.get(33)?,
+                    has_plus_membership: row.get(34)?,
+                    benefit_waiting_period: row.get(35)?,
+                    benefit_expected_amount: row.get(36)?,
+                    benefit_amount: row.get(37)?,
+                    is_membership: row.get(38)?,
+                    is_branch: row.get(39)?,
+                    is_last_subscription_round: row.get(40)?,
+                    is_cafe_safe_payment: row.get(41)?,
+                    merchant_country_code: row.get(42)?,
+                    merchant_country_name: row.get(43)?,
+                    application_completed: row.get(44)?,
+                    items: Vec::new(),
+                    raw: None,
+                },
+                created_at: row.get(45)?,
+                updated_at: row.get(46)?,
+            })
+        },
+    );
     
-    // limit 적용
-    if items.len() > result_limit as usize {
-        items.truncate(result_limit as usize);
+    match result {
+        Ok(mut detail) => {
+            detail.payment.items = load_naver_payment_items(conn, detail.id)?;
+            Ok(Some(detail))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+fn get_naver_payment_detail(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    pay_id: String,
+) -> Result<Option<NaverPaymentDetail>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
     }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    load_naver_payment_detail(&conn, &user_id, &pay_id)
+}
+
+// 쿠팡 결제 상세 (테이블의 모든 컬럼 + 상세 항목)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoupangPaymentDetail {
+    id: i64,
+    #[serde(flatten)]
+    payment: CoupangPayment,
+    created_at: String,
+    updated_at: String,
+}
+
+fn load_coupang_payment_detail(conn: &Connection, user_id: &str, order_id: &str) -> Result<Option<CoupangPaymentDetail>, String> {
+    let result = conn.query_row(
+        "SELECT id, order_id, external_id, status_code, status_text, status_color,
+                    ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
+                    product_name, product_count, product_detail_url, order_detail_url, total_amount, total_order_amount,
+                    total_cancel_amount, discount_amount, rest_amount, main_pay_type, pay_rocket_balance_amount, pay_card_amount,
+                    pay_coupon_amount, pay_coupang_cash_amount, pay_rocket_bank_amount, wow_instant_discount, reward_cash_amount, created_at,
+                    updated_at
+         FROM tbl_coupang_payment
+         WHERE user_id = ?1 AND order_id = ?2",
+        rusqlite::params![user_id, order_id],
+        |row| {
+            Ok(CoupangPaymentDetail {
+                id: row.get(0)?,
+                payment: CoupangPayment {
+                    order_id: row.get(1)?,
+                    external_id: row.get(2)?,
+                    status_code: row.get(3)?,
+                    status_text: row.get(4)?,
+                    status_color: row.get(5)?,
+                    ordered_at: row.get(6)?,
+                    paid_at: row.get(7)?,
+                    merchant_name: row.get(8)?,
+                    merchant_tel: row.get(9)?,
+                    merchant_url: row.get(10)?,
+                    merchant_image_url: row.get(11)?,
+                    product_name: row.get(12)?,
+                    product_count: row.get(13)?,
+                    product_detail_url: row.get(14)?,
+                    order_detail_url: row.get(15)?,
+                    total_amount: row.get(16)?,
+                    total_order_amount: row.get(17)?,
+                    total_cancel_amount: row.get(18)?,
+                    discount_amount: row.get(19)?,
+                    rest_amount: row.get(20)?,
+                    main_pay_type: row.get(21)?,
+                    pay_rocket_balance_amount: row.get(22)?,
+                    pay_card_amount: row.get(23)?,
+                    pay_coupon_amount: row.get(24)?,
+                    pay_coupang_cash_amount: row.get(25)?,
+                    pay_rocket_bank_amount: row.get(26)?,
+                    wow_instant_discount: row.get(27)?,
+                    reward_cash_amount: row.get(28)?,
+                    items: Vec::new(),
+                    raw: None,
+                },
+                created_at: row.get(29)?,
+                updated_at: row.get(30)?,
+            })
+        },
+    );
     
-    Ok(SearchResponse { items, total })
+    match result {
+        Ok(mut detail) => {
+            detail.payment.items = load_coupang_payment_items(conn, detail.id)?;
+            Ok(Some(detail))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 #[tauri::command]
-fn get_last_naver_payment(
+fn get_coupang_payment_detail(
     app_handle: AppHandle,
     state: State<AppState>,
     user_id: String,
-) -> Result<Option<NaverLatestPayment>, String> {
+    order_id: String,
+) -> Result<Option<CoupangPaymentDetail>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    load_coupang_payment_detail(&conn, &user_id, &order_id)
+}
+
+// 쿠팡 상품 라인별 할인 금액 (원래 단가 - 최종 단가) * 수량
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoupangItemSaving {
+    line_no: i32,
+    product_name: String,
+    quantity: i32,
+    unit_price: i64,
+    combined_unit_price: i64,
+    saved: i64,
+    is_price_increase: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoupangPaymentSavings {
+    payment_id: i64,
+    total_saved: i64,
+    items: Vec<CoupangItemSaving>,
+}
+
+// 주문의 상품별 할인 금액 조회 (원래 단가가 없는 라인은 제외, 최종 단가가 더 비싸면 0으로 보정하고 표시)
+#[tauri::command]
+fn get_coupang_item_savings(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    payment_id: i64,
+) -> Result<Option<CoupangPaymentSavings>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Ok(None);
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tbl_coupang_payment WHERE id = ?1)",
+            [payment_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Ok(None);
+    }
+    
     let mut stmt = conn
         .prepare(
-            "SELECT pay_id, paid_at 
-             FROM tbl_naver_payment 
-             WHERE user_id = ?1 
-             ORDER BY paid_at DESC 
-             LIMIT 1",
+            "SELECT line_no, product_name, quantity, unit_price,
+                    COALESCE(combined_unit_price, discounted_unit_price)
+             FROM tbl_coupang_payment_item
+             WHERE payment_id = ?1
+               AND unit_price IS NOT NULL
+               AND COALESCE(combined_unit_price, discounted_unit_price) IS NOT NULL
+             ORDER BY line_no, id",
         )
         .map_err(|e| e.to_string())?;
-    let mut rows = stmt
-        .query(rusqlite::params![user_id])
+    let rows = stmt
+        .query_map([payment_id], |row| {
+            let quantity: i32 = row.get(2)?;
+            let unit_price: i64 = row.get(3)?;
+            let combined_unit_price: i64 = row.get(4)?;
+            let difference = (unit_price - combined_unit_price) * i64::from(quantity);
+            Ok(CoupangItemSaving {
+                line_no: row.get(0)?,
+                product_name: row.get(1)?,
+                quantity,
+                unit_price,
+                combined_unit_price,
+                saved: difference.max(0),
+                is_price_increase: difference < 0,
+            })
+        })
         .map_err(|e| e.to_string())?;
-    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
-        Ok(Some(NaverLatestPayment {
-            pay_id: row.get(0).map_err(|e| e.to_string())?,
-            paid_at: row.get(1).map_err(|e| e.to_string())?,
-        }))
-    } else {
-        Ok(None)
+    
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
     }
+    let total_saved = items.iter().map(|item| item.saved).sum();
+    
+    Ok(Some(CoupangPaymentSavings {
+        payment_id,
+        total_saved,
+        items,
+    }))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NaverPaymentItemWithContext {
+    #[serde(flatten)]
+    item: NaverPaymentItem,
+    pay_id: String,
+    paid_at: String,
+    merchant_name: String,
+    total_payment_amount: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoupangPaymentItemWithContext {
+    #[serde(flatten)]
+    item: CoupangPaymentItem,
+    order_id: String,
+    ordered_at: String,
+    merchant_name: String,
+    total_payment_amount: i64,
 }
 
+// 특정 상품의 구매 이력 조회 (정확히 일치 또는 키워드 포함, 둘 다 지정 시 모두 만족)
 #[tauri::command]
-fn get_last_coupang_payment(
+fn get_naver_payment_items_by_product_name(
     app_handle: AppHandle,
     state: State<AppState>,
     user_id: String,
-) -> Result<Option<CoupangLatestPayment>, String> {
+    product_name_exact: Option<String>,
+    product_name_keyword: Option<String>,
+) -> Result<Vec<NaverPaymentItemWithContext>, String> {
+    if product_name_exact.is_none() && product_name_keyword.is_none() {
+        return Err("상품명 또는 검색어를 입력해주세요.".to_string());
+    }
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
     let mut stmt = conn
         .prepare(
-            "SELECT order_id, ordered_at 
-             FROM tbl_coupang_payment 
-             WHERE user_id = ?1 
-             ORDER BY ordered_at DESC 
-             LIMIT 1",
+            "SELECT i.id, i.line_no, i.product_name, i.image_url, i.info_url, i.quantity,
+                    i.unit_price, i.line_amount, i.rest_amount, i.memo,
+                    p.pay_id, p.paid_at, p.merchant_name, p.total_amount
+             FROM tbl_naver_payment_item i
+             JOIN tbl_naver_payment p ON i.payment_id = p.id
+             WHERE p.user_id = ?1
+               AND (?2 IS NULL OR i.product_name = ?2)
+               AND (?3 IS NULL OR i.product_name LIKE '%' || ?3 || '%')
+               AND p.status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+             ORDER BY p.paid_at DESC, p.id DESC, i.line_no",
         )
         .map_err(|e| e.to_string())?;
-    let mut rows = stmt
-        .query(rusqlite::params![user_id])
+    
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, product_name_exact, product_name_keyword], |row| {
+            Ok(NaverPaymentItemWithContext {
+                item: naver_payment_item_from_row(row)?,
+                pay_id: row.get(10)?,
+                paid_at: row.get(11)?,
+                merchant_name: row.get(12)?,
+                total_payment_amount: row.get(13)?,
+            })
+        })
         .map_err(|e| e.to_string())?;
-    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
-        Ok(Some(CoupangLatestPayment {
-            order_id: row.get(0).map_err(|e| e.to_string())?,
-            ordered_at: row.get(1).map_err(|e| e.to_string())?,
-        }))
-    } else {
-        Ok(None)
+    
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
     }
+    Ok(items)
 }
 
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
-
+fn get_coupang_payment_items_by_product_name(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    product_name_exact: Option<String>,
+    product_name_keyword: Option<String>,
+) -> Result<Vec<CoupangPaymentItemWithContext>, String> {
+    if product_name_exact.is_none() && product_name_keyword.is_none() {
+        return Err("상품명 또는 검색어를 입력해주세요.".to_string());
+    }
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.id, i.line_no, i.product_id, i.vendor_item_id, i.product_name, i.image_url, i.info_url,
+                    i.brand_name, i.quantity, i.unit_price, i.discounted_unit_price, i.combined_unit_price,
+                    i.line_amount, i.rest_amount, i.memo,
+                    p.order_id, p.ordered_at, p.merchant_name, p.total_amount
+             FROM tbl_coupang_payment_item i
+             JOIN tbl_coupang_payment p ON i.payment_id = p.id
+             WHERE p.user_id = ?1
+               AND (?2 IS NULL OR i.product_name = ?2)
+               AND (?3 IS NULL OR i.product_name LIKE '%' || ?3 || '%')
+               AND (p.status_code IS NULL OR p.status_code != 'CANCELED')
+             ORDER BY p.ordered_at DESC, p.id DESC, i.line_no",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, product_name_exact, product_name_keyword], |row| {
+            Ok(CoupangPaymentItemWithContext {
+                item: coupang_payment_item_from_row(row)?,
+                order_id: row.get(15)?,
+                ordered_at: row.get(16)?,
+                merchant_name: row.get(17)?,
+                total_payment_amount: row.get(18)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(items)
+}
+
+// 쿠팡 상품 URL의 productId/vendorItemId로 구매 이력 조회 (둘 다 지정 시 어느 하나라도 일치)
+#[tauri::command]
+fn find_coupang_payment_by_item_info(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    product_id: Option<String>,
+    vendor_item_id: Option<String>,
+) -> Result<Vec<CoupangPaymentItemWithContext>, String> {
+    let product_id = product_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+    let vendor_item_id = vendor_item_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+    if product_id.is_none() && vendor_item_id.is_none() {
+        return Err("productId 또는 vendorItemId를 입력해주세요.".to_string());
+    }
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.id, i.line_no, i.product_id, i.vendor_item_id, i.product_name, i.image_url, i.info_url,
+                    i.brand_name, i.quantity, i.unit_price, i.discounted_unit_price, i.combined_unit_price,
+                    i.line_amount, i.rest_amount, i.memo,
+                    p.order_id, p.ordered_at, p.merchant_name, p.total_amount
+             FROM tbl_coupang_payment_item i
+             JOIN tbl_coupang_payment p ON i.payment_id = p.id
+             WHERE p.user_id = ?1
+               AND (i.product_id = ?2 OR i.vendor_item_id = ?3)
+               AND (p.status_code IS NULL OR p.status_code != 'CANCELED')
+             ORDER BY p.ordered_at DESC, p.id DESC, i.line_no",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, product_id, vendor_item_id], |row| {
+            Ok(CoupangPaymentItemWithContext {
+                item: coupang_payment_item_from_row(row)?,
+                order_id: row.get(15)?,
+                ordered_at: row.get(16)?,
+                merchant_name: row.get(17)?,
+                total_payment_amount: row.get(18)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(items)
+}
+
+// 브랜드별 쿠팡 구매 상품 (브랜드명은 대소문자 무시)
+#[tauri::command]
+fn get_coupang_payment_items_by_brand(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    brand_name: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<CoupangPaymentItemWithContext>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.id, i.line_no, i.product_id, i.vendor_item_id, i.product_name, i.image_url, i.info_url,
+                    i.brand_name, i.quantity, i.unit_price, i.discounted_unit_price, i.combined_unit_price,
+                    i.line_amount, i.rest_amount, i.memo,
+                    p.order_id, p.ordered_at, p.merchant_name, p.total_amount
+             FROM tbl_coupang_payment_item i
+             JOIN tbl_coupang_payment p ON i.payment_id = p.id
+             WHERE p.user_id = ?1
+               AND LOWER(i.brand_name) = LOWER(?2)
+               AND (p.status_code IS NULL OR p.status_code != 'CANCELED')
+             ORDER BY p.ordered_at DESC, p.id DESC, i.line_no
+             LIMIT ?3 OFFSET ?4",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, brand_name, limit, offset], |row| {
+            Ok(CoupangPaymentItemWithContext {
+                item: coupang_payment_item_from_row(row)?,
+                order_id: row.get(15)?,
+                ordered_at: row.get(16)?,
+                merchant_name: row.get(17)?,
+                total_payment_amount: row.get(18)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(items)
+}
+
+// get_coupang_payment_items_by_brand의 전체 건수 (페이지네이션용)
+#[tauri::command]
+fn count_coupang_payment_items_by_brand(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    brand_name: String,
+) -> Result<i64, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(0);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    conn.query_row(
+        "SELECT COUNT(*)
+         FROM tbl_coupang_payment_item i
+         JOIN tbl_coupang_payment p ON i.payment_id = p.id
+         WHERE p.user_id = ?1
+           AND LOWER(i.brand_name) = LOWER(?2)
+           AND (p.status_code IS NULL OR p.status_code != 'CANCELED')",
+        rusqlite::params![user_id, brand_name],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaginatedNaverPayments {
+    payments: Vec<NaverPaymentListItem>,
+    total: i64,
+    limit: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaginatedCoupangPayments {
+    payments: Vec<CoupangPaymentListItem>,
+    total: i64,
+    limit: i64,
+}
+
+// 특정 가맹점의 결제 목록 조회 (가맹점 통계 드릴다운)
+#[tauri::command]
+fn get_naver_payments_by_merchant(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    merchant_name: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<PaginatedNaverPayments, String> {
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(PaginatedNaverPayments { payments: Vec::new(), total: 0, limit });
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let offset = offset.unwrap_or(0);
+    
+    let where_clause = "WHERE user_id = ?1
+               AND merchant_name = ?2
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND (service_type IS NULL OR service_type NOT IN ('BOOKING', 'CONTENTS'))";
+    
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM tbl_naver_payment {}", where_clause),
+            rusqlite::params![user_id, merchant_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let payments = collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {} FROM tbl_naver_payment {} ORDER BY paid_at DESC, id DESC LIMIT ?3 OFFSET ?4",
+            NAVER_PAYMENT_LIST_COLUMNS, where_clause
+        ),
+        rusqlite::params![user_id, merchant_name, limit, offset],
+    )?;
+    
+    Ok(PaginatedNaverPayments { payments, total, limit })
+}
+
+// 상품명에 검색어가 포함된 항목이 있는 네이버 결제 목록 (상품 검색의 결제 단위 보기)
+#[tauri::command]
+fn list_naver_payments_by_product_keyword(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    keyword: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<NaverPaymentListItem>, String> {
+    let keyword = keyword.trim().to_string();
+    if keyword.is_empty() {
+        return Err("검색어를 입력해주세요.".to_string());
+    }
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
+    
+    collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {} FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND id IN (SELECT payment_id FROM tbl_naver_payment_item WHERE product_name LIKE '%' || ?2 || '%')
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+             ORDER BY paid_at DESC, id DESC
+             LIMIT ?3 OFFSET ?4",
+            NAVER_PAYMENT_LIST_COLUMNS
+        ),
+        rusqlite::params![user_id, keyword, limit, offset],
+    )
+}
+
+// list_naver_payments_by_product_keyword의 전체 건수 (페이지네이션용)
+#[tauri::command]
+fn count_naver_payments_by_product_keyword(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    keyword: String,
+) -> Result<i64, String> {
+    let keyword = keyword.trim().to_string();
+    if keyword.is_empty() {
+        return Err("검색어를 입력해주세요.".to_string());
+    }
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(0);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    conn.query_row(
+        "SELECT COUNT(*) FROM tbl_naver_payment
+         WHERE user_id = ?1
+           AND id IN (SELECT payment_id FROM tbl_naver_payment_item WHERE product_name LIKE '%' || ?2 || '%')
+           AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')",
+        rusqlite::params![user_id, keyword],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_coupang_payments_by_merchant(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    merchant_name: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<PaginatedCoupangPayments, String> {
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(PaginatedCoupangPayments { payments: Vec::new(), total: 0, limit });
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let offset = offset.unwrap_or(0);
+    
+    let where_clause = "WHERE user_id = ?1
+               AND merchant_name = ?2
+               AND (status_code IS NULL OR status_code != 'CANCELED')";
+    
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM tbl_coupang_payment {}", where_clause),
+            rusqlite::params![user_id, merchant_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let payments = collect_coupang_payments(
+        &conn,
+        &format!(
+            "SELECT {} FROM tbl_coupang_payment {} ORDER BY ordered_at DESC, id DESC LIMIT ?3 OFFSET ?4",
+            COUPANG_PAYMENT_LIST_COLUMNS, where_clause
+        ),
+        rusqlite::params![user_id, merchant_name, limit, offset],
+    )?;
+    
+    Ok(PaginatedCoupangPayments { payments, total, limit })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CountryStat {
+    merchant_country_code: Option<String>,
+    merchant_country_name: Option<String>,
+    count: i64,
+    total_amount: i64,
+}
+
+// 네이버 결제 가맹점 국가별 건수/금액 (국가 코드가 없는 국내 결제는 KR로 묶음)
+#[tauri::command]
+fn get_naver_country_breakdown(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Vec<CountryStat>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(merchant_country_code, 'KR') AS country_code, MAX(merchant_country_name),
+                    COUNT(*), COALESCE(SUM(total_amount), 0) AS total
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+             GROUP BY country_code
+             ORDER BY total DESC, country_code",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([user_id], |row| {
+            Ok(CountryStat {
+                merchant_country_code: row.get(0)?,
+                merchant_country_name: row.get(1)?,
+                count: row.get(2)?,
+                total_amount: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut stats = Vec::new();
+    for row in rows {
+        stats.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(stats)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CountrySpend {
+    country_code: String,
+    country_name: String,
+    total: i64,
+    count: i64,
+}
+
+// 국가별 지출 합계 (get_naver_country_breakdown과 같은 집계, 이름이 없는 KR 그룹은 국내/미상으로 표시)
+#[tauri::command]
+fn get_spend_by_country(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Vec<CountrySpend>, String> {
+    let stats = get_naver_country_breakdown(app_handle, state, user_id)?;
+    Ok(stats
+        .into_iter()
+        .map(|stat| {
+            let country_code = stat.merchant_country_code.unwrap_or_else(|| "KR".to_string());
+            let country_name = match stat.merchant_country_name {
+                Some(name) => name,
+                None if country_code == "KR" => "국내/미상".to_string(),
+                None => country_code.clone(),
+            };
+            CountrySpend {
+                country_code,
+                country_name,
+                total: stat.total_amount,
+                count: stat.count,
+            }
+        })
+        .collect())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PurchaseFrequency {
+    merchant_name: String,
+    first_purchase_date: String,
+    last_purchase_date: String,
+    total_purchases: i64,
+    unique_months: i64,
+    avg_days_between_purchases: f64,
+}
+
+// 네이버 가맹점별 재구매 빈도 (첫/마지막 구매일, 구매한 달 수, 평균 구매 간격)
+#[tauri::command]
+fn get_naver_purchase_frequency(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Vec<PurchaseFrequency>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT merchant_name, MIN(paid_at), MAX(paid_at), COUNT(*) AS total,
+                    COUNT(DISTINCT strftime('%Y-%m', paid_at))
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+             GROUP BY merchant_name
+             ORDER BY total DESC, merchant_name",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([user_id], |row| {
+            let first_purchase_date: String = row.get(1)?;
+            let last_purchase_date: String = row.get(2)?;
+            let total_purchases: i64 = row.get(3)?;
+            
+            // 구매가 한 번뿐이면 간격 없음 (0.0)
+            let parse_date = |value: &str| {
+                chrono::NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+            };
+            let avg_days_between_purchases = match (parse_date(&first_purchase_date), parse_date(&last_purchase_date)) {
+                (Some(first), Some(last)) if total_purchases > 1 => {
+                    (last - first).num_days() as f64 / (total_purchases - 1) as f64
+                }
+                _ => 0.0,
+            };
+            
+            Ok(PurchaseFrequency {
+                merchant_name: row.get(0)?,
+                first_purchase_date,
+                last_purchase_date,
+                total_purchases,
+                unique_months: row.get(4)?,
+                avg_days_between_purchases,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut frequencies = Vec::new();
+    for row in rows {
+        frequencies.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(frequencies)
+}
+
+// 이름에 맞는 가맹점 규칙의 카테고리 (대소문자 무시, exact > prefix > contains 순, 같은 종류는 priority 높은 순)
+fn merchant_rule_category(conn: &Connection, name: &str) -> Result<Option<String>, String> {
+    match conn.query_row(
+        "SELECT category FROM tbl_merchant_rule
+         WHERE (match_type = 'exact' AND LOWER(pattern) = LOWER(?1))
+            OR (match_type = 'prefix' AND substr(LOWER(?1), 1, length(pattern)) = LOWER(pattern))
+            OR (match_type = 'contains' AND instr(LOWER(?1), LOWER(pattern)) > 0)
+         ORDER BY CASE match_type WHEN 'exact' THEN 0 WHEN 'prefix' THEN 1 ELSE 2 END,
+                  priority DESC, length(pattern) DESC, created_at
+         LIMIT 1",
+        [name],
+        |row| row.get(0),
+    ) {
+        Ok(category) => Ok(Some(category)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// 네이버 결제의 추천 카테고리 (가맹점 이름 규칙 우선, 없으면 상품명으로 다시 매칭)
+#[tauri::command]
+fn get_naver_payment_category_guess(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    merchant_name: String,
+    product_name: Option<String>,
+) -> Result<Option<String>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    if let Some(category) = merchant_rule_category(&conn, &merchant_name)? {
+        return Ok(Some(category));
+    }
+    match product_name.as_deref().map(str::trim) {
+        Some(product_name) if !product_name.is_empty() => merchant_rule_category(&conn, product_name),
+        _ => Ok(None),
+    }
+}
+
+// 가맹점 카테고리 규칙
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MerchantRule {
+    id: String,
+    pattern: String,
+    match_type: String,
+    category: String,
+    priority: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+// 규칙 입력값 정리 (패턴/카테고리 앞뒤 공백 제거)
+fn normalize_merchant_rule_input(
+    pattern: &str,
+    match_type: &str,
+    category: &str,
+) -> Result<(String, String), String> {
+    if !matches!(match_type, "exact" | "prefix" | "contains") {
+        return Err(format!("지원하지 않는 규칙 방식입니다: {}", match_type));
+    }
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("규칙 패턴이 비어 있습니다.".to_string());
+    }
+    let category = category.trim();
+    if category.is_empty() {
+        return Err("카테고리가 비어 있습니다.".to_string());
+    }
+    Ok((pattern.to_string(), category.to_string()))
+}
+
+#[tauri::command]
+fn create_merchant_rule(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    pattern: String,
+    match_type: String,
+    category: String,
+    priority: Option<i64>,
+) -> Result<MerchantRule, String> {
+    let (pattern, category) = normalize_merchant_rule_input(&pattern, &match_type, &category)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let priority = priority.unwrap_or(0);
+    conn.execute(
+        "INSERT INTO tbl_merchant_rule (id, pattern, match_type, category, priority, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+        rusqlite::params![id, pattern, match_type, category, priority, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(MerchantRule {
+        id,
+        pattern,
+        match_type,
+        category,
+        priority,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+#[tauri::command]
+fn update_merchant_rule(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    rule_id: String,
+    pattern: String,
+    match_type: String,
+    category: String,
+    priority: Option<i64>,
+) -> Result<MerchantRule, String> {
+    let (pattern, category) = normalize_merchant_rule_input(&pattern, &match_type, &category)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let now = Utc::now().to_rfc3339();
+    let priority = priority.unwrap_or(0);
+    let created_at: String = conn
+        .query_row(
+            "UPDATE tbl_merchant_rule
+             SET pattern = ?1, match_type = ?2, category = ?3, priority = ?4, updated_at = ?5
+             WHERE id = ?6
+             RETURNING created_at",
+            rusqlite::params![pattern, match_type, category, priority, now, rule_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => "가맹점 규칙을 찾을 수 없습니다.".to_string(),
+            e => e.to_string(),
+        })?;
+    
+    Ok(MerchantRule {
+        id: rule_id,
+        pattern,
+        match_type,
+        category,
+        priority,
+        created_at,
+        updated_at: now,
+    })
+}
+
+#[tauri::command]
+fn delete_merchant_rule(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    rule_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let deleted = conn
+        .execute("DELETE FROM tbl_merchant_rule WHERE id = ?1", [&rule_id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err("가맹점 규칙을 찾을 수 없습니다.".to_string());
+    }
+    Ok(())
+}
+
+// 적용 순서대로 정렬 (종류 exact > prefix > contains, 같은 종류는 priority 높은 순)
+#[tauri::command]
+fn list_merchant_rules(app_handle: AppHandle, state: State<AppState>) -> Result<Vec<MerchantRule>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, pattern, match_type, category, priority, created_at, updated_at
+             FROM tbl_merchant_rule
+             ORDER BY CASE match_type WHEN 'exact' THEN 0 WHEN 'prefix' THEN 1 ELSE 2 END,
+                      priority DESC, length(pattern) DESC, created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(MerchantRule {
+                id: row.get(0)?,
+                pattern: row.get(1)?,
+                match_type: row.get(2)?,
+                category: row.get(3)?,
+                priority: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut rules = Vec::new();
+    for row in rows {
+        rules.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(rules)
+}
+
+// 사용자의 결제에서 변환된 해당 월 가계부 항목 중 미분류(기본 카테고리) 항목에 가맹점 규칙 적용
+// 가맹점 이름으로 먼저 매칭하고 없으면 제목(상품명)으로 매칭, 잠긴 가계부 계정의 항목은 건너뜀
+#[tauri::command]
+fn apply_merchant_rules_to_pending(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    year_month: String,
+) -> Result<u32, String> {
+    validate_year_month(&year_month)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    
+    let entries = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant,
+                        payment_method, memo, color, created_at, updated_at
+                 FROM tbl_ledger_entry
+                 WHERE date LIKE ?2
+                   AND (category = '' OR category = ?3)
+                   AND (
+                       (source_provider = 'naver'
+                        AND source_payment_id IN (SELECT id FROM tbl_naver_payment WHERE user_id = ?1))
+                       OR (source_provider = 'coupang'
+                        AND source_payment_id IN (SELECT id FROM tbl_coupang_payment WHERE user_id = ?1))
+                   )
+                 ORDER BY date, created_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let date_pattern = format!("{}%", year_month);
+        let rows = stmt
+            .query_map(
+                rusqlite::params![user_id, date_pattern, PAYMENT_LEDGER_CATEGORY],
+                ledger_entry_from_row,
+            )
+            .map_err(|e| e.to_string())?;
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| e.to_string())?);
+        }
+        entries
+    };
+    
+    let now = Utc::now().to_rfc3339();
+    let mut changed = 0u32;
+    
+    for mut entry in entries {
+        if ensure_ledger_unlocked(&tx, &state, &entry.account_id).is_err() {
+            continue;
+        }
+        let mut new_category = match entry.merchant.as_deref() {
+            Some(merchant) => merchant_rule_category(&tx, merchant)?,
+            None => None,
+        };
+        if new_category.is_none() {
+            new_category = merchant_rule_category(&tx, &entry.title)?;
+        }
+        let Some(new_category) = new_category.filter(|category| *category != entry.category) else {
+            continue;
+        };
+        
+        let snapshot_before: Option<String> = tx
+            .query_row(
+                "SELECT json_object(
+                    'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                    'date', date, 'title', title, 'category', category, 'platform', platform,
+                    'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                    'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+                ) FROM tbl_ledger_entry WHERE id = ?1",
+                [&entry.id],
+                |row| row.get(0),
+            )
+            .ok();
+        
+        tx.execute(
+            "UPDATE tbl_ledger_entry SET category = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![new_category, now, entry.id],
+        )
+        .map_err(|e| e.to_string())?;
+        
+        // 히스토리 기록
+        entry.category = new_category;
+        entry.tags = load_ledger_tags(&tx, &entry.id)?;
+        entry.updated_at = now.clone();
+        let snapshot_after = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let history_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+             VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
+            rusqlite::params![history_id, entry.id, snapshot_before, snapshot_after, now],
+        )
+        .map_err(|e| e.to_string())?;
+        changed += 1;
+    }
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(changed)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusCount {
+    status_code: Option<String>,
+    status_text: Option<String>,
+    count: i64,
+    total_amount: i64,
+}
+
+// 네이버 결제 상태별 건수/금액 (목록 필터에 포함할 상태 코드 확인 및 동기화 디버깅용)
+#[tauri::command]
+fn get_naver_payment_count_by_status(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Vec<StatusCount>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT status_code, status_text, COUNT(*) AS count, COALESCE(SUM(total_amount), 0)
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+             GROUP BY status_code, status_text
+             ORDER BY count DESC, status_code",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([user_id], |row| {
+            Ok(StatusCount {
+                status_code: row.get(0)?,
+                status_text: row.get(1)?,
+                count: row.get(2)?,
+                total_amount: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut counts = Vec::new();
+    for row in rows {
+        counts.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(counts)
+}
+
+// 과세 유형(is_tax_type) 여부로 네이버 결제 목록 조회 (is_tax_type이 비어 있으면 비과세로 취급, 날짜는 양 끝 포함)
+#[tauri::command]
+fn get_naver_tax_type_payments(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    is_tax_type: bool,
+) -> Result<Vec<NaverPaymentListItem>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    collect_naver_payments(
+        &conn,
+        &format!(
+            "SELECT {} FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND COALESCE(is_tax_type, 0) = ?2
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND (?3 IS NULL OR substr(paid_at, 1, 10) >= ?3)
+               AND (?4 IS NULL OR substr(paid_at, 1, 10) <= ?4)
+             ORDER BY paid_at DESC, id DESC",
+            NAVER_PAYMENT_LIST_COLUMNS
+        ),
+        rusqlite::params![user_id, is_tax_type, from_date, to_date],
+    )
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NaverTaxSummary {
+    tax_type_total: i64,
+    non_tax_total: i64,
+    tax_type_count: i64,
+    non_tax_count: i64,
+}
+
+// 연도별 과세 유형/그 외 결제 합계 (사업 경비 분리용)
+#[tauri::command]
+fn get_naver_tax_summary(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    year: i32,
+) -> Result<NaverTaxSummary, String> {
+    if !(1..=9999).contains(&year) {
+        return Err(format!("유효하지 않은 연도입니다: {}", year));
+    }
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(NaverTaxSummary {
+            tax_type_total: 0,
+            non_tax_total: 0,
+            tax_type_count: 0,
+            non_tax_count: 0,
+        });
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    conn.query_row(
+        "SELECT
+            COALESCE(SUM(CASE WHEN is_tax_type = 1 THEN total_amount ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN is_tax_type = 1 THEN 0 ELSE total_amount END), 0),
+            COALESCE(SUM(CASE WHEN is_tax_type = 1 THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN is_tax_type = 1 THEN 0 ELSE 1 END), 0)
+         FROM tbl_naver_payment
+         WHERE user_id = ?1
+           AND substr(paid_at, 1, 4) = ?2
+           AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')",
+        rusqlite::params![user_id, format!("{:04}", year)],
+        |row| {
+            Ok(NaverTaxSummary {
+                tax_type_total: row.get(0)?,
+                non_tax_total: row.get(1)?,
+                tax_type_count: row.get(2)?,
+                non_tax_count: row.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NaverPlusStats {
+    plus_order_count: i64,
+    plus_total_amount: i64,
+    non_plus_order_count: i64,
+    non_plus_total_amount: i64,
+    plus_benefit_earned: i64,
+    plus_benefit_ratio: f64,
+}
+
+// 네이버플러스 멤버십 가입 상태로 결제한 주문과 그 외 주문 비교 통계
+#[tauri::command]
+fn get_naver_plus_stats(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<NaverPlusStats, String> {
+    let empty = NaverPlusStats {
+        plus_order_count: 0,
+        plus_total_amount: 0,
+        non_plus_order_count: 0,
+        non_plus_total_amount: 0,
+        plus_benefit_earned: 0,
+        plus_benefit_ratio: 0.0,
+    };
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(empty);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    // 날짜 범위는 YYYY-MM-DD 기준 (양 끝 포함)
+    let mut stats = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN has_plus_membership = 1 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN has_plus_membership = 1 THEN total_amount ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN has_plus_membership = 1 THEN 0 ELSE 1 END), 0),
+                COALESCE(SUM(CASE WHEN has_plus_membership = 1 THEN 0 ELSE total_amount END), 0),
+                COALESCE(SUM(CASE WHEN has_plus_membership = 1 THEN COALESCE(benefit_amount, 0) ELSE 0 END), 0)
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND (?2 IS NULL OR substr(paid_at, 1, 10) >= ?2)
+               AND (?3 IS NULL OR substr(paid_at, 1, 10) <= ?3)",
+            rusqlite::params![user_id, from_date, to_date],
+            |row| {
+                Ok(NaverPlusStats {
+                    plus_order_count: row.get(0)?,
+                    plus_total_amount: row.get(1)?,
+                    non_plus_order_count: row.get(2)?,
+                    non_plus_total_amount: row.get(3)?,
+                    plus_benefit_earned: row.get(4)?,
+                    plus_benefit_ratio: 0.0,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    if stats.plus_total_amount > 0 {
+        stats.plus_benefit_ratio =
+            stats.plus_benefit_earned as f64 / stats.plus_total_amount as f64 * 100.0;
+    }
+    
+    Ok(stats)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BenefitComparison {
+    total_expected: i64,
+    total_received: i64,
+    pending_count: i64,
+    waiting_period_avg_days: f64,
+    materialization_rate_pct: f64,
+}
+
+// 네이버페이 적립 예정 금액 대비 실제 적립 금액 비교
+#[tauri::command]
+fn get_naver_benefit_comparison(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<BenefitComparison, String> {
+    let empty = BenefitComparison {
+        total_expected: 0,
+        total_received: 0,
+        pending_count: 0,
+        waiting_period_avg_days: 0.0,
+        materialization_rate_pct: 0.0,
+    };
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(empty);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    // 날짜 범위는 YYYY-MM-DD 기준 (양 끝 포함)
+    let mut comparison = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(COALESCE(benefit_expected_amount, 0)), 0),
+                COALESCE(SUM(COALESCE(benefit_amount, 0)), 0),
+                COALESCE(SUM(CASE WHEN COALESCE(benefit_expected_amount, 0) > COALESCE(benefit_amount, 0) THEN 1 ELSE 0 END), 0),
+                COALESCE(AVG(benefit_waiting_period), 0.0)
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND (?2 IS NULL OR substr(paid_at, 1, 10) >= ?2)
+               AND (?3 IS NULL OR substr(paid_at, 1, 10) <= ?3)",
+            rusqlite::params![user_id, from_date, to_date],
+            |row| {
+                Ok(BenefitComparison {
+                    total_expected: row.get(0)?,
+                    total_received: row.get(1)?,
+                    pending_count: row.get(2)?,
+                    waiting_period_avg_days: row.get(3)?,
+                    materialization_rate_pct: 0.0,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    if comparison.total_expected > 0 {
+        comparison.materialization_rate_pct =
+            comparison.total_received as f64 / comparison.total_expected as f64 * 100.0;
+    }
+    
+    Ok(comparison)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NaverCafeStats {
+    cafe_payment_count: i64,
+    cafe_total_amount: i64,
+    cafe_avg_amount: f64,
+    non_cafe_count: i64,
+    non_cafe_total: i64,
+    cafe_pct_of_total: f64,
+}
+
+// 네이버 카페 안전결제(에스크로) 결제와 그 외 결제 비교 통계 (year_month: YYYY-MM)
+#[tauri::command]
+fn get_naver_cafe_payment_stats(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    year_month: Option<String>,
+) -> Result<NaverCafeStats, String> {
+    if let Some(ym) = &year_month {
+        validate_year_month(ym)?;
+    }
+    let empty = NaverCafeStats {
+        cafe_payment_count: 0,
+        cafe_total_amount: 0,
+        cafe_avg_amount: 0.0,
+        non_cafe_count: 0,
+        non_cafe_total: 0,
+        cafe_pct_of_total: 0.0,
+    };
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(empty);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stats = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN is_cafe_safe_payment = 1 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN is_cafe_safe_payment = 1 THEN total_amount ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN is_cafe_safe_payment = 1 THEN 0 ELSE 1 END), 0),
+                COALESCE(SUM(CASE WHEN is_cafe_safe_payment = 1 THEN 0 ELSE total_amount END), 0)
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND (?2 IS NULL OR substr(paid_at, 1, 7) = ?2)",
+            rusqlite::params![user_id, year_month],
+            |row| {
+                Ok(NaverCafeStats {
+                    cafe_payment_count: row.get(0)?,
+                    cafe_total_amount: row.get(1)?,
+                    cafe_avg_amount: 0.0,
+                    non_cafe_count: row.get(2)?,
+                    non_cafe_total: row.get(3)?,
+                    cafe_pct_of_total: 0.0,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    if stats.cafe_payment_count > 0 {
+        stats.cafe_avg_amount = stats.cafe_total_amount as f64 / stats.cafe_payment_count as f64;
+    }
+    let total = stats.cafe_total_amount + stats.non_cafe_total;
+    if total > 0 {
+        stats.cafe_pct_of_total = stats.cafe_total_amount as f64 / total as f64 * 100.0;
+    }
+    
+    Ok(stats)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingBenefit {
+    pay_id: String,
+    merchant_name: String,
+    paid_at: String,
+    benefit_expected_amount: i64,
+    benefit_amount: i64,
+    pending_amount: i64,
+    benefit_waiting_period: Option<i32>,
+    // paid_at + benefit_waiting_period일 (YYYY-MM-DD)
+    estimated_release_date: Option<String>,
+}
+
+// 적립 예정 금액이 아직 다 적립되지 않은 네이버 결제 (적립 예상일 빠른 순, 예상일 없으면 마지막)
+#[tauri::command]
+fn get_naver_pending_benefits(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Vec<PendingBenefit>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT pay_id, merchant_name, paid_at, COALESCE(benefit_expected_amount, 0), COALESCE(benefit_amount, 0),
+                    benefit_waiting_period
+             FROM tbl_naver_payment
+             WHERE user_id = ?1
+               AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+               AND COALESCE(benefit_expected_amount, 0) > COALESCE(benefit_amount, 0)",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([user_id], |row| {
+            let paid_at: String = row.get(2)?;
+            let benefit_expected_amount: i64 = row.get(3)?;
+            let benefit_amount: i64 = row.get(4)?;
+            let benefit_waiting_period: Option<i32> = row.get(5)?;
+            // paid_at은 ISO 8601 (시간대 포함 여부가 다를 수 있으므로 날짜 부분만 사용)
+            let estimated_release_date = benefit_waiting_period.and_then(|days| {
+                let paid_date = chrono::NaiveDate::parse_from_str(paid_at.get(..10)?, "%Y-%m-%d").ok()?;
+                let release = paid_date.checked_add_signed(chrono::Duration::days(days as i64))?;
+                Some(release.format("%Y-%m-%d").to_string())
+            });
+            Ok(PendingBenefit {
+                pay_id: row.get(0)?,
+                merchant_name: row.get(1)?,
+                paid_at,
+                benefit_expected_amount,
+                benefit_amount,
+                pending_amount: benefit_expected_amount - benefit_amount,
+                benefit_waiting_period,
+                estimated_release_date,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut benefits = Vec::new();
+    for row in rows {
+        benefits.push(row.map_err(|e| e.to_string())?);
+    }
+    // 예상일 오름차순, 예상일이 없는 항목은 결제일 순으로 마지막에
+    benefits.sort_by(|a, b| match (&a.estimated_release_date, &b.estimated_release_date) {
+        (Some(x), Some(y)) => x.cmp(y).then_with(|| a.paid_at.cmp(&b.paid_at)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.paid_at.cmp(&b.paid_at),
+    });
+    
+    Ok(benefits)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeBucket {
+    bucket: i64,
+    order_count: i64,
+    total_amount: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpendingByTime {
+    // 0 = 일요일 ~ 6 = 토요일
+    by_weekday: Vec<TimeBucket>,
+    by_hour: Vec<TimeBucket>,
+}
+
+// 요일/시간대별 결제 건수와 금액 (provider 미지정 시 네이버+쿠팡 합산, 시간대 기본값은 KST)
+#[tauri::command]
+fn get_spending_by_time(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: Option<String>,
+    provider: Option<String>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<SpendingByTime, String> {
+    let naver_source = "SELECT paid_at AS ts, total_amount AS amount FROM tbl_naver_payment
+                        WHERE (?1 IS NULL OR user_id = ?1)
+                          AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')";
+    let coupang_source = "SELECT ordered_at AS ts, total_amount AS amount FROM tbl_coupang_payment
+                          WHERE (?1 IS NULL OR user_id = ?1)
+                            AND (status_code IS NULL OR status_code != 'CANCELED')";
+    let source = match provider.as_deref() {
+        Some("naver") => naver_source.to_string(),
+        Some("coupang") => coupang_source.to_string(),
+        None => format!("{} UNION ALL {}", naver_source, coupang_source),
+        Some(other) => return Err(format!("지원하지 않는 provider입니다: {}", other)),
+    };
+    
+    // 0으로 채운 버킷에 조회 결과를 덮어씀
+    let empty_buckets = |size: i64| -> Vec<TimeBucket> {
+        (0..size)
+            .map(|bucket| TimeBucket { bucket, order_count: 0, total_amount: 0 })
+            .collect()
+    };
+    let mut result = SpendingByTime {
+        by_weekday: empty_buckets(7),
+        by_hour: empty_buckets(24),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(result);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let tz_modifier = format!("{:+} minutes", tz_offset_minutes.unwrap_or(9 * 60));
+    
+    for (format, buckets) in [("%w", &mut result.by_weekday), ("%H", &mut result.by_hour)] {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT CAST(strftime('{}', ts, ?2) AS INTEGER) AS bucket, COUNT(*), COALESCE(SUM(amount), 0)
+                 FROM ({})
+                 WHERE strftime('{}', ts, ?2) IS NOT NULL
+                 GROUP BY bucket",
+                format, source, format
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(rusqlite::params![user_id, tz_modifier], |row| {
+                Ok(TimeBucket {
+                    bucket: row.get(0)?,
+                    order_count: row.get(1)?,
+                    total_amount: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            let row = row.map_err(|e| e.to_string())?;
+            if let Some(slot) = buckets.get_mut(row.bucket as usize) {
+                *slot = row;
+            }
+        }
+    }
+    
+    Ok(result)
+}
+
+#[tauri::command]
+fn save_coupang_payment(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    payment: CoupangPayment,
+    report_changes: Option<bool>,
+) -> Result<Option<PaymentChangeSet>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // 요청 시 덮어쓰기 전에 이전 저장본과 비교
+    let change_set = if report_changes.unwrap_or(false) {
+        let previous = load_coupang_payment_detail(&tx, &user_id, &payment.order_id)?
+            .map(|detail| serde_json::to_value(&detail.payment))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let current = serde_json::to_value(&payment).map_err(|e| e.to_string())?;
+        Some(diff_payment_json(previous.as_ref(), &current))
+    } else {
+        None
+    };
+
+    {
+        let now = Utc::now().to_rfc3339();
+        
+        // 1. 결제 정보 저장 (UPSERT)
+        tx.execute(
+            "INSERT INTO tbl_coupang_payment (
+                user_id, order_id, external_id, status_code, status_text, status_color,
+                ordered_at, paid_at, merchant_name, merchant_tel, merchant_url, merchant_image_url,
+                product_name, product_count, product_detail_url, order_detail_url,
+                total_amount, total_order_amount, total_cancel_amount, discount_amount, rest_amount,
+                main_pay_type, pay_rocket_balance_amount, pay_card_amount, pay_coupon_amount,
+                pay_coupang_cash_amount, pay_rocket_bank_amount, wow_instant_discount, reward_cash_amount,
+                created_at, updated_at, raw_json
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32
+            )
+            ON CONFLICT(user_id, order_id) DO UPDATE SET
+                external_id = excluded.external_id,
+                status_code = excluded.status_code,
+                status_text = excluded.status_text,
+                status_color = excluded.status_color,
+                ordered_at = excluded.ordered_at,
+                paid_at = excluded.paid_at,
+                merchant_name = excluded.merchant_name,
+                merchant_tel = excluded.merchant_tel,
+                merchant_url = excluded.merchant_url,
+                merchant_image_url = excluded.merchant_image_url,
+                product_name = excluded.product_name,
+                product_count = excluded.product_count,
+                product_detail_url = excluded.product_detail_url,
+                order_detail_url = excluded.order_detail_url,
+                total_amount = excluded.total_amount,
+                total_order_amount = excluded.total_order_amount,
+                total_cancel_amount = excluded.total_cancel_amount,
+                discount_amount = excluded.discount_amount,
+                rest_amount = excluded.rest_amount,
+                main_pay_type = excluded.main_pay_type,
+                pay_rocket_balance_amount = excluded.pay_rocket_balance_amount,
+                pay_card_amount = excluded.pay_card_amount,
+                pay_coupon_amount = excluded.pay_coupon_amount,
+                pay_coupang_cash_amount = excluded.pay_coupang_cash_amount,
+                pay_rocket_bank_amount = excluded.pay_rocket_bank_amount,
+                wow_instant_discount = excluded.wow_instant_discount,
+                reward_cash_amount = excluded.reward_cash_amount,
+                updated_at = excluded.updated_at,
+                raw_json = COALESCE(excluded.raw_json, raw_json)",
+            rusqlite::params![
+                user_id, payment.order_id, payment.external_id, payment.status_code,
+                payment.status_text, payment.status_color, payment.ordered_at, payment.paid_at,
+                payment.merchant_name, payment.merchant_tel, payment.merchant_url,
+                payment.merchant_image_url, payment.product_name, payment.product_count,
+                payment.product_detail_url, payment.order_detail_url, payment.total_amount,
+                payment.total_order_amount, payment.total_cancel_amount, payment.discount_amount,
+                payment.rest_amount, payment.main_pay_type, payment.pay_rocket_balance_amount,
+                payment.pay_card_amount, payment.pay_coupon_amount, payment.pay_coupang_cash_amount,
+                payment.pay_rocket_bank_amount, payment.wow_instant_discount, payment.reward_cash_amount,
+                now, now, payment.raw.as_ref().map(|raw| raw.to_string())
+            ],
+        ).map_err(|e| e.to_string())?;
+
+        // 저장된 결제의 ID 조회
+        let payment_pk: i64 = tx.query_row(
+            "SELECT id FROM tbl_coupang_payment WHERE user_id = ?1 AND order_id = ?2",
+            rusqlite::params![&user_id, &payment.order_id],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+
+        // 2. 결제 항목 UPSERT
+        for item in payment.items {
+            tx.execute(
+                "INSERT INTO tbl_coupang_payment_item (
+                    payment_id, line_no, product_id, vendor_item_id, product_name, image_url, info_url,
+                    brand_name, quantity, unit_price, discounted_unit_price, combined_unit_price,
+                    line_amount, rest_amount, memo, created_at, updated_at
+                ) VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17
+                )
+                ON CONFLICT(payment_id, line_no) DO UPDATE SET
+                    product_id = excluded.product_id,
+                    vendor_item_id = excluded.vendor_item_id,
+                    product_name = excluded.product_name,
+                    image_url = excluded.image_url,
+                    info_url = excluded.info_url,
+                    brand_name = excluded.brand_name,
+                    quantity = excluded.quantity,
+                    unit_price = excluded.unit_price,
+                    discounted_unit_price = excluded.discounted_unit_price,
+                    combined_unit_price = excluded.combined_unit_price,
+                    line_amount = excluded.line_amount,
+                    rest_amount = excluded.rest_amount,
+                    memo = excluded.memo,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    payment_pk, item.line_no, item.product_id, item.vendor_item_id, item.product_name,
+                    item.image_url, item.info_url, item.brand_name, item.quantity, item.unit_price,
+                    item.discounted_unit_price, item.combined_unit_price, item.line_amount,
+                    item.rest_amount, item.memo, now, now
+                ],
+            ).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(change_set)
+}
+
+// 네이버 결제 상태 수동 수정 (동기화 이후 바뀐 상태 보정용)
+#[tauri::command]
+fn update_naver_payment_status(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    pay_id: String,
+    status_code: String,
+    status_text: String,
+    status_color: Option<String>,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let now = Utc::now().to_rfc3339();
+    
+    // user_id 조건으로 다른 계정의 결제는 수정되지 않도록 함
+    let updated = conn
+        .execute(
+            "UPDATE tbl_naver_payment
+             SET status_code = ?1, status_text = ?2, status_color = ?3, updated_at = ?4
+             WHERE user_id = ?5 AND pay_id = ?6",
+            rusqlite::params![status_code, status_text, status_color, now, user_id, pay_id],
+        )
+        .map_err(|e| e.to_string())?;
+    
+    if updated == 0 {
+        return Err("해당 결제 정보를 찾을 수 없습니다.".to_string());
+    }
+    
+    Ok(())
+}
+
+// 쿠팡 결제 상태 수동 수정 (동기화 이후 바뀐 상태 보정용)
+#[tauri::command]
+fn update_coupang_payment_status(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    order_id: String,
+    status_code: String,
+    status_text: String,
+    status_color: Option<String>,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let now = Utc::now().to_rfc3339();
+    
+    // user_id 조건으로 다른 계정의 결제는 수정되지 않도록 함
+    let updated = conn
+        .execute(
+            "UPDATE tbl_coupang_payment
+             SET status_code = ?1, status_text = ?2, status_color = ?3, updated_at = ?4
+             WHERE user_id = ?5 AND order_id = ?6",
+            rusqlite::params![status_code, status_text, status_color, now, user_id, order_id],
+        )
+        .map_err(|e| e.to_string())?;
+    
+    if updated == 0 {
+        return Err("해당 결제 정보를 찾을 수 없습니다.".to_string());
+    }
+    
+    Ok(())
+}
+
+// provider에 해당하는 (결제 테이블, 상세 항목 테이블) 이름 반환
+fn payment_tables(provider: &str) -> Result<(&'static str, &'static str), String> {
+    match provider {
+        "naver" => Ok(("tbl_naver_payment", "tbl_naver_payment_item")),
+        "coupang" => Ok(("tbl_coupang_payment", "tbl_coupang_payment_item")),
+        _ => Err(format!("지원하지 않는 provider입니다: {}", provider)),
+    }
+}
+
+// 결제 저장 시 함께 보관한 원본 JSON 조회 (보관하지 않았으면 None)
+#[tauri::command]
+fn get_payment_raw(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: String,
+    payment_id: i64,
+) -> Result<Option<serde_json::Value>, String> {
+    let (payment_table, _) = payment_tables(&provider)?;
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let raw_json: Option<String> = match conn.query_row(
+        &format!("SELECT raw_json FROM {} WHERE id = ?1", payment_table),
+        [payment_id],
+        |row| row.get(0),
+    ) {
+        Ok(raw_json) => raw_json,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err("해당 결제 내역을 찾을 수 없습니다.".to_string())
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    raw_json
+        .map(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemPricePoint {
+    recorded_at: String,
+    combined_unit_price: Option<i64>,
+    line_amount: Option<i64>,
+}
+
+// 결제 상세 항목의 재저장 간 가격 변화 (결제 이력을 보관하지 않으므로 항상 빈 목록)
+#[tauri::command]
+fn get_item_price_history(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: String,
+    payment_id: i64,
+    line_no: i64,
+) -> Result<Vec<ItemPricePoint>, String> {
+    let (_, item_table) = payment_tables(&provider)?;
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let exists: bool = conn
+        .query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE payment_id = ?1 AND line_no = ?2)", item_table),
+            [payment_id, line_no],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err("해당 결제 상세 항목을 찾을 수 없습니다.".to_string());
+    }
+    
+    // save_*_payment는 이전 버전을 저장하지 않으므로 기록된 가격 이력이 없음
+    Ok(Vec::new())
+}
+
+// 결제 상세 항목의 line_no를 1..N으로 다시 매김 (변경된 항목 수 반환)
+#[tauri::command]
+fn renumber_payment_items(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: String,
+    payment_id: i64,
+) -> Result<u32, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let (_, item_table) = payment_tables(&provider)?;
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    let renumbered = renumber_item_lines(&tx, item_table, payment_id)?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(renumbered)
+}
+
+// 결제의 상품 라인 번호를 1..N으로 다시 매김 (번호가 바뀐 라인 수 반환)
+fn renumber_item_lines(conn: &Connection, item_table: &str, payment_id: i64) -> Result<u32, String> {
+    let item_lines: Vec<(i64, i64)> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, line_no FROM {} WHERE payment_id = ?1 ORDER BY line_no, id",
+                item_table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([payment_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        let mut item_lines = Vec::new();
+        for row in rows {
+            item_lines.push(row.map_err(|e| e.to_string())?);
+        }
+        item_lines
+    };
+    
+    // UNIQUE(payment_id, line_no) 충돌을 피하기 위해 먼저 음수 번호로 옮긴 뒤 양수로 되돌림
+    let now = Utc::now().to_rfc3339();
+    let mut renumbered = 0;
+    for (index, (id, line_no)) in item_lines.iter().enumerate() {
+        let new_line_no = index as i64 + 1;
+        if *line_no == new_line_no {
+            conn.execute(
+                &format!("UPDATE {} SET line_no = ?1 WHERE id = ?2", item_table),
+                rusqlite::params![-new_line_no, id],
+            )
+            .map_err(|e| e.to_string())?;
+        } else {
+            renumbered += 1;
+            conn.execute(
+                &format!("UPDATE {} SET line_no = ?1, updated_at = ?2 WHERE id = ?3", item_table),
+                rusqlite::params![-new_line_no, now, id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    
+    conn.execute(
+        &format!(
+            "UPDATE {} SET line_no = -line_no WHERE payment_id = ?1 AND line_no < 0",
+            item_table
+        ),
+        [payment_id],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(renumbered)
+}
+
+// 헤더의 product_count를 실제 상품 라인 수로 재계산 (변경된 결제 수 반환)
+#[tauri::command]
+fn recalc_product_counts(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+    provider: String,
+) -> Result<u32, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let (payment_table, item_table) = payment_tables(&provider)?;
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let changed = update_product_counts(&conn, payment_table, item_table, &user_id)?;
+    
+    tracing::info!(provider = %provider, changed, "product counts recalculated");
+    Ok(changed)
+}
+
+// 사용자의 결제 product_count를 상품 라인 수와 맞춤 (변경된 결제 수 반환)
+fn update_product_counts(
+    conn: &Connection,
+    payment_table: &str,
+    item_table: &str,
+    user_id: &str,
+) -> Result<u32, String> {
+    let sql = format!(
+        "UPDATE {payment} AS p
+         SET product_count = (SELECT COUNT(*) FROM {item} i WHERE i.payment_id = p.id)
+         WHERE p.user_id = ?1
+           AND p.product_count IS NOT (SELECT COUNT(*) FROM {item} i WHERE i.payment_id = p.id)",
+        payment = payment_table,
+        item = item_table,
+    );
+    let changed = conn.execute(&sql, [user_id]).map_err(|e| e.to_string())?;
+    Ok(changed as u32)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResultItem {
+    id: i64,
+    provider: String,
+    product_name: String,
+    image_url: Option<String>,
+    merchant_name: String,
+    paid_at: String,
+    quantity: i64,
+    unit_price: Option<i64>,
+    line_amount: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    items: Vec<SearchResultItem>,
+    total: i64,
+}
+
+#[tauri::command]
+fn search_products(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    query: String,
+    limit: Option<i64>,
+    search_field: Option<Vec<String>>,
+) -> Result<SearchResponse, String> {
+    // 검색 대상 컬럼 (미지정 시 상품명만), 여러 개면 OR로 결합
+    let mut columns: Vec<&str> = Vec::new();
+    for field in search_field.unwrap_or_default() {
+        let column = match field.as_str() {
+            "product_name" => "i.product_name",
+            "memo" => "i.memo",
+            "merchant_name" => "p.merchant_name",
+            _ => return Err(format!("지원하지 않는 검색 필드입니다: {}", field)),
+        };
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    if columns.is_empty() {
+        columns.push("i.product_name");
+    }
+    let match_clause = columns
+        .iter()
+        .map(|column| format!("{} LIKE ?1", column))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(SearchResponse { items: vec![], total: 0 });
+    }
+    
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let search_term = format!("%{}%", query);
+    let result_limit = clamp_limit(limit, 50, MAX_LIST_LIMIT);
+    
+    let mut items = Vec::new();
+    
+    // 네이버 결제 항목 검색 (실제 거래만: 구매확정, 결제완료, 배송완료, 구매확정연장)
+    let mut naver_stmt = conn.prepare(&format!(
+        "SELECT i.id, i.product_name, i.image_url, p.merchant_name, p.paid_at, 
+                i.quantity, i.unit_price, i.line_amount
+         FROM tbl_naver_payment_item i
+         JOIN tbl_naver_payment p ON i.payment_id = p.id
+         WHERE ({})
+           AND p.status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+         ORDER BY p.paid_at DESC, i.id DESC
+         LIMIT ?2",
+        match_clause
+    )).map_err(|e| e.to_string())?;
+    
+    let naver_rows = naver_stmt.query_map(rusqlite::params![&search_term, result_limit], |row| {
+        Ok(SearchResultItem {
+            id: row.get(0)?,
+            provider: "naver".to_string(),
+            product_name: row.get(1)?,
+            image_url: row.get(2)?,
+            merchant_name: row.get(3)?,
+            paid_at: row.get(4)?,
+            quantity: row.get(5)?,
+            unit_price: row.get(6)?,
+            line_amount: row.get(7)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    
+    for row in naver_rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    // 쿠팡 결제 항목 검색 (CANCELED 상태 제외)
+    let mut coupang_stmt = conn.prepare(&format!(
+        "SELECT i.id, i.product_name, i.image_url, p.merchant_name, p.ordered_at,
+                i.quantity, i.unit_price, i.line_amount
+         FROM tbl_coupang_payment_item i
+         JOIN tbl_coupang_payment p ON i.payment_id = p.id
+         WHERE ({})
+           AND (p.status_code IS NULL OR p.status_code != 'CANCELED')
+         ORDER BY p.ordered_at DESC, i.id DESC
+         LIMIT ?2",
+        match_clause
+    )).map_err(|e| e.to_string())?;
+    
+    let coupang_rows = coupang_stmt.query_map(rusqlite::params![&search_term, result_limit], |row| {
+        Ok(SearchResultItem {
+            id: row.get(0)?,
+            provider: "coupang".to_string(),
+            product_name: row.get(1)?,
+            image_url: row.get(2)?,
+            merchant_name: row.get(3)?,
+            paid_at: row.get(4)?,
+            quantity: row.get(5)?,
+            unit_price: row.get(6)?,
+            line_amount: row.get(7)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    
+    for row in coupang_rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    // 날짜순 정렬
+    items.sort_by(|a, b| b.paid_at.cmp(&a.paid_at));
+    
+    let total = items.len() as i64;
+    
+    // limit 적용
+    if items.len() > result_limit as usize {
+        items.truncate(result_limit as usize);
+    }
+    
+    Ok(SearchResponse { items, total })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentActivity {
+    kind: String,
+    title: String,
+    amount: Option<i64>,
+    timestamp: String,
+}
+
+// 결제/가계부/상품 메타데이터 변경을 하나의 타임라인으로 조회
+#[tauri::command]
+fn get_recent_activity(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    limit: Option<i64>,
+) -> Result<Vec<RecentActivity>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let result_limit = clamp_limit(limit, 20, MAX_LIST_LIMIT);
+    
+    // 잠긴 계정의 가계부 항목은 제외 (?2: 열려 있는 계정 ID 목록 JSON)
+    check_and_reset_expired_passwords(&conn)?;
+    let open_account_ids = open_ledger_account_ids_json(&conn, &state)?;
+    
+    // 각 소스에서 최근 N건씩만 가져온 뒤 합쳐서 다시 N건으로 자름
+    // 소스마다 시각 형식(오프셋 포함 ISO, UTC RFC3339, datetime('now'))이 달라 julianday로 변환해 정렬
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, title, amount, ts FROM (
+                SELECT * FROM (
+                    SELECT 'naver_payment' AS kind, COALESCE(product_name, merchant_name) AS title,
+                           total_amount AS amount, paid_at AS ts
+                    FROM tbl_naver_payment
+                    WHERE status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+                    ORDER BY paid_at DESC, id DESC
+                    LIMIT ?1
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 'coupang_payment', COALESCE(product_name, merchant_name), total_amount, ordered_at
+                    FROM tbl_coupang_payment
+                    WHERE status_code IS NULL OR status_code != 'CANCELED'
+                    ORDER BY ordered_at DESC, id DESC
+                    LIMIT ?1
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 'ledger_entry', title, amount, created_at
+                    FROM tbl_ledger_entry
+                    WHERE account_id IN (SELECT value FROM json_each(?2))
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT ?1
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 'product_meta',
+                           COALESCE(
+                               CASE m.provider
+                                   WHEN 'naver' THEN (SELECT product_name FROM tbl_naver_payment_item WHERE id = m.item_id)
+                                   WHEN 'coupang' THEN (SELECT product_name FROM tbl_coupang_payment_item WHERE id = m.item_id)
+                               END,
+                               m.provider || ' #' || m.item_id
+                           ),
+                           NULL, m.updated_at
+                    FROM tbl_product_meta m
+                    ORDER BY m.updated_at DESC, m.id DESC
+                    LIMIT ?1
+                )
+             )
+             ORDER BY julianday(ts) DESC, kind, title
+             LIMIT ?1"
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![result_limit, open_account_ids], |row| {
+            Ok(RecentActivity {
+                kind: row.get(0)?,
+                title: row.get(1)?,
+                amount: row.get(2)?,
+                timestamp: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut activities = Vec::new();
+    for row in rows {
+        activities.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(activities)
+}
+
+#[tauri::command]
+fn get_last_naver_payment(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Option<NaverLatestPayment>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT pay_id, paid_at 
+             FROM tbl_naver_payment 
+             WHERE user_id = ?1 
+             ORDER BY paid_at DESC, id DESC 
+             LIMIT 1",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt
+        .query(rusqlite::params![user_id])
+        .map_err(|e| e.to_string())?;
+    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        Ok(Some(NaverLatestPayment {
+            pay_id: row.get(0).map_err(|e| e.to_string())?,
+            paid_at: row.get(1).map_err(|e| e.to_string())?,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+fn get_last_coupang_payment(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    user_id: String,
+) -> Result<Option<CoupangLatestPayment>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT order_id, ordered_at 
+             FROM tbl_coupang_payment 
+             WHERE user_id = ?1 
+             ORDER BY ordered_at DESC, id DESC 
+             LIMIT 1",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt
+        .query(rusqlite::params![user_id])
+        .map_err(|e| e.to_string())?;
+    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        Ok(Some(CoupangLatestPayment {
+            order_id: row.get(0).map_err(|e| e.to_string())?,
+            ordered_at: row.get(1).map_err(|e| e.to_string())?,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+// 모든 사용자의 최근 결제 1건씩 한 번에 조회
+#[tauri::command]
+fn get_latest_payments_all_users(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<UserLatestPayment>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "WITH ranked AS (
+                SELECT user_id, pay_id AS payment_id, paid_at AS payment_date, total_amount,
+                       ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY paid_at DESC, id DESC) AS row_num
+                FROM tbl_naver_payment
+                UNION ALL
+                SELECT user_id, order_id AS payment_id, ordered_at AS payment_date, total_amount,
+                       ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY ordered_at DESC, id DESC) AS row_num
+                FROM tbl_coupang_payment
+             )
+             SELECT r.user_id, u.alias, u.provider, r.payment_date, r.payment_id, r.total_amount
+             FROM ranked r
+             INNER JOIN tbl_user u ON u.id = r.user_id
+             WHERE r.row_num = 1
+             ORDER BY r.payment_date DESC, r.user_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(UserLatestPayment {
+                user_id: row.get(0)?,
+                user_alias: row.get(1)?,
+                provider: row.get(2)?,
+                last_payment_date: row.get(3)?,
+                last_payment_id: row.get(4)?,
+                last_payment_amount: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut payments = Vec::new();
+    for row in rows {
+        payments.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(payments)
+}
+
+// 런타임 로그 레벨 변경 (trace, debug, info, warn, error, off)
+#[tauri::command]
+fn set_log_level(level: String) -> Result<(), String> {
+    let filter: LevelFilter = level
+        .parse()
+        .map_err(|_| format!("유효하지 않은 로그 레벨입니다: {}", level))?;
+    let handle = LOG_LEVEL_HANDLE
+        .get()
+        .ok_or_else(|| "로거가 초기화되지 않았습니다.".to_string())?;
+    handle.modify(|current| *current = filter).map_err(|e| e.to_string())?;
+    tracing::info!(level = %filter, "log level changed");
+    Ok(())
+}
+
+// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+#[tauri::command]
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+// URL, 메서드, 헤더, 바디를 curl 핸들에 설정하고 실제 전송된 요청 헤더 목록을 반환
+fn prepare_request(
+    easy: &mut Easy,
+    url: &str,
+    method: &str,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+) -> Result<Vec<String>, String> {
+    // 쿼리스트링/헤더 값에 토큰이나 쿠키가 포함될 수 있으므로 경로와 헤더 이름만 기록
+    tracing::debug!(
+        method,
+        url = url.split('?').next().unwrap_or_default(),
+        header_names = ?headers.keys().collect::<Vec<_>>(),
+        "preparing request"
+    );
+    easy.url(url).map_err(|e| e.to_string())?;
+    easy.follow_location(true).map_err(|e| e.to_string())?;
+    easy.accept_encoding("").map_err(|e| e.to_string())?;
+
+    easy.cookie_file("").map_err(|e| e.to_string())?; // enable cookie engine in memory
+
+    let payload_bytes = body.map(|b| b.into_bytes());
+
+    match method {
+        "POST" => {
+            easy.post(true).map_err(|e| e.to_string())?;
+            if let Some(ref bytes) = payload_bytes {
+                easy.post_fields_copy(bytes).map_err(|e| e.to_string())?;
+            }
+        }
+        "PUT" => {
+            easy.custom_request("PUT").map_err(|e| e.to_string())?;
+        }
+        "DELETE" => {
+            easy.custom_request("DELETE").map_err(|e| e.to_string())?;
+        }
+        _ => {} // GET by default
+    }
+
+    let mut header_list = List::new();
+    let mut cookie_header: Option<String> = None;
+    let mut request_headers: Vec<String> = Vec::new();
+
+    for (key, value) in headers {
+        if key.eq_ignore_ascii_case("cookie") {
+            cookie_header = Some(value);
+        } else {
+            let header_line = format!("{key}: {value}");
+            header_list
+                .append(&header_line)
+                .map_err(|e| e.to_string())?;
+            request_headers.push(header_line);
+        }
+    }
+
+    if let Some(cookies) = cookie_header {
+        let cookie_line = format!("Cookie: {cookies}");
+        header_list
+            .append(&cookie_line)
+            .map_err(|e| e.to_string())?;
+        request_headers.push(cookie_line);
+    }
+
+    easy.http_headers(header_list).map_err(|e| e.to_string())?;
+    Ok(request_headers)
+}
+
+const DEFAULT_REQUEST_HEADERS_KEY: &str = "default_request_headers";
+
+// tbl_setting에 저장된 proxy_request 기본 헤더 조회 (DB가 없으면 빈 값)
+fn load_default_request_headers(
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<HashMap<String, String>, String> {
+    let path = match configured_db_path(app_handle, state)? {
+        Some(path) if path.exists() => path,
+        _ => return Ok(HashMap::new()),
+    };
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM tbl_setting WHERE key = ?1",
+            [DEFAULT_REQUEST_HEADERS_KEY],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    match value {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(HashMap::new()),
+    }
+}
+
+#[tauri::command]
+fn get_default_request_headers(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<HashMap<String, String>, String> {
+    load_default_request_headers(&app_handle, &state)
+}
+
+// 모든 proxy_request에 붙일 기본 헤더 저장 (요청별 헤더가 우선)
+#[tauri::command]
+fn set_default_request_headers(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    headers: HashMap<String, String>,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let value = serde_json::to_string(&headers).map_err(|e| e.to_string())?;
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO tbl_setting (id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![Uuid::new_v4().to_string(), DEFAULT_REQUEST_HEADERS_KEY, value, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+// HMAC-SHA256 (RFC 2104), 서명 요청용
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let inner_hash = inner.finalize();
+    
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner_hash);
+    outer.finalize().into()
+}
+
+// 쿠팡 CEA 방식 Authorization 헤더 값 생성
+// 서명 대상 문자열: signed_date(yyMMddTHHmmssZ) + METHOD + path + query('?' 제외), 서명은 소문자 hex
+fn cea_authorization(
+    method: &str,
+    url: &str,
+    access_key: &str,
+    secret: &str,
+    signed_date: &str,
+) -> Result<String, String> {
+    let after_scheme = url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .ok_or_else(|| format!("서명할 URL 형식이 올바르지 않습니다: {}", url))?;
+    let path_and_query = after_scheme
+        .find('/')
+        .map(|idx| &after_scheme[idx..])
+        .unwrap_or("/");
+    let path_and_query = path_and_query.split('#').next().unwrap_or_default();
+    let (path, query) = path_and_query.split_once('?').unwrap_or((path_and_query, ""));
+    
+    let message = format!("{}{}{}{}", signed_date, method.to_uppercase(), path, query);
+    let signature: String = hmac_sha256(secret.as_bytes(), message.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!(
+        "CEA algorithm=HmacSHA256, access-key={}, signed-date={}, signature={}",
+        access_key, signed_date, signature
+    ))
+}
+
+// application/x-www-form-urlencoded 인코딩 (키 순서를 고정해 요청이 재현 가능하도록 정렬)
+fn form_urlencode(fields: &HashMap<String, String>) -> String {
+    fn encode(value: &str) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                    encoded.push(byte as char)
+                }
+                b' ' => encoded.push('+'),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+    
+    let mut pairs: Vec<(&String, &String)> = fields.iter().collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// body_type에 맞게 바디를 인코딩하고 Content-Type 지정 (요청별 Content-Type 헤더가 있으면 그 값이 우선)
+fn encode_request_body(
+    body_type: Option<&str>,
+    body: Option<String>,
+    form_fields: Option<HashMap<String, String>>,
+    headers: &mut HashMap<String, String>,
+) -> Result<Option<String>, String> {
+    let (body, content_type) = match body_type.unwrap_or("raw") {
+        "raw" => (body, None),
+        "json" => {
+            if let Some(json_body) = &body {
+                serde_json::from_str::<Value>(json_body)
+                    .map_err(|e| format!("JSON 바디 형식이 올바르지 않습니다: {}", e))?;
+            }
+            (body, Some("application/json"))
+        }
+        "form" => {
+            if body.is_some() {
+                return Err("form 바디는 body 대신 form_fields로 전달해주세요.".to_string());
+            }
+            let fields = form_fields.ok_or_else(|| "form_fields를 지정해주세요.".to_string())?;
+            (Some(form_urlencode(&fields)), Some("application/x-www-form-urlencoded"))
+        }
+        other => return Err(format!("지원하지 않는 body_type입니다: {}", other)),
+    };
+    if let Some(content_type) = content_type {
+        if !headers.keys().any(|name| name.eq_ignore_ascii_case("content-type")) {
+            headers.insert("Content-Type".to_string(), content_type.to_string());
+        }
+    }
+    Ok(body)
+}
+
+// proxy_request의 실제 전송 (블로킹, spawn_blocking 안에서 호출)
+#[allow(clippy::too_many_arguments)]
+fn perform_proxy_request(
+    url: &str,
+    method: &str,
+    merged_headers: HashMap<String, String>,
+    body: Option<String>,
+    user_agent: Option<String>,
+    follow_redirects: Option<bool>,
+    max_redirects: Option<u32>,
+    verbose: Option<bool>,
+) -> Result<ProxyResponse, String> {
+    let _span = tracing::info_span!("proxy_request", method = %method).entered();
+    let mut easy = Easy::new();
+    let request_headers = prepare_request(&mut easy, url, method, merged_headers, body)?;
+    // 요청별 User-Agent 헤더가 있으면 그 값이 우선
+    if let Some(user_agent) = &user_agent {
+        easy.useragent(user_agent).map_err(|e| e.to_string())?;
+    }
+    // 리다이렉트 응답(302 등)을 직접 확인하려면 follow_redirects = false
+    easy.follow_location(follow_redirects.unwrap_or(true))
+        .map_err(|e| e.to_string())?;
+    if let Some(max) = max_redirects {
+        easy.max_redirections(max).map_err(|e| e.to_string())?;
+    }
+
+    let mut response_body = Vec::<u8>::new();
+    let mut response_headers = Vec::<String>::new();
+    {
+        let mut transfer = easy.transfer();
+        transfer
+            .header_function(|data| {
+                if let Ok(line) = std::str::from_utf8(data) {
+                    response_headers.push(line.trim_end().to_string());
+                }
+                true
+            })
+            .map_err(|e| e.to_string())?;
+        transfer
+            .write_function(|data| {
+                response_body.extend_from_slice(data);
+                Ok(data.len())
+            })
+            .map_err(|e| e.to_string())?;
+        transfer.perform().map_err(|e| {
+            tracing::warn!(error = %e, "proxy request failed");
+            e.to_string()
+        })?;
+    }
+
+    let status = easy.response_code().map_err(|e| e.to_string())?;
+    let final_url = easy
+        .effective_url()
+        .map_err(|e| e.to_string())?
+        .map(|u| u.to_string());
+    let redirect_count = easy.redirect_count().map_err(|e| e.to_string())?;
+    tracing::info!(status, redirect_count, bytes = response_body.len(), "proxy request completed");
+    
+    // 느린 API 호출 디버깅용 단계별 시간
+    let timing = if verbose.unwrap_or(false) {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        Some(ProxyTiming {
+            namelookup_ms: ms(easy.namelookup_time().map_err(|e| e.to_string())?),
+            connect_ms: ms(easy.connect_time().map_err(|e| e.to_string())?),
+            pretransfer_ms: ms(easy.pretransfer_time().map_err(|e| e.to_string())?),
+            starttransfer_ms: ms(easy.starttransfer_time().map_err(|e| e.to_string())?),
+            total_ms: ms(easy.total_time().map_err(|e| e.to_string())?),
+        })
+    } else {
+        None
+    };
+
+    // 잘못된 UTF-8 바이트가 있으면 U+FFFD로 치환하고 표시 (한글 깨짐 디버깅용)
+    let (body, body_was_lossy) = match String::from_utf8(response_body) {
+        Ok(body) => (body, false),
+        Err(e) => {
+            tracing::warn!(valid_up_to = e.utf8_error().valid_up_to(), "proxy response body is not valid UTF-8");
+            (String::from_utf8_lossy(e.as_bytes()).into_owned(), true)
+        }
+    };
+
+    Ok(ProxyResponse {
+        status,
+        body,
+        final_url,
+        response_headers,
+        request_headers,
+        redirect_count,
+        body_was_lossy,
+        timing,
+    })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn proxy_request(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    follow_redirects: Option<bool>,
+    max_redirects: Option<i64>,
+    user_agent: Option<String>,
+    body_type: Option<String>,
+    form_fields: Option<HashMap<String, String>>,
+    sign_key: Option<String>,
+    sign_secret: Option<String>,
+    sign_algo: Option<String>,
+    verbose: Option<bool>,
+) -> Result<ProxyResponse, String> {
+    let max_redirects = max_redirects
+        .map(|max| u32::try_from(max).map_err(|_| "max_redirects는 0 이상이어야 합니다.".to_string()))
+        .transpose()?;
+    
+    // 기본 헤더 위에 요청별 헤더를 덮어씀 (헤더 이름은 대소문자 구분 없음)
+    let mut merged_headers = load_default_request_headers(&app_handle, &state)?;
+    if user_agent.is_some() {
+        merged_headers.retain(|name, _| !name.eq_ignore_ascii_case("user-agent"));
+    }
+    for (name, value) in headers {
+        merged_headers.retain(|existing, _| !existing.eq_ignore_ascii_case(&name));
+        merged_headers.insert(name, value);
+    }
+    
+    // 서명 옵션(sign_key/sign_secret)이 있으면 HMAC 서명을 Authorization 헤더로 주입 (기존 Authorization 대체)
+    if sign_key.is_some() || sign_secret.is_some() || sign_algo.is_some() {
+        let (Some(sign_key), Some(sign_secret)) = (&sign_key, &sign_secret) else {
+            return Err("서명하려면 sign_key와 sign_secret을 모두 지정해주세요.".to_string());
+        };
+        match sign_algo.as_deref().unwrap_or("hmac-sha256") {
+            "hmac-sha256" => {}
+            other => return Err(format!("지원하지 않는 sign_algo입니다: {}", other)),
+        }
+        let signed_date = Utc::now().format("%y%m%dT%H%M%SZ").to_string();
+        let authorization = cea_authorization(&method, &url, sign_key, sign_secret, &signed_date)?;
+        merged_headers.retain(|name, _| !name.eq_ignore_ascii_case("authorization"));
+        merged_headers.insert("Authorization".to_string(), authorization);
+    }
+    
+    let body = encode_request_body(body_type.as_deref(), body, form_fields, &mut merged_headers)?;
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        perform_proxy_request(
+            &url, &method, merged_headers, body, user_agent, follow_redirects, max_redirects, verbose,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 응답 바디를 JSON으로 파싱해 함께 반환 (파싱 실패는 HTTP 오류와 구분해 json_error로 전달)
+#[tauri::command]
+async fn proxy_request_json(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+) -> Result<JsonProxyResponse, String> {
+    let response = proxy_request(
+        app_handle, state, url, method, headers, body, None, None, None, None, None, None, None,
+        None, None,
+    )
+    .await?;
+    
+    let (json_body, json_error) = if response.body.trim().is_empty() {
+        (None, None)
+    } else {
+        match serde_json::from_str::<Value>(&response.body) {
+            Ok(value) => (Some(value), None),
+            Err(e) => {
+                tracing::warn!(error = %e, status = response.status, "proxy response body is not valid JSON");
+                (None, Some(e.to_string()))
+            }
+        }
+    };
+    
+    Ok(JsonProxyResponse {
+        response,
+        json_body,
+        json_error,
+    })
+}
+
+// 응답 바디를 메모리에 올리지 않고 파일로 바로 저장 (영수증/내보내기 다운로드용)
+#[tauri::command]
+async fn download_to_file(
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    target_path: String,
+) -> Result<DownloadResponse, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let _span = tracing::info_span!("download_to_file", method = %method).entered();
+        let target = PathBuf::from(target_path);
+        ensure_parent(&target)?;
+
+        let mut easy = Easy::new();
+        prepare_request(&mut easy, &url, &method, headers, body)?;
+
+        let mut file = fs::File::create(&target).map_err(|e| e.to_string())?;
+        let mut bytes_written: u64 = 0;
+        let mut write_error: Option<String> = None;
+
+        let result = (|| -> Result<(), String> {
+            let mut transfer = easy.transfer();
+            transfer
+                .write_function(|data| {
+                    match file.write_all(data) {
+                        Ok(()) => {
+                            bytes_written += data.len() as u64;
+                            Ok(data.len())
+                        }
+                        Err(e) => {
+                            // 0을 반환하면 curl이 전송을 중단함
+                            write_error = Some(e.to_string());
+                            Ok(0)
+                        }
+                    }
+                })
+                .map_err(|e| e.to_string())?;
+            transfer.perform().map_err(|e| e.to_string())
+        })()
+        .and_then(|_| file.flush().map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            // 중간에 실패하면 불완전한 파일을 남기지 않음
+            drop(file);
+            let _ = fs::remove_file(&target);
+            let message = write_error.unwrap_or(e);
+            tracing::warn!(error = %message, "download failed, partial file removed");
+            return Err(message);
+        }
+
+        let status = easy.response_code().map_err(|e| e.to_string())?;
+        let final_url = easy
+            .effective_url()
+            .map_err(|e| e.to_string())?
+            .map(|u| u.to_string());
+        tracing::info!(status, bytes_written, "download completed");
+
+        Ok(DownloadResponse {
+            status,
+            bytes_written,
+            final_url,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ========== 가계부 관련 구조체 및 함수 ==========
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerAccount {
+    id: String,
+    nickname: String,
+    password_hash: Option<String>,
+    password_expires_at: Option<String>,
+    created_at: String,
+    updated_at: String,
+    // 백업 버전 1에는 없던 필드이므로 기본값 허용
+    #[serde(default = "default_currency_symbol")]
+    currency_symbol: String,
+}
+
+const DEFAULT_CURRENCY_SYMBOL: &str = "₩";
+
+fn default_currency_symbol() -> String {
+    DEFAULT_CURRENCY_SYMBOL.to_string()
+}
+
+// SELECT id, nickname, password_hash, password_expires_at, created_at, updated_at, currency_symbol
+// 순서의 행을 LedgerAccount로 변환
+fn ledger_account_from_row(row: &rusqlite::Row) -> rusqlite::Result<LedgerAccount> {
+    Ok(LedgerAccount {
+        id: row.get(0)?,
+        nickname: row.get(1)?,
+        password_hash: row.get(2)?,
+        password_expires_at: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        currency_symbol: row.get(6)?,
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerEntry {
+    id: String,
+    account_id: String,
+    r#type: String,
+    amount: i64,
+    date: String,
+    title: String,
+    category: String,
+    platform: Option<String>,
+    url: Option<String>,
+    merchant: Option<String>,
+    payment_method: Option<String>,
+    memo: Option<String>,
+    color: Option<String>,
+    tags: Vec<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerEntryInput {
+    account_id: String,
+    r#type: String,
+    amount: i64,
+    date: String,
+    title: String,
+    category: String,
+    platform: Option<String>,
+    url: Option<String>,
+    merchant: Option<String>,
+    payment_method: Option<String>,
+    memo: Option<String>,
+    color: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerHistory {
+    id: String,
+    entry_id: String,
+    action: String,
+    snapshot_before: Option<String>,
+    snapshot_after: Option<String>,
+    created_at: String,
+}
+
+// 계정 전체 히스토리 조회용 (항목 정보 포함)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerHistoryWithEntry {
+    #[serde(flatten)]
+    history: LedgerHistory,
+    entry_title: Option<String>,
+    entry_category: Option<String>,
+    entry_date: Option<String>,
+}
+
+// 가계부 자동 분류 규칙
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerRule {
+    id: String,
+    account_id: String,
+    match_field: String,
+    match_type: String,
+    match_value: String,
+    set_category: Option<String>,
+    set_tags: Vec<String>,
+    created_at: String,
+}
+
+// 가계부 계정 백업 파일 (계정/항목/히스토리/규칙, 패스워드 정보는 포함하지 않음)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerAccountBackup {
+    version: u32,
+    exported_at: String,
+    account: LedgerAccount,
+    entries: Vec<LedgerBackupEntry>,
+    history: Vec<LedgerHistory>,
+    #[serde(default)]
+    rules: Vec<LedgerRule>,
+}
+
+// 백업 항목 (결제에서 변환된 항목의 출처 연결 포함)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerBackupEntry {
+    #[serde(flatten)]
+    entry: LedgerEntry,
+    source_provider: Option<String>,
+    source_payment_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerRuleInput {
+    match_field: String,
+    match_type: String,
+    match_value: String,
+    set_category: Option<String>,
+    #[serde(default)]
+    set_tags: Vec<String>,
+}
+
+fn hash_password(password: &str) -> String {
+    let digest = md5::compute(password.as_bytes());
+    format!("{:x}", digest)
+}
+
+fn check_and_reset_expired_passwords(conn: &Connection) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE tbl_ledger_account 
+         SET password_hash = NULL, password_expires_at = NULL, updated_at = ?1 
+         WHERE password_expires_at IS NOT NULL AND password_expires_at < ?1",
+        [&now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 잠금 해제 후 이 시간 동안 가계부 접근이 없으면 자동으로 다시 잠김
+const LEDGER_UNLOCK_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+// 이 시간 안에 패스워드 확인이 연속으로 실패하면 잠금
+const AUTH_LOCKOUT_MINUTES: i64 = 15;
+const AUTH_MAX_FAILED_ATTEMPTS: i64 = 5;
+
+// 패스워드가 없는 계정은 항상 열려 있고, 있는 계정은 잠금 해제 세션이 유효해야 함 (접근 시 세션 연장)
+fn is_ledger_account_unlocked(conn: &Connection, state: &AppState, account_id: &str) -> Result<bool, String> {
+    check_ledger_unlock(conn, state, account_id, true)
+}
+
+// touch가 false면 세션을 연장하지 않고 상태만 확인 (프론트엔드 폴링용)
+fn check_ledger_unlock(conn: &Connection, state: &AppState, account_id: &str, touch: bool) -> Result<bool, String> {
+    let password_hash: Option<String> = match conn.query_row(
+        "SELECT password_hash FROM tbl_ledger_account WHERE id = ?1",
+        [account_id],
+        |row| row.get(0),
+    ) {
+        Ok(hash) => hash,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(true),
+        Err(e) => return Err(e.to_string()),
+    };
+    
+    let mut unlocked = state
+        .unlocked_ledger_accounts
+        .lock()
+        .expect("failed to lock unlocked_ledger_accounts");
+    if password_hash.is_none() {
+        return Ok(true);
+    }
+    match unlocked.get_mut(account_id) {
+        Some(last_access) if last_access.elapsed() < LEDGER_UNLOCK_TIMEOUT => {
+            if touch {
+                *last_access = Instant::now();
+            }
+            Ok(true)
+        }
+        Some(_) => {
+            unlocked.remove(account_id);
+            Ok(false)
+        }
+        None => Ok(false),
+    }
+}
+
+fn ensure_ledger_unlocked(conn: &Connection, state: &AppState, account_id: &str) -> Result<(), String> {
+    if is_ledger_account_unlocked(conn, state, account_id)? {
+        Ok(())
+    } else {
+        Err("가계부 계정이 잠겨 있습니다. 패스워드를 다시 입력해주세요.".to_string())
+    }
+}
+
+// 지금 열람 가능한 가계부 계정 ID 목록을 json_each용 JSON 배열로 반환 (세션은 연장하지 않음)
+fn open_ledger_account_ids_json(conn: &Connection, state: &AppState) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM tbl_ledger_account")
+        .map_err(|e| e.to_string())?;
+    let account_ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut open_ids = Vec::new();
+    for account_id in account_ids {
+        if check_ledger_unlock(conn, state, &account_id, false)? {
+            open_ids.push(account_id);
+        }
+    }
+    serde_json::to_string(&open_ids).map_err(|e| e.to_string())
+}
+
+// 항목이 속한 계정 ID 조회 (삭제된 항목은 히스토리 스냅샷에서 찾음)
+fn ledger_entry_account_id(conn: &Connection, entry_id: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT COALESCE(
+            (SELECT account_id FROM tbl_ledger_entry WHERE id = ?1),
+            (SELECT COALESCE(json_extract(snapshot_before, '$.account_id'), json_extract(snapshot_after, '$.accountId'))
+             FROM tbl_ledger_history WHERE entry_id = ?1
+             ORDER BY created_at DESC, rowid DESC LIMIT 1)
+        )",
+        [entry_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// 만료된 키를 정리한 뒤 같은 계정에서 같은 키로 저장된 결과 ID 조회
+fn find_idempotent_result(
+    conn: &Connection,
+    scope: &str,
+    account_id: &str,
+    key: &str,
+) -> Result<Option<String>, String> {
+    let expires_before = (Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+    conn.execute(
+        "DELETE FROM tbl_idempotency_key WHERE created_at < ?1",
+        [&expires_before],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    match conn.query_row(
+        "SELECT result_id FROM tbl_idempotency_key WHERE key = ?1 AND scope = ?2 AND account_id = ?3",
+        [key, scope, account_id],
+        |row| row.get(0),
+    ) {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// 가계부 항목의 태그 목록 조회
+fn load_ledger_tags(conn: &Connection, entry_id: &str) -> Result<Vec<String>, String> {
+    let mut tag_stmt = conn
+        .prepare("SELECT tag FROM tbl_ledger_tag WHERE entry_id = ?1 ORDER BY tag")
+        .map_err(|e| e.to_string())?;
+    let tag_rows = tag_stmt
+        .query_map([entry_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+
+    let mut tags = Vec::new();
+    for tag_result in tag_rows {
+        tags.push(tag_result.map_err(|e| e.to_string())?);
+    }
+    Ok(tags)
+}
+
+// 카테고리 이름에 해당하는 tbl_category 색상 조회 (카테고리가 없거나 색상이 없으면 None)
+// UI의 기본 카테고리 ID(food 등)는 tbl_category에 없으므로 사용자가 같은 이름의 카테고리를 만들어야 색상이 생김
+fn category_color(conn: &Connection, category: &str) -> Result<Option<String>, String> {
+    match conn.query_row(
+        "SELECT color FROM tbl_category WHERE name = ?1",
+        [category],
+        |row| row.get::<_, Option<String>>(0),
+    ) {
+        Ok(color) => Ok(color),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// SELECT id, account_id, type, amount, date, title, category, platform, url, merchant,
+//        payment_method, memo, color, created_at, updated_at 순서의 행을 LedgerEntry로 변환 (태그 제외)
+fn ledger_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<LedgerEntry> {
+    Ok(LedgerEntry {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        r#type: row.get(2)?,
+        amount: row.get(3)?,
+        date: row.get(4)?,
+        title: row.get(5)?,
+        category: row.get(6)?,
+        platform: row.get(7)?,
+        url: row.get(8)?,
+        merchant: row.get(9)?,
+        payment_method: row.get(10)?,
+        memo: row.get(11)?,
+        color: row.get(12)?,
+        tags: Vec::new(),
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+    })
+}
+
+#[tauri::command]
+fn create_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    nickname: String,
+    password: Option<String>,
+) -> Result<LedgerAccount, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    // 이름 변경과 같은 닉네임 중복 검사 (새 계정이므로 제외할 ID 없음)
+    let nickname = nickname.trim().to_string();
+    if nickname.is_empty() {
+        return Err("닉네임을 입력해주세요.".to_string());
+    }
+    ensure_ledger_nickname_available(&conn, &nickname, "")?;
+    
+    if let Some(password) = &password {
+        ensure_password_strong_enough(password)?;
+    }
+    
+    let account_id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    
+    let password_hash = password.map(|p| hash_password(&p));
+    let password_expires_at = password_hash.as_ref().map(|_| {
+        let expires = Utc::now() + chrono::Duration::days(30);
+        expires.to_rfc3339()
+    });
+    
+    conn.execute(
+        "INSERT INTO tbl_ledger_account (id, nickname, password_hash, password_expires_at, created_at, updated_at) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![account_id, nickname, password_hash, password_expires_at, now, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(LedgerAccount {
+        id: account_id,
+        nickname,
+        password_hash,
+        password_expires_at,
+        created_at: now.clone(),
+        updated_at: now,
+        currency_symbol: default_currency_symbol(),
+    })
+}
+
+// 계정 목록 항목 (계정 정보 + 수입/지출 요약)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerAccountSummary {
+    #[serde(flatten)]
+    account: LedgerAccount,
+    // 잠긴 계정은 금액을 노출하지 않음
+    total_income: Option<i64>,
+    total_expense: Option<i64>,
+    net_balance: Option<i64>,
+    entry_count: i64,
+    last_entry_date: Option<String>,
+}
+
+#[tauri::command]
+fn list_ledger_accounts(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<LedgerAccountSummary>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    // 계정별 항목 합계를 한 번에 조회 (항목이 없는 계정도 포함)
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.nickname, a.password_hash, a.password_expires_at, a.created_at, a.updated_at,
+                    a.currency_symbol,
+                    COALESCE(SUM(CASE WHEN e.type = 'income' THEN e.amount ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN e.type = 'expense' THEN e.amount ELSE 0 END), 0),
+                    COUNT(e.id),
+                    MAX(e.date)
+             FROM tbl_ledger_account a
+             LEFT JOIN tbl_ledger_entry e ON e.account_id = a.id
+             GROUP BY a.id
+             ORDER BY a.created_at DESC, a.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let total_income: i64 = row.get(7)?;
+            let total_expense: i64 = row.get(8)?;
+            Ok(LedgerAccountSummary {
+                account: ledger_account_from_row(row)?,
+                total_income: Some(total_income),
+                total_expense: Some(total_expense),
+                net_balance: Some(total_income - total_expense),
+                entry_count: row.get(9)?,
+                last_entry_date: row.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut accounts = Vec::new();
+    for row in rows {
+        let mut summary = row.map_err(|e| e.to_string())?;
+        // 목록 조회로 잠금 해제 세션을 연장하지 않음
+        if !check_ledger_unlock(&conn, &state, &summary.account.id, false)? {
+            summary.total_income = None;
+            summary.total_expense = None;
+            summary.net_balance = None;
+        }
+        accounts.push(summary);
+    }
+    Ok(accounts)
+}
+
+#[tauri::command]
+fn verify_ledger_password(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    password: String,
+) -> Result<bool, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    let now = Utc::now();
+    
+    // 하루 지난 시도 기록 정리
+    conn.execute(
+        "DELETE FROM tbl_auth_attempt WHERE attempt_at < ?1",
+        [(now - chrono::Duration::hours(24)).to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 최근 15분 내 (마지막 성공 이후) 실패가 5회 이상이면 패스워드 확인 없이 거부
+    let window_start = (now - chrono::Duration::minutes(AUTH_LOCKOUT_MINUTES)).to_rfc3339();
+    let recent_failures: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM tbl_auth_attempt
+             WHERE account_id = ?1 AND success = 0 AND attempt_at >= ?2
+               AND attempt_at > COALESCE(
+                   (SELECT MAX(attempt_at) FROM tbl_auth_attempt WHERE account_id = ?1 AND success = 1), '')",
+            rusqlite::params![account_id, window_start],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if recent_failures >= AUTH_MAX_FAILED_ATTEMPTS {
+        return Err("잠금: 15분 후 다시 시도하세요".to_string());
+    }
+    
+    let password_hash = hash_password(&password);
+    let stored_hash: Option<String> = conn
+        .query_row(
+            "SELECT password_hash FROM tbl_ledger_account WHERE id = ?1",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let is_valid = stored_hash.map(|h| h == password_hash).unwrap_or(false);
+    
+    conn.execute(
+        "INSERT INTO tbl_auth_attempt (account_id, success, attempt_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![account_id, is_valid, now.to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(is_valid)
+}
+
+// 패스워드 확인 잠금 해제 (실패 기록 삭제, 잠금 해제된 계정만 가능)
+#[tauri::command]
+fn clear_auth_lockout(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    conn.execute(
+        "DELETE FROM tbl_auth_attempt WHERE account_id = ?1 AND success = 0",
+        [&account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+// 패스워드 확인 후 계정을 잠금 해제 (일정 시간 접근이 없으면 자동 잠금)
+#[tauri::command]
+fn unlock_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    password: String,
+) -> Result<bool, String> {
+    let is_valid = verify_ledger_password(app_handle, state.clone(), account_id.clone(), password)?;
+    if is_valid {
+        state
+            .unlocked_ledger_accounts
+            .lock()
+            .expect("failed to lock unlocked_ledger_accounts")
+            .insert(account_id, Instant::now());
+    }
+    Ok(is_valid)
+}
+
+// 잠금 해제 세션이 아직 유효한지 확인 (세션 연장 없음)
+#[tauri::command]
+fn get_ledger_unlock_status(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<bool, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    check_ledger_unlock(&conn, &state, &account_id, false)
+}
+
+#[tauri::command]
+fn lock_ledger_account(state: State<AppState>, account_id: String) -> Result<(), String> {
+    state
+        .unlocked_ledger_accounts
+        .lock()
+        .expect("failed to lock unlocked_ledger_accounts")
+        .remove(&account_id);
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PasswordStrength {
+    score: u8,
+    is_strong_enough: bool,
+    issues: Vec<String>,
+}
+
+// 패스워드 강도 평가 (길이/대문자/소문자/숫자/특수문자 각 1점, 3점 이상 통과)
+fn evaluate_password_strength(password: &str) -> PasswordStrength {
+    let checks = [
+        (password.chars().count() >= 8, "8자 이상이어야 합니다"),
+        (password.chars().any(|c| c.is_uppercase()), "대문자를 포함해야 합니다"),
+        (password.chars().any(|c| c.is_lowercase()), "소문자를 포함해야 합니다"),
+        (password.chars().any(|c| c.is_ascii_digit()), "숫자를 포함해야 합니다"),
+        (
+            password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+            "특수문자를 포함해야 합니다",
+        ),
+    ];
+    
+    let score = checks.iter().filter(|(passed, _)| *passed).count() as u8;
+    let issues = checks
+        .iter()
+        .filter(|(passed, _)| !*passed)
+        .map(|(_, issue)| issue.to_string())
+        .collect();
+    
+    PasswordStrength {
+        score,
+        is_strong_enough: score >= 3,
+        issues,
+    }
+}
+
+fn ensure_password_strong_enough(password: &str) -> Result<(), String> {
+    let strength = evaluate_password_strength(password);
+    if !strength.is_strong_enough {
+        return Err(format!("패스워드가 너무 약합니다: {}", strength.issues.join(", ")));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn check_password_strength(password: String) -> Result<PasswordStrength, String> {
+    Ok(evaluate_password_strength(&password))
+}
+
+#[tauri::command]
+fn update_ledger_password(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    password: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    ensure_password_strong_enough(&password)?;
+    
+    let password_hash = hash_password(&password);
+    let expires_at = Utc::now() + chrono::Duration::days(30);
+    let now = Utc::now().to_rfc3339();
+    
+    conn.execute(
+        "UPDATE tbl_ledger_account 
+         SET password_hash = ?1, password_expires_at = ?2, updated_at = ?3 
+         WHERE id = ?4",
+        rusqlite::params![password_hash, expires_at.to_rfc3339(), now, account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+// 다른 가계부 계정이 같은 닉네임(대소문자 무시)을 쓰고 있으면 오류
+fn ensure_ledger_nickname_available(conn: &Connection, nickname: &str, account_id: &str) -> Result<(), String> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM tbl_ledger_account WHERE LOWER(nickname) = LOWER(?1) AND id != ?2",
+            rusqlite::params![nickname, account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if count > 0 {
+        return Err(format!("이미 사용 중인 닉네임입니다: {}", nickname));
+    }
+    Ok(())
+}
+
+// 닉네임이 겹치면 "닉네임 (2)", "닉네임 (3)" … 처럼 번호를 붙여 사용 가능한 이름 반환 (백업 복원용)
+fn unique_ledger_nickname(conn: &Connection, nickname: &str) -> Result<String, String> {
+    let mut candidate = nickname.to_string();
+    let mut suffix = 2;
+    loop {
+        let taken: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM tbl_ledger_account WHERE LOWER(nickname) = LOWER(?1))",
+                [&candidate],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !taken {
+            return Ok(candidate);
+        }
+        candidate = format!("{} ({})", nickname, suffix);
+        suffix += 1;
+    }
+}
+
+// 계정 이름/표시 통화 기호 변경 (지정한 값만 변경)
+#[tauri::command]
+fn update_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    nickname: Option<String>,
+    currency_symbol: Option<String>,
+) -> Result<LedgerAccount, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let nickname = nickname.map(|n| n.trim().to_string());
+    if nickname.as_deref() == Some("") {
+        return Err("닉네임을 입력해주세요.".to_string());
+    }
+    let currency_symbol = currency_symbol.map(|c| c.trim().to_string());
+    if currency_symbol.as_deref() == Some("") {
+        return Err("통화 기호를 입력해주세요.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    if let Some(nickname) = &nickname {
+        ensure_ledger_nickname_available(&conn, nickname, &account_id)?;
+    }
+    
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE tbl_ledger_account
+         SET nickname = COALESCE(?1, nickname),
+             currency_symbol = COALESCE(?2, currency_symbol),
+             updated_at = ?3
+         WHERE id = ?4",
+        rusqlite::params![nickname, currency_symbol, now, account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    conn.query_row(
+        "SELECT id, nickname, password_hash, password_expires_at, created_at, updated_at, currency_symbol
+         FROM tbl_ledger_account WHERE id = ?1",
+        [&account_id],
+        ledger_account_from_row,
+    )
+    .map_err(|_| "해당 가계부 계정을 찾을 수 없습니다.".to_string())
+}
+
+// 가계부 계정 닉네임 변경 (다른 계정과 중복 불가)
+#[tauri::command]
+fn update_ledger_account_nickname(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    nickname: String,
+) -> Result<LedgerAccount, String> {
+    update_ledger_account(app_handle, state, account_id, Some(nickname), None)
+}
+
+#[tauri::command]
+fn check_password_expiry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    check_and_reset_expired_passwords(&conn)
+}
+
+#[tauri::command]
+fn delete_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    // 히스토리는 항목 FK가 없으므로 계정 소속 이력을 직접 삭제
+    tx.execute(
+        "DELETE FROM tbl_ledger_history
+         WHERE entry_id IN (SELECT id FROM tbl_ledger_entry WHERE account_id = ?1)
+            OR COALESCE(json_extract(snapshot_before, '$.account_id'), json_extract(snapshot_after, '$.accountId')) = ?1",
+        [&account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM tbl_ledger_account WHERE id = ?1", [&account_id])
+        .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 가계부 항목 입력값 검증 (DB 제약 조건 에러 대신 이해하기 쉬운 메시지 반환)
+fn validate_ledger_entry_input(entry: &LedgerEntryInput) -> Result<(), String> {
+    if !matches!(entry.r#type.as_str(), "income" | "expense") {
+        return Err("유형은 'income' 또는 'expense'여야 합니다.".to_string());
+    }
+    if entry.amount <= 0 {
+        return Err("금액은 0보다 커야 합니다.".to_string());
+    }
+    let is_valid_date = entry.date.len() == 10
+        && entry.date.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    if !is_valid_date {
+        return Err(format!("날짜는 YYYY-MM-DD 형식이어야 합니다: {}", entry.date));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn create_ledger_entry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    entry: LedgerEntryInput,
+    idempotency_key: Option<String>,
+) -> Result<String, String> {
+    validate_ledger_entry_input(&entry)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    
+    let entry_id = insert_ledger_entry(&tx, &account_id, entry, idempotency_key.as_deref())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(entry_id)
+}
+
+// 가계부 항목과 태그/생성 이력 저장 (같은 계정에서 처리된 idempotency_key면 기존 항목 ID 반환, 잠금 확인은 호출자 책임)
+fn insert_ledger_entry(
+    conn: &Connection,
+    account_id: &str,
+    mut entry: LedgerEntryInput,
+    idempotency_key: Option<&str>,
+) -> Result<String, String> {
+    // 이미 처리된 요청이면 기존 항목 ID 반환
+    if let Some(key) = idempotency_key {
+        if let Some(existing_id) = find_idempotent_result(conn, "ledger_entry", account_id, key)? {
+            return Ok(existing_id);
+        }
+    }
+    
+    // 색상 미지정 시 카테고리 색상 사용
+    if entry.color.is_none() {
+        entry.color = category_color(conn, &entry.category)?;
+    }
+    
+    let entry_id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    
+    // 항목 저장
+    conn.execute(
+        "INSERT INTO tbl_ledger_entry 
+         (id, account_id, type, amount, date, title, category, platform, url, merchant, payment_method, memo, color, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        rusqlite::params![
+            entry_id, account_id, entry.r#type, entry.amount, entry.date, entry.title,
+            entry.category, entry.platform, entry.url, entry.merchant, entry.payment_method,
+            entry.memo, entry.color, now, now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 태그 저장
+    for tag in &entry.tags {
+        let tag_id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![tag_id, entry_id, tag, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    // 히스토리 기록 (완전한 LedgerEntry 생성)
+    let full_entry = LedgerEntry {
+        id: entry_id.clone(),
+        account_id: account_id.to_string(),
+        r#type: entry.r#type.clone(),
+        amount: entry.amount,
+        date: entry.date.clone(),
+        title: entry.title.clone(),
+        category: entry.category.clone(),
+        platform: entry.platform.clone(),
+        url: entry.url.clone(),
+        merchant: entry.merchant.clone(),
+        payment_method: entry.payment_method.clone(),
+        memo: entry.memo.clone(),
+        color: entry.color.clone(),
+        tags: entry.tags.clone(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    let snapshot_after = serde_json::to_string(&full_entry).map_err(|e| e.to_string())?;
+    let history_id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_after, created_at) 
+         VALUES (?1, ?2, 'create', ?3, ?4)",
+        rusqlite::params![history_id, entry_id, snapshot_after, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    if let Some(key) = idempotency_key {
+        conn.execute(
+            "INSERT INTO tbl_idempotency_key (key, scope, account_id, result_id, created_at)
+             VALUES (?1, 'ledger_entry', ?2, ?3, ?4)",
+            rusqlite::params![key, account_id, entry_id, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    Ok(entry_id)
+}
+
+// 결제 내역에서 변환한 가계부 항목의 기본 카테고리 (가맹점 규칙 적용 전, UI의 카테고리 ID)
+const PAYMENT_LEDGER_CATEGORY: &str = "shopping";
+
+// 저장된 결제를 가계부 지출 항목으로 변환 (이미 변환된 결제면 기존 항목 ID 반환)
+#[tauri::command]
+fn payment_to_ledger(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    provider: String,
+    payment_id: i64,
+) -> Result<String, String> {
+    let payment_sql = match provider.as_str() {
+        "naver" => {
+            "SELECT total_amount, merchant_name, paid_at, COALESCE(product_name, merchant_name), order_detail_url
+             FROM tbl_naver_payment WHERE id = ?1"
+        }
+        "coupang" => {
+            "SELECT total_amount, merchant_name, COALESCE(paid_at, ordered_at), COALESCE(product_name, merchant_name), order_detail_url
+             FROM tbl_coupang_payment WHERE id = ?1"
+        }
+        _ => return Err(format!("지원하지 않는 provider입니다: {}", provider)),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    
+    // 이미 변환된 결제면 기존 항목 ID 반환
+    let existing_id: Option<String> = tx
+        .query_row(
+            "SELECT id FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND source_provider = ?2 AND source_payment_id = ?3",
+            rusqlite::params![account_id, provider, payment_id],
+            |row| row.get(0),
+        )
+        .ok();
+    if let Some(existing_id) = existing_id {
+        return Ok(existing_id);
+    }
+    
+    let (amount, merchant, paid_at, title, url): (i64, String, String, String, Option<String>) = tx
+        .query_row(payment_sql, [payment_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })
+        .map_err(|_| "해당 결제 정보를 찾을 수 없습니다.".to_string())?;
+    
+    let entry_id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let entry = LedgerEntry {
+        id: entry_id.clone(),
+        account_id: account_id.clone(),
+        r#type: "expense".to_string(),
+        amount,
+        date: paid_at.chars().take(10).collect(),
+        title,
+        category: PAYMENT_LEDGER_CATEGORY.to_string(),
+        platform: Some("online_shopping".to_string()),
+        url,
+        merchant: Some(merchant),
+        payment_method: None,
+        memo: None,
+        color: None,
+        tags: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    
+    tx.execute(
+        "INSERT INTO tbl_ledger_entry 
+         (id, account_id, type, amount, date, title, category, platform, url, merchant,
+          source_provider, source_payment_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        rusqlite::params![
+            entry.id, entry.account_id, entry.r#type, entry.amount, entry.date, entry.title,
+            entry.category, entry.platform, entry.url, entry.merchant, provider, payment_id,
+            now, now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 히스토리 기록
+    let snapshot_after = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    let history_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_after, created_at) 
+         VALUES (?1, ?2, 'create', ?3, ?4)",
+        rusqlite::params![history_id, entry_id, snapshot_after, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(entry_id)
+}
+
+#[tauri::command]
+fn update_ledger_entry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    entry_id: String,
+    mut entry: LedgerEntryInput,
+) -> Result<(), String> {
+    validate_ledger_entry_input(&entry)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    
+    // 기존 항목 조회 (히스토리용)
+    let (existing_account_id, existing_created_at): (String, String) = tx
+        .query_row(
+            "SELECT account_id, created_at FROM tbl_ledger_entry WHERE id = ?1",
+            [&entry_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    ensure_ledger_unlocked(&tx, &state, &existing_account_id)?;
+    
+    // 색상 미지정 시 카테고리 색상 사용
+    if entry.color.is_none() {
+        entry.color = category_color(&tx, &entry.category)?;
+    }
+    
+    let snapshot_before: Option<String> = tx
+        .query_row(
+            "SELECT json_object(
+                'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                'date', date, 'title', title, 'category', category, 'platform', platform,
+                'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+            ) FROM tbl_ledger_entry WHERE id = ?1",
+            [&entry_id],
+            |row| row.get(0),
+        )
+        .ok();
+    
+    let now = Utc::now().to_rfc3339();
+    
+    // 항목 업데이트
+    tx.execute(
+        "UPDATE tbl_ledger_entry 
+         SET type = ?1, amount = ?2, date = ?3, title = ?4, category = ?5, platform = ?6,
+             url = ?7, merchant = ?8, payment_method = ?9, memo = ?10, color = ?11, updated_at = ?12
+         WHERE id = ?13",
+        rusqlite::params![
+            entry.r#type, entry.amount, entry.date, entry.title, entry.category,
+            entry.platform, entry.url, entry.merchant, entry.payment_method,
+            entry.memo, entry.color, now, entry_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 태그 삭제 후 재생성
+    tx.execute("DELETE FROM tbl_ledger_tag WHERE entry_id = ?1", [&entry_id])
+        .map_err(|e| e.to_string())?;
+    
+    for tag in &entry.tags {
+        let tag_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![tag_id, entry_id, tag, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    // 히스토리 기록 (완전한 LedgerEntry 생성)
+    let full_entry_after = LedgerEntry {
+        id: entry_id.clone(),
+        account_id: existing_account_id,
+        r#type: entry.r#type.clone(),
+        amount: entry.amount,
+        date: entry.date.clone(),
+        title: entry.title.clone(),
+        category: entry.category.clone(),
+        platform: entry.platform.clone(),
+        url: entry.url.clone(),
+        merchant: entry.merchant.clone(),
+        payment_method: entry.payment_method.clone(),
+        memo: entry.memo.clone(),
+        color: entry.color.clone(),
+        tags: entry.tags.clone(),
+        created_at: existing_created_at,
+        updated_at: now.clone(),
+    };
+    let snapshot_after = serde_json::to_string(&full_entry_after).map_err(|e| e.to_string())?;
+    let history_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+         VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
+        rusqlite::params![history_id, entry_id, snapshot_before, snapshot_after, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 항목을 다른 가계부 계정으로 이동 (태그/생성일 유지, 업데이트 히스토리 기록)
+#[tauri::command]
+fn move_ledger_entry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    entry_id: String,
+    target_account_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    
+    let select_entry = "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant,
+                payment_method, memo, color, created_at, updated_at
+         FROM tbl_ledger_entry WHERE id = ?1";
+    let entry = match tx.query_row(select_entry, [&entry_id], ledger_entry_from_row) {
+        Ok(entry) => entry,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Err("가계부 항목을 찾을 수 없습니다.".to_string()),
+        Err(e) => return Err(e.to_string()),
+    };
+    ensure_ledger_unlocked(&tx, &state, &entry.account_id)?;
+    if entry.account_id == target_account_id {
+        return Ok(());
+    }
+    
+    let target_exists: bool = tx
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tbl_ledger_account WHERE id = ?1)",
+            [&target_account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !target_exists {
+        return Err("이동할 가계부 계정을 찾을 수 없습니다.".to_string());
+    }
+    ensure_ledger_unlocked(&tx, &state, &target_account_id)?;
+    
+    relocate_ledger_entry(&tx, entry, &target_account_id)?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 항목의 account_id를 옮기고 update 이력 기록 (존재/잠금 확인은 호출자 책임)
+fn relocate_ledger_entry(
+    conn: &Connection,
+    mut entry: LedgerEntry,
+    target_account_id: &str,
+) -> Result<(), String> {
+    let entry_id = entry.id.clone();
+    
+    // 같은 결제에서 변환된 항목이 대상 계정에 이미 있으면 ux_ledger_entry_source 충돌
+    let source_conflict: bool = conn
+        .query_row(
+            "SELECT EXISTS(
+                SELECT 1 FROM tbl_ledger_entry src
+                JOIN tbl_ledger_entry dst
+                  ON dst.source_provider IS src.source_provider
+                 AND dst.source_payment_id = src.source_payment_id
+                WHERE src.id = ?1 AND dst.account_id = ?2
+            )",
+            rusqlite::params![entry_id, target_account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if source_conflict {
+        return Err("같은 결제에서 변환된 항목이 이동할 가계부 계정에 이미 있습니다.".to_string());
+    }
+    
+    let snapshot_before: Option<String> = conn
+        .query_row(
+            "SELECT json_object(
+                'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                'date', date, 'title', title, 'category', category, 'platform', platform,
+                'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+            ) FROM tbl_ledger_entry WHERE id = ?1",
+            [&entry_id],
+            |row| row.get(0),
+        )
+        .ok();
+    
+    let now = Utc::now().to_rfc3339();
+    
+    // 태그는 entry_id로 연결되어 있어 그대로 따라감
+    conn.execute(
+        "UPDATE tbl_ledger_entry SET account_id = ?1, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![target_account_id, now, entry_id],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    entry.account_id = target_account_id.to_string();
+    entry.updated_at = now.clone();
+    entry.tags = load_ledger_tags(conn, &entry_id)?;
+    let snapshot_after = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    let history_id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+         VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
+        rusqlite::params![history_id, entry_id, snapshot_before, snapshot_after, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_ledger_entry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    entry_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    if let Some(account_id) = ledger_entry_account_id(&tx, &entry_id)? {
+        ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    }
+    
+    // 삭제 전 스냅샷 저장
+    let snapshot_before: Option<String> = tx
+        .query_row(
+            "SELECT json_object(
+                'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                'date', date, 'title', title, 'category', category, 'platform', platform,
+                'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+            ) FROM tbl_ledger_entry WHERE id = ?1",
+            [&entry_id],
+            |row| row.get(0),
+        )
+        .ok();
+    
+    let now = Utc::now().to_rfc3339();
+    
+    // 히스토리 기록
+    let history_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, created_at) 
+         VALUES (?1, ?2, 'delete', ?3, ?4)",
+        rusqlite::params![history_id, entry_id, snapshot_before, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    // 항목 삭제 (CASCADE로 태그도 자동 삭제)
+    tx.execute("DELETE FROM tbl_ledger_entry WHERE id = ?1", [entry_id])
+        .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn list_ledger_entries(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: String,
+) -> Result<Vec<LedgerEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    load_ledger_month_entries(&conn, &account_id, &year_month)
+}
+
+// 계정의 해당 월 항목 조회 (잠금 확인은 호출자 책임)
+fn load_ledger_month_entries(
+    conn: &Connection,
+    account_id: &str,
+    year_month: &str,
+) -> Result<Vec<LedgerEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
+                    payment_method, memo, color, created_at, updated_at
+             FROM tbl_ledger_entry 
+             WHERE account_id = ?1 AND date LIKE ?2
+             ORDER BY date DESC, created_at DESC, id",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let date_pattern = format!("{}%", year_month);
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, date_pattern], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, Option<String>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+                row.get::<_, Option<String>>(10)?,
+                row.get::<_, Option<String>>(11)?,
+                row.get::<_, Option<String>>(12)?,
+                row.get::<_, String>(13)?,
+                row.get::<_, String>(14)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut entries = Vec::new();
+    for row_result in rows {
+        let (
+            id, account_id, r#type, amount, date, title, category, platform, url, merchant,
+            payment_method, memo, color, created_at, updated_at,
+        ) = row_result.map_err(|e| e.to_string())?;
+        
+        // 태그 조회
+        let tags = load_ledger_tags(conn, &id)?;
+        
+        entries.push(LedgerEntry {
+            id,
+            account_id,
+            r#type,
+            amount,
+            date,
+            title,
+            category,
+            platform,
+            url,
+            merchant,
+            payment_method,
+            memo,
+            color,
+            tags,
+            created_at,
+            updated_at,
+        });
+    }
+    
+    Ok(entries)
+}
+
+// 영수증 URL이 있는 항목만 조회 (year_month 미지정 시 전체 기간)
+#[tauri::command]
+fn list_ledger_entries_with_receipt(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: Option<String>,
+) -> Result<Vec<LedgerEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant,
+                    payment_method, memo, color, created_at, updated_at
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND date LIKE ?2
+               AND url IS NOT NULL AND url != ''
+             ORDER BY date DESC, created_at DESC, id",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let date_pattern = format!("{}%", year_month.unwrap_or_default());
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, date_pattern], ledger_entry_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut entries = Vec::new();
+    for row in rows {
+        let mut entry = row.map_err(|e| e.to_string())?;
+        entry.tags = load_ledger_tags(&conn, &entry.id)?;
+        entries.push(entry);
+    }
+    
+    Ok(entries)
+}
+
+// 가계부 UI가 저장하는 기본 카테고리 ID (src/pages/ledger/ui/LedgerEntryPage.tsx의 EXPENSE/INCOME_CATEGORIES)
+const LEDGER_CATEGORY_IDS: [&str; 15] = [
+    "food", "transport", "housing", "shopping", "leisure", "medical", "education", "finance", "social", "etc",
+    "salary", "side", "investment", "gift", "refund",
+];
+
+// 정리가 필요한 항목 조회 (untagged: 태그 없음, uncategorized: 기본 카테고리 ID도 tbl_category 이름도 아닌 카테고리, 둘 다 지정 시 AND)
+fn list_incomplete_ledger_entries(
+    conn: &Connection,
+    account_id: &str,
+    untagged: bool,
+    uncategorized: bool,
+) -> Result<Vec<LedgerEntry>, String> {
+    let mut conditions = Vec::new();
+    if untagged {
+        conditions.push("NOT EXISTS (SELECT 1 FROM tbl_ledger_tag t WHERE t.entry_id = e.id)");
+    }
+    let known_categories = format!("'{}'", LEDGER_CATEGORY_IDS.join("', '"));
+    let uncategorized_condition = format!(
+        "e.category NOT IN ({}) AND NOT EXISTS (SELECT 1 FROM tbl_category c WHERE c.name = e.category)",
+        known_categories
+    );
+    if uncategorized {
+        conditions.push(&uncategorized_condition);
+    }
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.id, e.account_id, e.type, e.amount, e.date, e.title, e.category, e.platform, e.url,
+                    e.merchant, e.payment_method, e.memo, e.color, e.created_at, e.updated_at
+             FROM tbl_ledger_entry e
+             WHERE e.account_id = ?1 AND {}
+             ORDER BY e.date DESC, e.created_at DESC, e.id",
+            conditions.join(" AND ")
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([account_id], ledger_entry_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut entries = Vec::new();
+    for row in rows {
+        let mut entry = row.map_err(|e| e.to_string())?;
+        entry.tags = load_ledger_tags(conn, &entry.id)?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+// 태그가 하나도 없는 항목 (also_uncategorized면 카테고리도 미분류인 항목만)
+#[tauri::command]
+fn list_untagged_ledger_entries(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    also_uncategorized: Option<bool>,
+) -> Result<Vec<LedgerEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    list_incomplete_ledger_entries(&conn, &account_id, true, also_uncategorized.unwrap_or(false))
+}
+
+// 기본 카테고리 ID도 tbl_category 이름도 아닌 카테고리의 항목 (also_untagged면 태그도 없는 항목만)
+#[tauri::command]
+fn list_uncategorized_ledger_entries(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    also_untagged: Option<bool>,
+) -> Result<Vec<LedgerEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    list_incomplete_ledger_entries(&conn, &account_id, also_untagged.unwrap_or(false), true)
+}
+
+// 영수증 URL이 있는 항목 수
+#[tauri::command]
+fn get_ledger_url_count(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<i64, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(0);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    conn.query_row(
+        "SELECT COUNT(*) FROM tbl_ledger_entry WHERE account_id = ?1 AND url IS NOT NULL AND url != ''",
+        [&account_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaginatedLedgerResponse {
+    entries: Vec<LedgerEntry>,
+    total: i64,
+    limit: i64,
+}
+
+// 날짜/계정 구분 없이 지정한 태그를 모두 가진 항목 조회 (AND 조건)
+#[tauri::command]
+fn list_ledger_entries_by_tag(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    tags: Vec<String>,
+    account_ids: Option<Vec<String>>,
+    entry_type: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<PaginatedLedgerResponse, String> {
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(PaginatedLedgerResponse { entries: Vec::new(), total: 0, limit });
+    }
+    if tags.is_empty() {
+        return Err("태그를 하나 이상 지정해야 합니다.".to_string());
+    }
+    if let Some(t) = &entry_type {
+        if t != "income" && t != "expense" {
+            return Err("유효하지 않은 항목 유형입니다.".to_string());
+        }
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    let offset = offset.unwrap_or(0);
+    
+    // 태그마다 EXISTS 조건을 추가하여 모든 태그를 가진 항목만 남김
+    let mut where_clause = String::from("WHERE 1 = 1");
+    let mut params: Vec<rusqlite::types::Value> = Vec::new();
+    for tag in &tags {
+        params.push(tag.clone().into());
+        where_clause.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM tbl_ledger_tag t WHERE t.entry_id = e.id AND t.tag = ?{})",
+            params.len()
+        ));
+    }
+    // 계정을 지정하지 않으면 잠금 해제된 계정 전체, 지정하면 모두 잠금 해제되어 있어야 함
+    let account_ids = match account_ids {
+        Some(ids) => {
+            for id in &ids {
+                ensure_ledger_unlocked(&conn, &state, id)?;
+            }
+            ids
+        }
+        None => {
+            let mut stmt = conn
+                .prepare("SELECT id FROM tbl_ledger_account")
+                .map_err(|e| e.to_string())?;
+            let all_ids = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            let mut ids = Vec::new();
+            for id in all_ids {
+                if is_ledger_account_unlocked(&conn, &state, &id)? {
+                    ids.push(id);
+                }
+            }
+            ids
+        }
+    };
+    if account_ids.is_empty() {
+        return Ok(PaginatedLedgerResponse { entries: Vec::new(), total: 0, limit });
+    }
+    let mut placeholders = Vec::new();
+    for id in account_ids {
+        params.push(id.into());
+        placeholders.push(format!("?{}", params.len()));
+    }
+    where_clause.push_str(&format!(" AND e.account_id IN ({})", placeholders.join(", ")));
+    if let Some(t) = entry_type {
+        params.push(t.into());
+        where_clause.push_str(&format!(" AND e.type = ?{}", params.len()));
+    }
+    
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM tbl_ledger_entry e {}", where_clause),
+            rusqlite::params_from_iter(params.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let sql = format!(
+        "SELECT e.id, e.account_id, e.type, e.amount, e.date, e.title, e.category, e.platform, e.url,
+                e.merchant, e.payment_method, e.memo, e.color, e.created_at, e.updated_at
+         FROM tbl_ledger_entry e
+         {}
+         ORDER BY e.date DESC, e.created_at DESC, e.id
+         LIMIT ?{} OFFSET ?{}",
+        where_clause,
+        params.len() + 1,
+        params.len() + 2
+    );
+    params.push(limit.into());
+    params.push(offset.into());
+    
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), ledger_entry_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut entries = Vec::new();
+    for row in rows {
+        let mut entry = row.map_err(|e| e.to_string())?;
+        entry.tags = load_ledger_tags(&conn, &entry.id)?;
+        entries.push(entry);
+    }
+    
+    Ok(PaginatedLedgerResponse { entries, total, limit })
+}
+
+#[tauri::command]
+fn get_ledger_entry(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    entry_id: String,
+) -> Result<Option<LedgerEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    let result: Result<(String, String, String, i64, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, String, String), rusqlite::Error> = conn.query_row(
+        "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
+                payment_method, memo, color, created_at, updated_at
+         FROM tbl_ledger_entry WHERE id = ?1",
+        [entry_id],
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+                row.get(8)?,
+                row.get(9)?,
+                row.get(10)?,
+                row.get(11)?,
+                row.get(12)?,
+                row.get(13)?,
+                row.get(14)?,
+            ))
+        },
+    );
+    
+    match result {
+        Ok((
+            id, account_id, r#type, amount, date, title, category, platform, url, merchant,
+            payment_method, memo, color, created_at, updated_at,
+        )) => {
+            ensure_ledger_unlocked(&conn, &state, &account_id)?;
+            
+            // 태그 조회
+            let tags = load_ledger_tags(&conn, &id)?;
+            
+            Ok(Some(LedgerEntry {
+                id,
+                account_id,
+                r#type,
+                amount,
+                date,
+                title,
+                category,
+                platform,
+                url,
+                merchant,
+                payment_method,
+                memo,
+                color,
+                tags,
+                created_at,
+                updated_at,
+            }))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+fn list_ledger_history(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    entry_id: String,
+) -> Result<Vec<LedgerHistory>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    if let Some(account_id) = ledger_entry_account_id(&conn, &entry_id)? {
+        ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    }
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, entry_id, action, snapshot_before, snapshot_after, created_at
+             FROM tbl_ledger_history 
+             WHERE entry_id = ?1
+             ORDER BY created_at DESC, rowid DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map([entry_id], |row| {
+            Ok(LedgerHistory {
+                id: row.get(0)?,
+                entry_id: row.get(1)?,
+                action: row.get(2)?,
+                snapshot_before: row.get(3)?,
+                snapshot_after: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut histories = Vec::new();
+    for row in rows {
+        histories.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(histories)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerFieldChange {
+    field: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerHistoryDiff {
+    history_id: String,
+    action: String,
+    changes: Vec<LedgerFieldChange>,
+    // 스냅샷 파싱에 실패하면 true이고 원본 스냅샷을 그대로 전달
+    raw_fallback: bool,
+    snapshot_before: Option<String>,
+    snapshot_after: Option<String>,
+}
+
+// 스냅샷 JSON을 camelCase 키의 객체로 변환
+// (snapshot_before는 SQL json_object의 snake_case, snapshot_after는 LedgerEntry 직렬화 결과의 camelCase)
+fn parse_ledger_snapshot(snapshot: &str) -> Option<serde_json::Map<String, Value>> {
+    match serde_json::from_str::<Value>(snapshot) {
+        Ok(Value::Object(map)) => Some(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let mut camel = String::with_capacity(key.len());
+                    let mut upper_next = false;
+                    for c in key.chars() {
+                        if c == '_' {
+                            upper_next = true;
+                        } else if upper_next {
+                            camel.push(c.to_ascii_uppercase());
+                            upper_next = false;
+                        } else {
+                            camel.push(c);
+                        }
+                    }
+                    (camel, value)
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+// 히스토리 한 건의 변경 전/후 스냅샷에서 바뀐 필드만 추출
+#[tauri::command]
+fn get_ledger_history_diff(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    history_id: String,
+) -> Result<LedgerHistoryDiff, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    
+    let (action, snapshot_before, snapshot_after, account_id): (String, Option<String>, Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT h.action, h.snapshot_before, h.snapshot_after,
+                    COALESCE(
+                        e.account_id,
+                        CASE WHEN json_valid(h.snapshot_before) THEN
+                            COALESCE(json_extract(h.snapshot_before, '$.account_id'), json_extract(h.snapshot_before, '$.accountId'))
+                        END,
+                        CASE WHEN json_valid(h.snapshot_after) THEN
+                            COALESCE(json_extract(h.snapshot_after, '$.accountId'), json_extract(h.snapshot_after, '$.account_id'))
+                        END
+                    )
+             FROM tbl_ledger_history h
+             LEFT JOIN tbl_ledger_entry e ON e.id = h.entry_id
+             WHERE h.id = ?1",
+            [&history_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|_| "해당 히스토리를 찾을 수 없습니다.".to_string())?;
+    // 계정을 알 수 없는 기록은 잠금 여부를 확인할 수 없으므로 보여주지 않음
+    let account_id = account_id.ok_or_else(|| "히스토리의 가계부 계정을 확인할 수 없습니다.".to_string())?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let before = snapshot_before.as_deref().map(parse_ledger_snapshot);
+    let after = snapshot_after.as_deref().map(parse_ledger_snapshot);
+    let (before, after) = match (before, after) {
+        (Some(None), _) | (_, Some(None)) => {
+            tracing::warn!(history_id = %history_id, "ledger history snapshot is not a JSON object");
+            return Ok(LedgerHistoryDiff {
+                history_id,
+                action,
+                changes: Vec::new(),
+                raw_fallback: true,
+                snapshot_before,
+                snapshot_after,
+            });
+        }
+        (before, after) => (before.flatten(), after.flatten()),
+    };
+    
+    // 수정 이력의 snapshot_before에는 태그가 없으므로 양쪽에 모두 있는 필드만 비교
+    let fields: Vec<&String> = match (&before, &after) {
+        (Some(before), Some(after)) => before.keys().filter(|key| after.contains_key(*key)).collect(),
+        (Some(before), None) => before.keys().collect(),
+        (None, Some(after)) => after.keys().collect(),
+        (None, None) => Vec::new(),
+    };
+    
+    let mut changes = Vec::new();
+    for field in fields {
+        // updated_at은 히스토리 생성 시각과 같으므로 제외
+        if field == "updatedAt" {
+            continue;
+        }
+        let old_value = before.as_ref().and_then(|map| map.get(field)).cloned();
+        let new_value = after.as_ref().and_then(|map| map.get(field)).cloned();
+        if old_value != new_value {
+            changes.push(LedgerFieldChange {
+                field: field.clone(),
+                before: old_value,
+                after: new_value,
+            });
+        }
+    }
+    
+    Ok(LedgerHistoryDiff {
+        history_id,
+        action,
+        changes,
+        raw_fallback: false,
+        snapshot_before: None,
+        snapshot_after: None,
+    })
+}
+
+// 계정 전체 변경 이력 조회 (최신순)
+#[tauri::command]
+fn list_ledger_history_all(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<LedgerHistoryWithEntry>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0);
+    
+    // 삭제된 항목은 스냅샷의 account_id로 계정 판별
+    let mut stmt = conn
+        .prepare(
+            "SELECT h.id, h.entry_id, h.action, h.snapshot_before, h.snapshot_after, h.created_at,
+                    e.title, e.category, e.date
+             FROM tbl_ledger_history h
+             LEFT JOIN tbl_ledger_entry e ON e.id = h.entry_id
+             WHERE e.account_id = ?1
+                OR (e.id IS NULL AND COALESCE(
+                        json_extract(h.snapshot_before, '$.account_id'),
+                        json_extract(h.snapshot_after, '$.accountId')
+                    ) = ?1)
+             ORDER BY h.created_at DESC, h.rowid DESC
+             LIMIT ?2 OFFSET ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, limit, offset], |row| {
+            Ok(LedgerHistoryWithEntry {
+                history: LedgerHistory {
+                    id: row.get(0)?,
+                    entry_id: row.get(1)?,
+                    action: row.get(2)?,
+                    snapshot_before: row.get(3)?,
+                    snapshot_after: row.get(4)?,
+                    created_at: row.get(5)?,
+                },
+                entry_title: row.get(6)?,
+                entry_category: row.get(7)?,
+                entry_date: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut histories = Vec::new();
+    for row in rows {
+        let mut item = row.map_err(|e| e.to_string())?;
+        
+        // 삭제된 항목은 스냅샷에서 제목/카테고리/날짜 추출
+        if item.entry_title.is_none() {
+            let snapshot = item
+                .history
+                .snapshot_before
+                .as_deref()
+                .or(item.history.snapshot_after.as_deref())
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok());
+            if let Some(snapshot) = snapshot {
+                let field = |key: &str| snapshot.get(key).and_then(|v| v.as_str()).map(String::from);
+                item.entry_title = field("title");
+                item.entry_category = field("category");
+                item.entry_date = field("date");
+            }
+        }
+        
+        histories.push(item);
+    }
+    
+    Ok(histories)
+}
+
+// 계정에 속한 히스토리 조건 (삭제된 항목은 스냅샷의 계정 ID로 판단, ?1: 계정 ID)
+const LEDGER_HISTORY_ACCOUNT_SCOPE: &str = "(
+    EXISTS (SELECT 1 FROM tbl_ledger_entry e WHERE e.id = h.entry_id AND e.account_id = ?1)
+    OR (NOT EXISTS (SELECT 1 FROM tbl_ledger_entry e WHERE e.id = h.entry_id)
+        AND COALESCE(json_extract(h.snapshot_before, '$.account_id'), json_extract(h.snapshot_after, '$.accountId')) = ?1)
+)";
+
+// 가계부 히스토리 정리: 계정의 항목별 최근 N개만 남기거나 기준일 이전 기록 삭제, 삭제된 계정의 고아 기록 정리
+#[tauri::command]
+fn prune_ledger_history(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    keep_per_entry: Option<u32>,
+    before_date: Option<String>,
+) -> Result<u32, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    
+    if keep_per_entry == Some(0) {
+        return Err("항목별 보관 개수는 1 이상이어야 합니다.".to_string());
+    }
+    if let Some(date) = &before_date {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(format!("날짜는 YYYY-MM-DD 형식이어야 합니다: {}", date));
+        }
+    }
+    
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut removed = 0;
+    
+    if let Some(keep) = keep_per_entry {
+        removed += tx
+            .execute(
+                &format!(
+                    "DELETE FROM tbl_ledger_history
+                     WHERE id IN (
+                         SELECT id FROM (
+                             SELECT h.id, ROW_NUMBER() OVER (
+                                 PARTITION BY h.entry_id ORDER BY h.created_at DESC, h.rowid DESC
+                             ) AS rn
+                             FROM tbl_ledger_history h
+                             WHERE {}
+                         )
+                         WHERE rn > ?2
+                     )",
+                    LEDGER_HISTORY_ACCOUNT_SCOPE
+                ),
+                rusqlite::params![account_id, keep],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    
+    // created_at은 RFC3339 문자열이므로 날짜 문자열과 사전순 비교 시 해당 날짜 이전만 삭제됨
+    if let Some(date) = &before_date {
+        removed += tx
+            .execute(
+                &format!(
+                    "DELETE FROM tbl_ledger_history
+                     WHERE id IN (
+                         SELECT h.id FROM tbl_ledger_history h
+                         WHERE h.created_at < ?2 AND {}
+                     )",
+                    LEDGER_HISTORY_ACCOUNT_SCOPE
+                ),
+                rusqlite::params![account_id, date],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    
+    // 항목도 계정도 남아 있지 않은 히스토리 (계정 삭제 이전 데이터 등)
+    removed += tx
+        .execute(
+            "DELETE FROM tbl_ledger_history
+             WHERE NOT EXISTS (SELECT 1 FROM tbl_ledger_entry e WHERE e.id = tbl_ledger_history.entry_id)
+               AND NOT EXISTS (
+                   SELECT 1 FROM tbl_ledger_account a
+                   WHERE a.id = COALESCE(
+                       json_extract(tbl_ledger_history.snapshot_before, '$.account_id'),
+                       json_extract(tbl_ledger_history.snapshot_after, '$.accountId')
+                   )
+               )",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    
+    tracing::info!(removed, "ledger history pruned");
+    Ok(removed as u32)
+}
+
+// SELECT id, account_id, match_field, match_type, match_value, set_category, set_tags, created_at 순서의 행을 LedgerRule로 변환
+fn ledger_rule_from_row(row: &rusqlite::Row) -> rusqlite::Result<LedgerRule> {
+    let set_tags: Option<String> = row.get(6)?;
+    Ok(LedgerRule {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        match_field: row.get(2)?,
+        match_type: row.get(3)?,
+        match_value: row.get(4)?,
+        set_category: row.get(5)?,
+        set_tags: set_tags
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        created_at: row.get(7)?,
+    })
+}
+
+fn load_ledger_rules(conn: &Connection, account_id: &str) -> Result<Vec<LedgerRule>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, account_id, match_field, match_type, match_value, set_category, set_tags, created_at
+             FROM tbl_ledger_rule
+             WHERE account_id = ?1
+             ORDER BY created_at, id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([account_id], ledger_rule_from_row)
+        .map_err(|e| e.to_string())?;
+    
+    let mut rules = Vec::new();
+    for row in rows {
+        rules.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(rules)
+}
+
+// 규칙이 항목에 해당하는지 확인 (대소문자 무시)
+fn ledger_rule_matches(rule: &LedgerRule, entry: &LedgerEntry) -> bool {
+    let target = match rule.match_field.as_str() {
+        "merchant" => entry.merchant.as_deref().unwrap_or(""),
+        _ => entry.title.as_str(),
+    };
+    let target = target.trim().to_lowercase();
+    let value = rule.match_value.trim().to_lowercase();
+    if target.is_empty() {
+        return false;
+    }
+    match rule.match_type.as_str() {
+        "exact" => target == value,
+        _ => target.contains(&value),
+    }
+}
+
+#[tauri::command]
+fn create_ledger_rule(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    rule: LedgerRuleInput,
+) -> Result<LedgerRule, String> {
+    let LedgerRuleInput { match_field, match_type, match_value, set_category, set_tags } = rule;
+    if !matches!(match_field.as_str(), "merchant" | "title") {
+        return Err(format!("지원하지 않는 규칙 대상입니다: {}", match_field));
+    }
+    if !matches!(match_type.as_str(), "exact" | "contains") {
+        return Err(format!("지원하지 않는 규칙 방식입니다: {}", match_type));
+    }
+    let match_value = match_value.trim().to_string();
+    if match_value.is_empty() {
+        return Err("규칙 값이 비어 있습니다.".to_string());
+    }
+    let set_category = set_category
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    let mut set_tags: Vec<String> = set_tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    set_tags.sort();
+    set_tags.dedup();
+    if set_category.is_none() && set_tags.is_empty() {
+        return Err("적용할 카테고리나 태그가 필요합니다.".to_string());
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let tags_json = serde_json::to_string(&set_tags).map_err(|e| e.to_string())?;
+    
+    conn.execute(
+        "INSERT INTO tbl_ledger_rule (id, account_id, match_field, match_type, match_value, set_category, set_tags, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![id, account_id, match_field, match_type, match_value, set_category, tags_json, now],
+    )
+    .map_err(|e| e.to_string())?;
+    
+    Ok(LedgerRule {
+        id,
+        account_id,
+        match_field,
+        match_type,
+        match_value,
+        set_category,
+        set_tags,
+        created_at: now,
+    })
+}
+
+#[tauri::command]
+fn list_ledger_rules(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<Vec<LedgerRule>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    load_ledger_rules(&conn, &account_id)
+}
+
+#[tauri::command]
+fn delete_ledger_rule(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    rule_id: String,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    let account_id: String = conn
+        .query_row(
+            "SELECT account_id FROM tbl_ledger_rule WHERE id = ?1",
+            [&rule_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "해당 규칙을 찾을 수 없습니다.".to_string())?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    conn.execute("DELETE FROM tbl_ledger_rule WHERE id = ?1", [&rule_id])
+        .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+// 해당 월 항목에 규칙 적용 (규칙은 생성 순서대로, 카테고리는 먼저 일치한 규칙 우선, 태그는 모두 추가)
+// 변경된 항목 수 반환
+#[tauri::command]
+fn apply_ledger_rules(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: String,
+) -> Result<u32, String> {
+    // 빈 문자열이면 LIKE '%'로 계정 전체가 대상이 되므로 먼저 검증
+    validate_year_month(&year_month)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    
+    let rules = load_ledger_rules(&tx, &account_id)?;
+    if rules.is_empty() {
+        return Ok(0);
+    }
+    
+    let entries = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
+                        payment_method, memo, color, created_at, updated_at
+                 FROM tbl_ledger_entry 
+                 WHERE account_id = ?1 AND date LIKE ?2
+                 ORDER BY date, created_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let date_pattern = format!("{}%", year_month);
+        let rows = stmt
+            .query_map(rusqlite::params![account_id, date_pattern], ledger_entry_from_row)
+            .map_err(|e| e.to_string())?;
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| e.to_string())?);
+        }
+        entries
+    };
+    
+    let now = Utc::now().to_rfc3339();
+    let mut changed = 0u32;
+    
+    for mut entry in entries {
+        let matched: Vec<&LedgerRule> = rules
+            .iter()
+            .filter(|rule| ledger_rule_matches(rule, &entry))
+            .collect();
+        if matched.is_empty() {
+            continue;
+        }
+        
+        entry.tags = load_ledger_tags(&tx, &entry.id)?;
+        let new_category = matched
+            .iter()
+            .find_map(|rule| rule.set_category.clone())
+            .unwrap_or_else(|| entry.category.clone());
+        let mut new_tags: Vec<String> = matched
+            .iter()
+            .flat_map(|rule| rule.set_tags.iter())
+            .filter(|tag| !entry.tags.contains(tag))
+            .cloned()
+            .collect();
+        new_tags.sort();
+        new_tags.dedup();
+        if new_category == entry.category && new_tags.is_empty() {
+            continue;
+        }
+        
+        let snapshot_before: Option<String> = tx
+            .query_row(
+                "SELECT json_object(
+                    'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                    'date', date, 'title', title, 'category', category, 'platform', platform,
+                    'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                    'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+                ) FROM tbl_ledger_entry WHERE id = ?1",
+                [&entry.id],
+                |row| row.get(0),
+            )
+            .ok();
+        
+        tx.execute(
+            "UPDATE tbl_ledger_entry SET category = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![new_category, now, entry.id],
+        )
+        .map_err(|e| e.to_string())?;
+        for tag in &new_tags {
+            let tag_id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT OR IGNORE INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![tag_id, entry.id, tag, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        
+        // 히스토리 기록
+        entry.category = new_category;
+        entry.tags.extend(new_tags);
+        entry.tags.sort();
+        entry.updated_at = now.clone();
+        let snapshot_after = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let history_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+             VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
+            rusqlite::params![history_id, entry.id, snapshot_before, snapshot_after, now],
+        )
+        .map_err(|e| e.to_string())?;
+        
+        changed += 1;
+    }
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(changed)
+}
+
+// 색상이 비어 있는 기존 항목에 카테고리 색상 채우기 (갱신된 항목 수 반환)
+// tbl_category에 같은 이름이 있는 카테고리만 대상 (category_color 참고)
+#[tauri::command]
+fn backfill_entry_colors(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<u32, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    
+    let targets: Vec<(LedgerEntry, String)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT e.id, e.account_id, e.type, e.amount, e.date, e.title, e.category, e.platform, e.url, e.merchant,
+                        e.payment_method, e.memo, e.color, e.created_at, e.updated_at, c.color
+                 FROM tbl_ledger_entry e
+                 JOIN tbl_category c ON c.name = e.category
+                 WHERE e.account_id = ?1 AND e.color IS NULL AND c.color IS NOT NULL
+                 ORDER BY e.date, e.created_at, e.id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([&account_id], |row| Ok((ledger_entry_from_row(row)?, row.get(15)?)))
+            .map_err(|e| e.to_string())?;
+        let mut targets = Vec::new();
+        for row in rows {
+            targets.push(row.map_err(|e| e.to_string())?);
+        }
+        targets
+    };
+    
+    let now = Utc::now().to_rfc3339();
+    let mut updated = 0u32;
+    
+    for (mut entry, color) in targets {
+        let snapshot_before: String = tx
+            .query_row(
+                "SELECT json_object(
+                    'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
+                    'date', date, 'title', title, 'category', category, 'platform', platform,
+                    'url', url, 'merchant', merchant, 'payment_method', payment_method,
+                    'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
+                ) FROM tbl_ledger_entry WHERE id = ?1",
+                [&entry.id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        
+        tx.execute(
+            "UPDATE tbl_ledger_entry SET color = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![color, now, entry.id],
+        )
+        .map_err(|e| e.to_string())?;
+        
+        // 히스토리 기록
+        entry.color = Some(color);
+        entry.tags = load_ledger_tags(&tx, &entry.id)?;
+        entry.updated_at = now.clone();
+        let snapshot_after = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let history_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+             VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
+            rusqlite::params![history_id, entry.id, snapshot_before, snapshot_after, now],
+        )
+        .map_err(|e| e.to_string())?;
+        
+        updated += 1;
+    }
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(updated)
+}
+
+const LEDGER_BACKUP_VERSION: u32 = 1;
+
+// 가계부 계정 하나를 JSON 문자열로 백업 (다른 기기로 옮기거나 공유용)
+#[tauri::command]
+fn backup_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<String, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    // 백업 파일은 외부에 전달될 수 있으므로 패스워드 해시/만료일은 제외
+    let mut account = conn
+        .query_row(
+            "SELECT id, nickname, password_hash, password_expires_at, created_at, updated_at, currency_symbol
+             FROM tbl_ledger_account WHERE id = ?1",
+            [&account_id],
+            ledger_account_from_row,
+        )
+        .map_err(|_| "해당 가계부 계정을 찾을 수 없습니다.".to_string())?;
+    account.password_hash = None;
+    account.password_expires_at = None;
+    
+    let mut entries = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
+                        payment_method, memo, color, created_at, updated_at, source_provider, source_payment_id
+                 FROM tbl_ledger_entry 
+                 WHERE account_id = ?1
+                 ORDER BY date, created_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([&account_id], |row| {
+                Ok(LedgerBackupEntry {
+                    entry: ledger_entry_from_row(row)?,
+                    source_provider: row.get(15)?,
+                    source_payment_id: row.get(16)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| e.to_string())?);
+        }
+        entries
+    };
+    for backup_entry in &mut entries {
+        backup_entry.entry.tags = load_ledger_tags(&conn, &backup_entry.entry.id)?;
+    }
+    
+    // 삭제된 항목의 히스토리도 포함
+    let history = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT h.id, h.entry_id, h.action, h.snapshot_before, h.snapshot_after, h.created_at
+                 FROM tbl_ledger_history h
+                 LEFT JOIN tbl_ledger_entry e ON e.id = h.entry_id
+                 WHERE e.account_id = ?1
+                    OR (e.id IS NULL AND COALESCE(
+                            json_extract(h.snapshot_before, '$.account_id'),
+                            json_extract(h.snapshot_after, '$.accountId')
+                        ) = ?1)
+                 ORDER BY h.created_at, h.rowid",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([&account_id], |row| {
+                Ok(LedgerHistory {
+                    id: row.get(0)?,
+                    entry_id: row.get(1)?,
+                    action: row.get(2)?,
+                    snapshot_before: row.get(3)?,
+                    snapshot_after: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        let mut history = Vec::new();
+        for row in rows {
+            history.push(row.map_err(|e| e.to_string())?);
+        }
+        history
+    };
+    
+    let rules = load_ledger_rules(&conn, &account_id)?;
+    
+    let backup = LedgerAccountBackup {
+        version: LEDGER_BACKUP_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        account,
+        entries,
+        history,
+        rules,
+    };
+    serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())
+}
+
+// 히스토리 스냅샷의 계정/항목 ID를 새 값으로 바꿈 (스냅샷 형식에 따라 snake/camel 키 모두 처리)
+fn remap_ledger_snapshot(snapshot: Option<String>, account_id: &str, entry_id: &str) -> Option<String> {
+    snapshot.map(|json| match serde_json::from_str::<Value>(&json) {
+        Ok(Value::Object(mut map)) => {
+            for key in ["account_id", "accountId"] {
+                if map.contains_key(key) {
+                    map.insert(key.to_string(), json!(account_id));
+                }
+            }
+            if map.contains_key("id") {
+                map.insert("id".to_string(), json!(entry_id));
+            }
+            Value::Object(map).to_string()
+        }
+        _ => json,
+    })
+}
+
+// 백업 JSON을 가져와 계정 복원 (target_account_id를 지정하면 모든 데이터를 해당 계정으로 옮기고 ID를 새로 발급)
+// 새로 만드는 계정은 password를 지정한 경우에만 보호됨 (백업 파일의 패스워드 정보는 사용하지 않음)
+#[tauri::command]
+fn restore_ledger_account(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    json_content: String,
+    target_account_id: Option<String>,
+    password: Option<String>,
+) -> Result<String, String> {
+    let backup: LedgerAccountBackup = serde_json::from_str(&json_content)
+        .map_err(|e| format!("백업 파일 형식이 올바르지 않습니다: {}", e))?;
+    if backup.version > LEDGER_BACKUP_VERSION {
+        return Err(format!("지원하지 않는 백업 버전입니다: {}", backup.version));
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&tx)?;
+    
+    let account_id = target_account_id.unwrap_or_else(|| backup.account.id.clone());
+    
+    let account_exists: bool = tx
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tbl_ledger_account WHERE id = ?1)",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if account_exists {
+        // 기존 계정에 합치는 경우 계정 정보는 그대로 둠
+        ensure_ledger_unlocked(&tx, &state, &account_id)?;
+    } else {
+        let account = &backup.account;
+        if let Some(password) = &password {
+            ensure_password_strong_enough(password)?;
+        }
+        let password_hash = password.as_deref().map(hash_password);
+        let password_expires_at = password_hash
+            .as_ref()
+            .map(|_| (Utc::now() + chrono::Duration::days(30)).to_rfc3339());
+        // 같은 닉네임의 계정이 이미 있으면 번호를 붙여 구분
+        let nickname = unique_ledger_nickname(&tx, &account.nickname)?;
+        tx.execute(
+            "INSERT INTO tbl_ledger_account (id, nickname, password_hash, password_expires_at, created_at, updated_at, currency_symbol) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                account_id, nickname, password_hash, password_expires_at,
+                account.created_at, account.updated_at, account.currency_symbol
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    // 기존 항목과 ID가 겹치면 (다른 계정으로 옮기거나 같은 계정에 다시 복원) 모든 ID를 새로 발급
+    let mut remap = account_id != backup.account.id || account_exists;
+    for backup_entry in &backup.entries {
+        if remap {
+            break;
+        }
+        remap = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM tbl_ledger_entry WHERE id = ?1)",
+                [&backup_entry.entry.id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    
+    // 다른 계정으로 옮기면 같은 DB에서도 충돌하지 않도록 항목 ID를 새로 발급
+    let mut entry_ids: HashMap<String, String> = HashMap::new();
+    let mut new_entry_id = |old_id: &str| -> String {
+        if !remap {
+            return old_id.to_string();
+        }
+        entry_ids
+            .entry(old_id.to_string())
+            .or_insert_with(|| Uuid::new_v4().to_string())
+            .clone()
+    };
+    
+    let now = Utc::now().to_rfc3339();
+    for backup_entry in &backup.entries {
+        let entry = &backup_entry.entry;
+        let entry_id = new_entry_id(&entry.id);
+        
+        // 같은 결제가 이미 이 계정에 연결되어 있으면 항목만 복원하고 연결은 생략
+        let (mut source_provider, mut source_payment_id) =
+            (backup_entry.source_provider.clone(), backup_entry.source_payment_id);
+        if let (Some(provider), Some(payment_id)) = (&source_provider, source_payment_id) {
+            let linked: bool = tx
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM tbl_ledger_entry
+                     WHERE account_id = ?1 AND source_provider = ?2 AND source_payment_id = ?3)",
+                    rusqlite::params![account_id, provider, payment_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if linked {
+                tracing::warn!(entry_id = %entry.id, "payment already linked in target account; restoring without link");
+                source_provider = None;
+                source_payment_id = None;
+            }
+        }
+        
+        tx.execute(
+            "INSERT INTO tbl_ledger_entry 
+             (id, account_id, type, amount, date, title, category, platform, url, merchant, payment_method, memo, color,
+              created_at, updated_at, source_provider, source_payment_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            rusqlite::params![
+                entry_id, account_id, entry.r#type, entry.amount, entry.date, entry.title,
+                entry.category, entry.platform, entry.url, entry.merchant, entry.payment_method,
+                entry.memo, entry.color, entry.created_at, entry.updated_at, source_provider, source_payment_id
+            ],
+        )
+        .map_err(|e| format!("항목을 복원하지 못했습니다 ({}): {}", entry.id, e))?;
+        
+        for tag in &entry.tags {
+            let tag_id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT OR IGNORE INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![tag_id, entry_id, tag, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    
+    for history in &backup.history {
+        let entry_id = new_entry_id(&history.entry_id);
+        let (history_id, snapshot_before, snapshot_after) = if remap {
+            (
+                Uuid::new_v4().to_string(),
+                remap_ledger_snapshot(history.snapshot_before.clone(), &account_id, &entry_id),
+                remap_ledger_snapshot(history.snapshot_after.clone(), &account_id, &entry_id),
+            )
+        } else {
+            (history.id.clone(), history.snapshot_before.clone(), history.snapshot_after.clone())
+        };
+        tx.execute(
+            "INSERT OR IGNORE INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![history_id, entry_id, history.action, snapshot_before, snapshot_after, history.created_at],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    for rule in &backup.rules {
+        let rule_id = if remap { Uuid::new_v4().to_string() } else { rule.id.clone() };
+        let tags_json = serde_json::to_string(&rule.set_tags).map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT OR IGNORE INTO tbl_ledger_rule (id, account_id, match_field, match_type, match_value, set_category, set_tags, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                rule_id, account_id, rule.match_field, rule.match_type, rule.match_value,
+                rule.set_category, tags_json, rule.created_at
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    
+    tracing::info!(
+        account_id = %account_id,
+        entries = backup.entries.len(),
+        history = backup.history.len(),
+        "ledger account restored"
+    );
+    Ok(account_id)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaymentMethodStat {
+    payment_method: Option<String>,
+    expense_total: i64,
+    income_total: i64,
+    entry_count: i64,
+    expense_pct: f64,
+}
+
+// 결제 수단별 수입/지출 합계 (year_month 미지정 시 전체 기간)
+#[tauri::command]
+fn get_ledger_payment_method_breakdown(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: Option<String>,
+) -> Result<Vec<PaymentMethodStat>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let date_pattern = format!("{}%", year_month.unwrap_or_default());
+    let mut stmt = conn
+        .prepare(
+            "SELECT payment_method,
+                    COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0) AS expense_total,
+                    COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0) AS income_total,
+                    COUNT(*)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND date LIKE ?2
+             GROUP BY payment_method
+             ORDER BY expense_total DESC, payment_method",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, date_pattern], |row| {
+            let payment_method: Option<String> = row.get(0)?;
+            Ok(PaymentMethodStat {
+                payment_method: Some(payment_method.unwrap_or_else(|| "(미분류)".to_string())),
+                expense_total: row.get(1)?,
+                income_total: row.get(2)?,
+                entry_count: row.get(3)?,
+                expense_pct: 0.0,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut stats = Vec::new();
+    for row in rows {
+        stats.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    // 전체 지출 대비 비율은 집계 후 계산
+    let total_expense: i64 = stats.iter().map(|s| s.expense_total).sum();
+    if total_expense > 0 {
+        for stat in &mut stats {
+            stat.expense_pct = stat.expense_total as f64 / total_expense as f64 * 100.0;
+        }
+    }
+    
+    Ok(stats)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TagSummary {
+    tag: String,
+    count: i64,
+    total_amount: i64,
+}
+
+// 해당 월 태그별 항목 수/금액 합계 (entry_type 미지정 시 수입/지출 모두 합산)
+#[tauri::command]
+fn get_ledger_tag_summary(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: String,
+    entry_type: Option<String>,
+) -> Result<Vec<TagSummary>, String> {
+    validate_year_month(&year_month)?;
+    if let Some(t) = &entry_type {
+        if t != "income" && t != "expense" {
+            return Err("유효하지 않은 항목 유형입니다.".to_string());
+        }
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let date_pattern = format!("{}%", year_month);
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.tag, COUNT(*), COALESCE(SUM(e.amount), 0) AS total
+             FROM tbl_ledger_tag t
+             JOIN tbl_ledger_entry e ON e.id = t.entry_id
+             WHERE e.account_id = ?1 AND e.date LIKE ?2
+               AND (?3 IS NULL OR e.type = ?3)
+             GROUP BY t.tag
+             ORDER BY total DESC, t.tag",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, date_pattern, entry_type], |row| {
+            Ok(TagSummary {
+                tag: row.get(0)?,
+                count: row.get(1)?,
+                total_amount: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut summary = Vec::new();
+    for row in rows {
+        summary.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(summary)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IncomeSource {
+    merchant: Option<String>,
+    category: String,
+    total_amount: i64,
+    entry_count: i64,
+    last_date: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonthlyIncome {
+    year_month: String,
+    total_income: i64,
+    source_count: i64,
+}
+
+// 수입원(거래처 + 카테고리)별 수입 합계
 #[tauri::command]
-async fn proxy_request(
-    url: String,
-    method: String,
-    headers: HashMap<String, String>,
-    body: Option<String>,
-) -> Result<ProxyResponse, String> {
-    tauri::async_runtime::spawn_blocking(move || {
-        let mut easy = Easy::new();
-        easy.url(&url).map_err(|e| e.to_string())?;
-        easy.follow_location(true).map_err(|e| e.to_string())?;
-        easy.accept_encoding("").map_err(|e| e.to_string())?;
+fn get_ledger_income_sources(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: Option<String>,
+) -> Result<Vec<IncomeSource>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let date_pattern = format!("{}%", year_month.unwrap_or_default());
+    let mut stmt = conn
+        .prepare(
+            "SELECT merchant, category, SUM(amount) AS total_amount, COUNT(*), MAX(date)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND type = 'income' AND date LIKE ?2
+             GROUP BY merchant, category
+             ORDER BY total_amount DESC, merchant, category",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, date_pattern], |row| {
+            Ok(IncomeSource {
+                merchant: row.get(0)?,
+                category: row.get(1)?,
+                total_amount: row.get(2)?,
+                entry_count: row.get(3)?,
+                last_date: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut sources = Vec::new();
+    for row in rows {
+        sources.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(sources)
+}
+
+// 월별 수입 추이 (source_count는 서로 다른 거래처+카테고리 조합 수)
+#[tauri::command]
+fn get_ledger_income_trend(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+) -> Result<Vec<MonthlyIncome>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT strftime('%Y-%m', date) AS ym, SUM(amount),
+                    COUNT(DISTINCT COALESCE(merchant, '') || char(31) || category)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND type = 'income'
+             GROUP BY ym
+             ORDER BY ym",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map([account_id], |row| {
+            Ok(MonthlyIncome {
+                year_month: row.get(0)?,
+                total_income: row.get(1)?,
+                source_count: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut trend = Vec::new();
+    for row in rows {
+        trend.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(trend)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NetWorthPoint {
+    period: String,
+    income: i64,
+    expense: i64,
+    net: i64,
+    cumulative: i64,
+}
+
+// 기간별 수입/지출과 누적 잔액 (granularity: daily, weekly, monthly)
+#[tauri::command]
+fn get_ledger_net_worth_timeline(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    granularity: String,
+) -> Result<Vec<NetWorthPoint>, String> {
+    let period_expr = match granularity.as_str() {
+        "daily" => "date",
+        "weekly" => "strftime('%Y-%W', date)",
+        "monthly" => "strftime('%Y-%m', date)",
+        _ => return Err(format!("지원하지 않는 기간 단위입니다: {}", granularity)),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} AS period,
+                    COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1
+             GROUP BY period
+             ORDER BY period",
+            period_expr
+        ))
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map([&account_id], |row| {
+            let income: i64 = row.get(1)?;
+            let expense: i64 = row.get(2)?;
+            Ok(NetWorthPoint {
+                period: row.get(0)?,
+                income,
+                expense,
+                net: income - expense,
+                cumulative: 0,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    // 기간 순서대로 누적 잔액 계산
+    let mut timeline = Vec::new();
+    let mut running_total = 0;
+    for row in rows {
+        let mut point = row.map_err(|e| e.to_string())?;
+        running_total += point.net;
+        point.cumulative = running_total;
+        timeline.push(point);
+    }
+    
+    Ok(timeline)
+}
 
-        easy.cookie_file("").map_err(|e| e.to_string())?; // enable cookie engine in memory
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WeeklySummary {
+    year: i32,
+    week: u32,
+    // ISO 주의 월요일~일요일 (YYYY-MM-DD)
+    from_date: String,
+    to_date: String,
+    income_total: i64,
+    expense_total: i64,
+    net: i64,
+    entry_count: i64,
+    top_category: Option<String>,
+}
 
-        let payload_bytes = body.map(|b| b.into_bytes());
+// ISO 연도/주차의 주간 합계 (지출이 가장 큰 카테고리 포함)
+fn ledger_weekly_summary(
+    conn: &Connection,
+    account_id: &str,
+    year: i32,
+    week: u32,
+) -> Result<WeeklySummary, String> {
+    let from = chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)
+        .ok_or_else(|| format!("유효하지 않은 주차입니다: {}-W{:02}", year, week))?;
+    let to = from + chrono::Duration::days(6);
+    let from_date = from.format("%Y-%m-%d").to_string();
+    let to_date = to.format("%Y-%m-%d").to_string();
+    
+    let (income_total, expense_total, entry_count, top_category) = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0),
+                COUNT(*),
+                (SELECT category FROM tbl_ledger_entry
+                 WHERE account_id = ?1 AND type = 'expense' AND date >= ?2 AND date <= ?3
+                 GROUP BY category
+                 ORDER BY SUM(amount) DESC, category
+                 LIMIT 1)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND date >= ?2 AND date <= ?3",
+            rusqlite::params![account_id, from_date, to_date],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    Ok(WeeklySummary {
+        year,
+        week,
+        from_date,
+        to_date,
+        income_total,
+        expense_total,
+        net: income_total - expense_total,
+        entry_count,
+        top_category,
+    })
+}
 
-        match method.as_str() {
-            "POST" => {
-                easy.post(true).map_err(|e| e.to_string())?;
-                if let Some(ref bytes) = payload_bytes {
-                    easy.post_fields_copy(bytes).map_err(|e| e.to_string())?;
-                }
-            }
-            "PUT" => {
-                easy.custom_request("PUT").map_err(|e| e.to_string())?;
+#[tauri::command]
+fn get_ledger_weekly_summary(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year: i32,
+    week: u32,
+) -> Result<WeeklySummary, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    ledger_weekly_summary(&conn, &account_id, year, week)
+}
+
+// 해당 ISO 연도의 전체 주차(52 또는 53주) 요약
+#[tauri::command]
+fn list_ledger_weekly_summaries(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year: i32,
+) -> Result<Vec<WeeklySummary>, String> {
+    // 12월 28일은 항상 해당 ISO 연도의 마지막 주에 속함
+    let dec_28 = chrono::NaiveDate::from_ymd_opt(year, 12, 28)
+        .ok_or_else(|| format!("유효하지 않은 연도입니다: {}", year))?;
+    let last_week = chrono::Datelike::iso_week(&dec_28).week();
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    (1..=last_week)
+        .map(|week| ledger_weekly_summary(&conn, &account_id, year, week))
+        .collect()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CategoryMonthDelta {
+    category: String,
+    amount_a: i64,
+    amount_b: i64,
+    delta: i64,
+    // month_a 금액이 0이면 변화율을 정의할 수 없으므로 None
+    change_pct: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerMonthComparison {
+    month_a: String,
+    month_b: String,
+    income_a: i64,
+    income_b: i64,
+    income_delta: i64,
+    expense_a: i64,
+    expense_b: i64,
+    expense_delta: i64,
+    categories: Vec<CategoryMonthDelta>,
+}
+
+// YYYY-MM 형식 검증 후 해당 월 1일 반환
+fn validate_year_month(year_month: &str) -> Result<chrono::NaiveDate, String> {
+    let parsed = if year_month.len() == 7 {
+        chrono::NaiveDate::parse_from_str(&format!("{}-01", year_month), "%Y-%m-%d").ok()
+    } else {
+        None
+    };
+    parsed.ok_or_else(|| format!("월은 YYYY-MM 형식이어야 합니다: {}", year_month))
+}
+
+fn change_pct(before: i64, after: i64) -> Option<f64> {
+    if before == 0 {
+        None
+    } else {
+        Some((after - before) as f64 / before as f64 * 100.0)
+    }
+}
+
+// 두 달(YYYY-MM)의 카테고리별 지출과 전체 수입/지출 비교 (delta = month_b - month_a)
+#[tauri::command]
+fn compare_ledger_months(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    month_a: String,
+    month_b: String,
+) -> Result<LedgerMonthComparison, String> {
+    validate_year_month(&month_a)?;
+    validate_year_month(&month_b)?;
+    
+    let mut comparison = LedgerMonthComparison {
+        month_a: month_a.clone(),
+        month_b: month_b.clone(),
+        income_a: 0,
+        income_b: 0,
+        income_delta: 0,
+        expense_a: 0,
+        expense_b: 0,
+        expense_delta: 0,
+        categories: Vec::new(),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(comparison);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    // 두 달을 한 번에 조회해 월/유형/카테고리별로 집계
+    let mut stmt = conn
+        .prepare(
+            "SELECT substr(date, 1, 7) AS ym, type, category, COALESCE(SUM(amount), 0)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND substr(date, 1, 7) IN (?2, ?3)
+             GROUP BY ym, type, category",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, month_a, month_b], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut by_category: HashMap<String, (i64, i64)> = HashMap::new();
+    for row in rows {
+        let (year_month, entry_type, category, amount) = row.map_err(|e| e.to_string())?;
+        // month_a와 month_b가 같으면 양쪽에 모두 반영
+        for (is_a, month) in [(true, &month_a), (false, &month_b)] {
+            if &year_month != month {
+                continue;
             }
-            "DELETE" => {
-                easy.custom_request("DELETE").map_err(|e| e.to_string())?;
+            match (entry_type.as_str(), is_a) {
+                ("income", true) => comparison.income_a += amount,
+                ("income", false) => comparison.income_b += amount,
+                (_, is_a) => {
+                    let totals = by_category.entry(category.clone()).or_insert((0, 0));
+                    if is_a {
+                        comparison.expense_a += amount;
+                        totals.0 += amount;
+                    } else {
+                        comparison.expense_b += amount;
+                        totals.1 += amount;
+                    }
+                }
             }
-            _ => {} // GET by default
         }
+    }
+    comparison.income_delta = comparison.income_b - comparison.income_a;
+    comparison.expense_delta = comparison.expense_b - comparison.expense_a;
+    
+    comparison.categories = by_category
+        .into_iter()
+        .map(|(category, (amount_a, amount_b))| CategoryMonthDelta {
+            category,
+            amount_a,
+            amount_b,
+            delta: amount_b - amount_a,
+            change_pct: change_pct(amount_a, amount_b),
+        })
+        .collect();
+    // 변화량이 큰 카테고리부터
+    comparison.categories.sort_by(|a, b| {
+        b.delta
+            .abs()
+            .cmp(&a.delta.abs())
+            .then_with(|| a.category.cmp(&b.category))
+    });
+    
+    Ok(comparison)
+}
+
+// 해당 월 지출을 그룹 컬럼(category/merchant)별로 합산해 큰 순서로 limit개 반환 (빈 값 제외)
+fn ledger_top_expenses(
+    conn: &Connection,
+    account_id: &str,
+    year_month: &str,
+    group_column: &str,
+    limit: i64,
+) -> Result<Vec<(String, i64)>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {column}, COALESCE(SUM(amount), 0) AS total
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND type = 'expense' AND date LIKE ?2
+               AND {column} IS NOT NULL AND TRIM({column}) != ''
+             GROUP BY {column}
+             ORDER BY total DESC, {column}
+             LIMIT ?3",
+            column = group_column
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, format!("{}%", year_month), limit], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut totals = Vec::new();
+    for row in rows {
+        totals.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(totals)
+}
+
+// 천 단위 구분 기호를 넣은 금액 (음수는 기호 앞에 -)
+fn format_ledger_amount(currency_symbol: &str, amount: i64) -> String {
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    let sign = if amount < 0 { "-" } else { "" };
+    format!("{}{}{}", sign, currency_symbol, grouped)
+}
+
+// 고정폭 글꼴 기준 표시 폭 (한글 등 ASCII 외 문자는 2칸)
+fn display_width(text: &str) -> usize {
+    text.chars().map(|ch| if ch.is_ascii() { 1 } else { 2 }).sum()
+}
+
+// 라벨/금액 두 열을 정렬한 줄 목록 (라벨은 왼쪽, 금액은 오른쪽 정렬)
+fn format_aligned_rows(rows: &[(String, String)]) -> Vec<String> {
+    let label_width = rows.iter().map(|(label, _)| display_width(label)).max().unwrap_or(0);
+    let amount_width = rows.iter().map(|(_, amount)| amount.chars().count()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, amount)| {
+            format!(
+                "{}{}  {:>width$}",
+                label,
+                " ".repeat(label_width - display_width(label)),
+                amount,
+                width = amount_width
+            )
+        })
+        .collect()
+}
+
+// 월간 가계부 요약을 복사/공유용 텍스트로 생성 (수입/지출/순액, 지출 상위 카테고리/거래처 5개)
+#[tauri::command]
+fn export_ledger_summary_text(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    year_month: String,
+) -> Result<String, String> {
+    validate_year_month(&year_month)?;
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let (nickname, currency_symbol): (String, String) = conn
+        .query_row(
+            "SELECT nickname, currency_symbol FROM tbl_ledger_account WHERE id = ?1",
+            [&account_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "해당 가계부 계정을 찾을 수 없습니다.".to_string())?;
+    
+    let (income_total, expense_total): (i64, i64) = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND date LIKE ?2",
+            rusqlite::params![account_id, format!("{}%", year_month)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let top_categories = ledger_top_expenses(&conn, &account_id, &year_month, "category", 5)?;
+    let top_merchants = ledger_top_expenses(&conn, &account_id, &year_month, "merchant", 5)?;
+    
+    let mut lines = vec![format!("{} 가계부 요약 ({})", nickname, year_month), String::new()];
+    lines.extend(format_aligned_rows(&[
+        ("수입".to_string(), format_ledger_amount(&currency_symbol, income_total)),
+        ("지출".to_string(), format_ledger_amount(&currency_symbol, expense_total)),
+        ("순액".to_string(), format_ledger_amount(&currency_symbol, income_total - expense_total)),
+    ]));
+    for (title, totals) in [("지출 상위 카테고리", top_categories), ("지출 상위 거래처", top_merchants)] {
+        lines.push(String::new());
+        lines.push(format!("[{}]", title));
+        if totals.is_empty() {
+            lines.push("(내역 없음)".to_string());
+            continue;
+        }
+        let rows: Vec<(String, String)> = totals
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, amount))| {
+                (format!("{}. {}", i + 1, name), format_ledger_amount(&currency_symbol, amount))
+            })
+            .collect();
+        lines.extend(format_aligned_rows(&rows));
+    }
+    
+    Ok(lines.join("\n"))
+}
 
-        let mut header_list = List::new();
-        let mut cookie_header: Option<String> = None;
-        let mut request_headers: Vec<String> = Vec::new();
+// 차트 조회 최대 기간 (10년)
+const MAX_CHART_MONTHS: usize = 120;
 
-        for (key, value) in headers {
-            if key.eq_ignore_ascii_case("cookie") {
-                cookie_header = Some(value);
-            } else {
-                let header_line = format!("{key}: {value}");
-                header_list
-                    .append(&header_line)
-                    .map_err(|e| e.to_string())?;
-                request_headers.push(header_line);
-            }
-        }
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CategoryMonthlyChart {
+    months: Vec<String>,
+    // 기간 내 지출 합계가 큰 순서
+    categories: Vec<String>,
+    // 카테고리별 월 지출 합계 (months와 같은 순서, 없는 달은 0)
+    data: HashMap<String, Vec<i64>>,
+}
 
-        if let Some(cookies) = cookie_header {
-            let cookie_line = format!("Cookie: {cookies}");
-            header_list
-                .append(&cookie_line)
-                .map_err(|e| e.to_string())?;
-            request_headers.push(cookie_line);
+// 카테고리별 월 지출 추이 (누적 막대 차트용)
+#[tauri::command]
+fn get_ledger_category_monthly_chart(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    from_year_month: String,
+    to_year_month: String,
+) -> Result<CategoryMonthlyChart, String> {
+    let from = validate_year_month(&from_year_month)?;
+    let to = validate_year_month(&to_year_month)?;
+    if from > to {
+        return Err("시작 월이 종료 월보다 늦습니다.".to_string());
+    }
+    
+    let mut months = Vec::new();
+    let mut cursor = from;
+    while cursor <= to {
+        if months.len() >= MAX_CHART_MONTHS {
+            return Err(format!("조회 기간은 최대 {}개월입니다.", MAX_CHART_MONTHS));
+        }
+        months.push(cursor.format("%Y-%m").to_string());
+        cursor = cursor
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| "날짜 범위를 벗어났습니다.".to_string())?;
+    }
+    
+    let mut chart = CategoryMonthlyChart {
+        months,
+        categories: Vec::new(),
+        data: HashMap::new(),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(chart);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT category, strftime('%Y-%m', date) AS ym, SUM(amount)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND type = 'expense'
+               AND strftime('%Y-%m', date) BETWEEN ?2 AND ?3
+             GROUP BY category, ym",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![account_id, from_year_month, to_year_month], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    
+    // 월 인덱스에 맞춰 피벗
+    let month_index: HashMap<&str, usize> = chart
+        .months
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.as_str(), i))
+        .collect();
+    let mut data: HashMap<String, Vec<i64>> = HashMap::new();
+    for row in rows {
+        let (category, year_month, amount) = row.map_err(|e| e.to_string())?;
+        if let Some(&index) = month_index.get(year_month.as_str()) {
+            data.entry(category).or_insert_with(|| vec![0; chart.months.len()])[index] += amount;
         }
+    }
+    
+    let mut categories: Vec<(String, i64)> = data
+        .iter()
+        .map(|(category, values)| (category.clone(), values.iter().sum()))
+        .collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    chart.categories = categories.into_iter().map(|(category, _)| category).collect();
+    chart.data = data;
+    
+    Ok(chart)
+}
 
-        easy.http_headers(header_list).map_err(|e| e.to_string())?;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MerchantTrendPoint {
+    year_month: String,
+    expense_total: i64,
+    income_total: i64,
+    entry_count: i64,
+}
 
-        let mut response_body = Vec::<u8>::new();
-        let mut response_headers = Vec::<String>::new();
-        {
-            let mut transfer = easy.transfer();
-            transfer
-                .header_function(|data| {
-                    if let Ok(line) = std::str::from_utf8(data) {
-                        response_headers.push(line.trim_end().to_string());
-                    }
-                    true
-                })
-                .map_err(|e| e.to_string())?;
-            transfer
-                .write_function(|data| {
-                    response_body.extend_from_slice(data);
-                    Ok(data.len())
-                })
-                .map_err(|e| e.to_string())?;
-            transfer.perform().map_err(|e| e.to_string())?;
+// 특정 가맹점(대소문자 무시)의 최근 months개월(이번 달 포함) 월별 금액, 거래 없는 달은 0
+#[tauri::command]
+fn get_ledger_merchant_trend(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    merchant: String,
+    months: u32,
+) -> Result<Vec<MerchantTrendPoint>, String> {
+    if months == 0 || months as usize > MAX_CHART_MONTHS {
+        return Err(format!("조회 기간은 1~{}개월이어야 합니다.", MAX_CHART_MONTHS));
+    }
+    let current = validate_year_month(&Utc::now().format("%Y-%m").to_string())?;
+    let start = current
+        .checked_sub_months(chrono::Months::new(months - 1))
+        .ok_or_else(|| "날짜 범위를 벗어났습니다.".to_string())?;
+    
+    let mut trend = Vec::new();
+    let mut cursor = start;
+    while cursor <= current {
+        trend.push(MerchantTrendPoint {
+            year_month: cursor.format("%Y-%m").to_string(),
+            expense_total: 0,
+            income_total: 0,
+            entry_count: 0,
+        });
+        cursor = cursor
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| "날짜 범위를 벗어났습니다.".to_string())?;
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(trend);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT strftime('%Y-%m', date) AS ym,
+                    COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0),
+                    COUNT(*)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND LOWER(merchant) = LOWER(?2) AND date >= ?3
+             GROUP BY ym",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![account_id, merchant.trim(), start.format("%Y-%m-%d").to_string()],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    for row in rows {
+        let (year_month, expense_total, income_total, entry_count) = row.map_err(|e| e.to_string())?;
+        if let Some(point) = trend.iter_mut().find(|point| point.year_month == year_month) {
+            point.expense_total = expense_total;
+            point.income_total = income_total;
+            point.entry_count = entry_count;
         }
+    }
+    
+    Ok(trend)
+}
 
-        let status = easy.response_code().map_err(|e| e.to_string())?;
-        let final_url = easy
-            .effective_url()
-            .map_err(|e| e.to_string())?
-            .map(|u| u.to_string());
+// 반복 지출 판정: 최근 3개월 모두 나타나고 월별 금액 차이가 최소 금액의 20% 이내
+const FORECAST_LOOKBACK_MONTHS: u32 = 3;
+const RECURRING_AMOUNT_TOLERANCE: f64 = 0.2;
 
-        Ok(ProxyResponse {
-            status,
-            body: String::from_utf8_lossy(&response_body).into_owned(),
-            final_url,
-            response_headers,
-            request_headers,
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecurringForecastItem {
+    merchant: Option<String>,
+    title: String,
+    category: String,
+    // lookback_months 순서의 월별 지출 합계
+    monthly_amounts: Vec<i64>,
+    projected_amount: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerForecast {
+    forecast_month: String,
+    lookback_months: Vec<String>,
+    // 첫 항목이 조회 기간 시작 이후이면 false (반복 항목 판정 불가)
+    has_enough_history: bool,
+    projected_total: i64,
+    items: Vec<RecurringForecastItem>,
+}
+
+// 다음 달 반복 지출 예측 (base_month까지의 최근 3개월 기준, 미지정 시 지난달까지)
+// 거래처(없으면 제목) + 카테고리가 같은 지출을 같은 항목으로 보고, 3개월 평균을 예상 금액으로 사용
+#[tauri::command]
+fn forecast_next_month(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    account_id: String,
+    base_month: Option<String>,
+) -> Result<LedgerForecast, String> {
+    let base = match &base_month {
+        Some(month) => validate_year_month(month)?,
+        None => validate_year_month(&Utc::now().format("%Y-%m").to_string())?
+            .checked_sub_months(chrono::Months::new(1))
+            .ok_or_else(|| "날짜 범위를 벗어났습니다.".to_string())?,
+    };
+    let out_of_range = || "날짜 범위를 벗어났습니다.".to_string();
+    let lookback_months = (0..FORECAST_LOOKBACK_MONTHS)
+        .rev()
+        .map(|back| {
+            base.checked_sub_months(chrono::Months::new(back))
+                .map(|month| month.format("%Y-%m").to_string())
+                .ok_or_else(out_of_range)
         })
-    })
-    .await
-    .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()?;
+    let forecast_month = base
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(out_of_range)?
+        .format("%Y-%m")
+        .to_string();
+    
+    let mut forecast = LedgerForecast {
+        forecast_month,
+        lookback_months,
+        has_enough_history: false,
+        projected_total: 0,
+        items: Vec::new(),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(forecast);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let first_month: Option<String> = conn
+        .query_row(
+            "SELECT substr(MIN(date), 1, 7) FROM tbl_ledger_entry WHERE account_id = ?1",
+            [&account_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    forecast.has_enough_history = matches!(&first_month, Some(first) if *first <= forecast.lookback_months[0]);
+    if !forecast.has_enough_history {
+        return Ok(forecast);
+    }
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT substr(date, 1, 7) AS ym, COALESCE(NULLIF(merchant, ''), title) AS match_key, category,
+                    MAX(merchant), MAX(title), SUM(amount)
+             FROM tbl_ledger_entry
+             WHERE account_id = ?1 AND type = 'expense'
+               AND substr(date, 1, 7) BETWEEN ?2 AND ?3
+             GROUP BY ym, match_key, category
+             ORDER BY ym",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![
+                account_id,
+                forecast.lookback_months[0],
+                forecast.lookback_months[forecast.lookback_months.len() - 1]
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let mut candidates: HashMap<(String, String), RecurringForecastItem> = HashMap::new();
+    for row in rows {
+        let (year_month, match_key, category, merchant, title, amount) = row.map_err(|e| e.to_string())?;
+        let index = match forecast.lookback_months.iter().position(|m| *m == year_month) {
+            Some(index) => index,
+            None => continue,
+        };
+        let item = candidates
+            .entry((match_key, category.clone()))
+            .or_insert_with(|| RecurringForecastItem {
+                merchant: None,
+                title: String::new(),
+                category,
+                monthly_amounts: vec![0; forecast.lookback_months.len()],
+                projected_amount: 0,
+            });
+        // 월 순으로 조회하므로 마지막에 덮어쓴 가장 최근 달의 거래처/제목을 표시용으로 사용
+        item.merchant = merchant;
+        item.title = title;
+        item.monthly_amounts[index] += amount;
+    }
+    
+    for mut item in candidates.into_values() {
+        let min = item.monthly_amounts.iter().copied().min().unwrap_or(0);
+        let max = item.monthly_amounts.iter().copied().max().unwrap_or(0);
+        if min <= 0 || (max - min) as f64 > min as f64 * RECURRING_AMOUNT_TOLERANCE {
+            continue;
+        }
+        let total: i64 = item.monthly_amounts.iter().sum();
+        item.projected_amount = (total as f64 / item.monthly_amounts.len() as f64).round() as i64;
+        forecast.projected_total += item.projected_amount;
+        forecast.items.push(item);
+    }
+    forecast.items.sort_by(|a, b| {
+        b.projected_amount
+            .cmp(&a.projected_amount)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    
+    Ok(forecast)
 }
 
-// ========== 가계부 관련 구조체 및 함수 ==========
+// ========== 상품 메타데이터 관련 구조체 및 함수 ==========
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct LedgerAccount {
+struct Category {
     id: String,
-    nickname: String,
-    password_hash: Option<String>,
-    password_expires_at: Option<String>,
+    name: String,
+    color: Option<String>,
+    #[serde(default)]
+    parent_id: Option<String>,
     created_at: String,
-    updated_at: String,
+}
+
+// 카테고리 트리 노드
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CategoryNode {
+    #[serde(flatten)]
+    category: Category,
+    children: Vec<CategoryNode>,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct LedgerEntry {
+struct ProductMeta {
     id: String,
-    account_id: String,
-    r#type: String,
-    amount: i64,
-    date: String,
-    title: String,
-    category: String,
-    platform: Option<String>,
-    url: Option<String>,
-    merchant: Option<String>,
-    payment_method: Option<String>,
+    provider: String,
+    item_id: i64,
     memo: Option<String>,
-    color: Option<String>,
+    url: Option<String>,
+    rating: Option<i32>,
     tags: Vec<String>,
+    categories: Vec<Category>,
     created_at: String,
     updated_at: String,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct LedgerEntryInput {
-    account_id: String,
-    r#type: String,
-    amount: i64,
-    date: String,
-    title: String,
-    category: String,
-    platform: Option<String>,
-    url: Option<String>,
-    merchant: Option<String>,
-    payment_method: Option<String>,
+struct ProductMetaInput {
     memo: Option<String>,
-    color: Option<String>,
+    url: Option<String>,
+    rating: Option<i32>,
     tags: Vec<String>,
+    category_ids: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct LedgerHistory {
-    id: String,
-    entry_id: String,
-    action: String,
-    snapshot_before: Option<String>,
-    snapshot_after: Option<String>,
-    created_at: String,
+fn load_categories(conn: &Connection) -> Result<Vec<Category>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, color, parent_id, created_at FROM tbl_category ORDER BY name, id")
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Category {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                parent_id: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut categories = Vec::new();
+    for row in rows {
+        categories.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(categories)
 }
 
-fn hash_password(password: &str) -> String {
-    let digest = md5::compute(password.as_bytes());
-    format!("{:x}", digest)
+#[tauri::command]
+fn list_categories(
+    app_handle: AppHandle,
+    state: State<AppState>,
+) -> Result<Vec<Category>, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    load_categories(&conn)
 }
 
-fn check_and_reset_expired_passwords(conn: &Connection) -> Result<(), String> {
+#[tauri::command]
+fn create_category(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    name: String,
+    color: Option<String>,
+    parent_id: Option<String>,
+) -> Result<Category, String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    if let Some(parent_id) = &parent_id {
+        ensure_category_exists(&conn, parent_id)?;
+    }
+    
+    let category_id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
+    
     conn.execute(
-        "UPDATE tbl_ledger_account 
-         SET password_hash = NULL, password_expires_at = NULL, updated_at = ?1 
-         WHERE password_expires_at IS NOT NULL AND password_expires_at < ?1",
-        [&now],
+        "INSERT INTO tbl_category (id, name, color, parent_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![category_id, name, color, parent_id, now],
     )
     .map_err(|e| e.to_string())?;
-    Ok(())
+    
+    Ok(Category {
+        id: category_id,
+        name,
+        color,
+        parent_id,
+        created_at: now,
+    })
+}
+
+fn ensure_category_exists(conn: &Connection, category_id: &str) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tbl_category WHERE id = ?1)",
+            [category_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if exists {
+        Ok(())
+    } else {
+        Err("해당 카테고리를 찾을 수 없습니다.".to_string())
+    }
 }
 
+// 상위 카테고리 변경 (자기 자신이나 하위 카테고리를 상위로 지정하면 에러)
 #[tauri::command]
-fn create_ledger_account(
+fn set_category_parent(
     app_handle: AppHandle,
     state: State<AppState>,
-    nickname: String,
-    password: Option<String>,
-) -> Result<LedgerAccount, String> {
+    category_id: String,
+    parent_id: Option<String>,
+) -> Result<(), String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -2024,39 +10350,45 @@ fn create_ledger_account(
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&conn)?;
-    
-    let account_id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    
-    let password_hash = password.map(|p| hash_password(&p));
-    let password_expires_at = password_hash.as_ref().map(|_| {
-        let expires = Utc::now() + chrono::Duration::days(30);
-        expires.to_rfc3339()
-    });
+    ensure_category_exists(&conn, &category_id)?;
+    if let Some(parent_id) = &parent_id {
+        ensure_category_exists(&conn, parent_id)?;
+        
+        // 새 상위 카테고리의 조상 중에 자신이 있으면 순환
+        let creates_cycle: bool = conn
+            .query_row(
+                "WITH RECURSIVE ancestors(id) AS (
+                    SELECT ?1
+                    UNION
+                    SELECT c.parent_id FROM tbl_category c
+                    INNER JOIN ancestors a ON c.id = a.id
+                    WHERE c.parent_id IS NOT NULL
+                 )
+                 SELECT EXISTS(SELECT 1 FROM ancestors WHERE id = ?2)",
+                rusqlite::params![parent_id, category_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if creates_cycle {
+            return Err("하위 카테고리를 상위 카테고리로 지정할 수 없습니다.".to_string());
+        }
+    }
     
     conn.execute(
-        "INSERT INTO tbl_ledger_account (id, nickname, password_hash, password_expires_at, created_at, updated_at) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![account_id, nickname, password_hash, password_expires_at, now, now],
+        "UPDATE tbl_category SET parent_id = ?1 WHERE id = ?2",
+        rusqlite::params![parent_id, category_id],
     )
     .map_err(|e| e.to_string())?;
     
-    Ok(LedgerAccount {
-        id: account_id,
-        nickname,
-        password_hash,
-        password_expires_at,
-        created_at: now.clone(),
-        updated_at: now,
-    })
+    Ok(())
 }
 
+// 카테고리를 상위/하위 구조로 조회
 #[tauri::command]
-fn list_ledger_accounts(
+fn get_category_tree(
     app_handle: AppHandle,
     state: State<AppState>,
-) -> Result<Vec<LedgerAccount>, String> {
+) -> Result<Vec<CategoryNode>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -2064,130 +10396,352 @@ fn list_ledger_accounts(
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&conn)?;
+    let categories = load_categories(&conn)?;
+    let ids: Vec<String> = categories.iter().map(|c| c.id.clone()).collect();
+    let mut children_by_parent: HashMap<Option<String>, Vec<Category>> = HashMap::new();
+    for category in categories {
+        // 상위 카테고리가 없어진 경우 최상위로 취급
+        let parent = category.parent_id.clone().filter(|p| ids.contains(p));
+        children_by_parent.entry(parent).or_default().push(category);
+    }
     
-    let mut stmt = conn
-        .prepare("SELECT id, nickname, password_hash, password_expires_at, created_at, updated_at FROM tbl_ledger_account ORDER BY created_at DESC")
-        .map_err(|e| e.to_string())?;
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(LedgerAccount {
-                id: row.get(0)?,
-                nickname: row.get(1)?,
-                password_hash: row.get(2)?,
-                password_expires_at: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-            })
+    Ok(build_category_tree(None, &mut children_by_parent))
+}
+
+fn build_category_tree(
+    parent: Option<String>,
+    children_by_parent: &mut HashMap<Option<String>, Vec<Category>>,
+) -> Vec<CategoryNode> {
+    children_by_parent
+        .remove(&parent)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|category| {
+            let children = build_category_tree(Some(category.id.clone()), children_by_parent);
+            CategoryNode { category, children }
         })
+        .collect()
+}
+
+#[tauri::command]
+fn delete_category(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    category_id: String,
+    cascade_children: Option<bool>,
+) -> Result<(), String> {
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    if cascade_children.unwrap_or(false) {
+        // 하위 카테고리 전체 삭제
+        tx.execute(
+            "WITH RECURSIVE descendants(id) AS (
+                SELECT id FROM tbl_category WHERE parent_id = ?1
+                UNION
+                SELECT c.id FROM tbl_category c
+                INNER JOIN descendants d ON c.parent_id = d.id
+             )
+             DELETE FROM tbl_category WHERE id IN (SELECT id FROM descendants)",
+            [&category_id],
+        )
+        .map_err(|e| e.to_string())?;
+    } else {
+        // 하위 카테고리는 삭제되는 카테고리의 상위로 이동
+        tx.execute(
+            "UPDATE tbl_category
+             SET parent_id = (SELECT parent_id FROM tbl_category WHERE id = ?1)
+             WHERE parent_id = ?1",
+            [&category_id],
+        )
         .map_err(|e| e.to_string())?;
+    }
     
-    let mut accounts = Vec::new();
-    for row in rows {
-        accounts.push(row.map_err(|e| e.to_string())?);
+    tx.execute("DELETE FROM tbl_category WHERE id = ?1", [&category_id])
+        .map_err(|e| e.to_string())?;
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 상품 메타데이터 조회 (태그/카테고리 포함)
+fn load_product_meta(conn: &Connection, provider: &str, item_id: i64) -> Result<Option<ProductMeta>, String> {
+    // 메타데이터 조회
+    let meta_result: Result<(String, String, i64, Option<String>, Option<String>, Option<i32>, String, String), rusqlite::Error> = conn.query_row(
+        "SELECT id, provider, item_id, memo, url, rating, created_at, updated_at
+         FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
+        rusqlite::params![provider, item_id],
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ))
+        },
+    );
+    
+    match meta_result {
+        Ok((id, provider, item_id, memo, url, rating, created_at, updated_at)) => {
+            // 태그 조회
+            let mut tag_stmt = conn
+                .prepare("SELECT tag FROM tbl_product_tag WHERE meta_id = ?1 ORDER BY tag")
+                .map_err(|e| e.to_string())?;
+            let tag_rows = tag_stmt
+                .query_map([&id], |row| row.get::<_, String>(0))
+                .map_err(|e| e.to_string())?;
+            
+            let mut tags = Vec::new();
+            for tag_result in tag_rows {
+                tags.push(tag_result.map_err(|e| e.to_string())?);
+            }
+            
+            // 카테고리 조회
+            let mut cat_stmt = conn
+                .prepare(
+                    "SELECT c.id, c.name, c.color, c.parent_id, c.created_at
+                     FROM tbl_category c
+                     INNER JOIN tbl_product_category pc ON c.id = pc.category_id
+                     WHERE pc.meta_id = ?1
+                     ORDER BY c.name, c.id"
+                )
+                .map_err(|e| e.to_string())?;
+            let cat_rows = cat_stmt
+                .query_map([&id], |row| {
+                    Ok(Category {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        color: row.get(2)?,
+                        parent_id: row.get(3)?,
+                        created_at: row.get(4)?,
+                    })
+                })
+                .map_err(|e| e.to_string())?;
+            
+            let mut categories = Vec::new();
+            for cat_result in cat_rows {
+                categories.push(cat_result.map_err(|e| e.to_string())?);
+            }
+            
+            Ok(Some(ProductMeta {
+                id,
+                provider,
+                item_id,
+                memo,
+                url,
+                rating,
+                tags,
+                categories,
+                created_at,
+                updated_at,
+            }))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
     }
-    Ok(accounts)
 }
 
 #[tauri::command]
-fn verify_ledger_password(
+fn get_product_meta(
     app_handle: AppHandle,
     state: State<AppState>,
-    account_id: String,
-    password: String,
-) -> Result<bool, String> {
+    provider: String,
+    item_id: i64,
+) -> Result<Option<ProductMeta>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    load_product_meta(&conn, &provider, item_id)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PurchaseHistoryEntry {
+    paid_at: String,
+    quantity: i32,
+    unit_price: Option<i64>,
+    line_amount: Option<i64>,
+    merchant_name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductMetaWithHistory {
+    #[serde(flatten)]
+    meta: ProductMeta,
+    purchase_history: Vec<PurchaseHistoryEntry>,
+}
+
+// 상품 메타데이터 + 해당 결제 항목의 구매 내역 조회
+#[tauri::command]
+fn get_product_meta_with_purchase_history(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: String,
+    item_id: i64,
+) -> Result<Option<ProductMetaWithHistory>, String> {
+    let history_sql = match provider.as_str() {
+        "naver" => {
+            "SELECT p.paid_at, i.quantity, i.unit_price, i.line_amount, p.merchant_name
+             FROM tbl_naver_payment_item i
+             INNER JOIN tbl_naver_payment p ON p.id = i.payment_id
+             WHERE i.id = ?1
+             ORDER BY p.paid_at DESC"
+        }
+        "coupang" => {
+            "SELECT COALESCE(p.paid_at, p.ordered_at), i.quantity, i.unit_price, i.line_amount, p.merchant_name
+             FROM tbl_coupang_payment_item i
+             INNER JOIN tbl_coupang_payment p ON p.id = i.payment_id
+             WHERE i.id = ?1
+             ORDER BY COALESCE(p.paid_at, p.ordered_at) DESC"
+        }
+        _ => return Err(format!("지원하지 않는 provider입니다: {}", provider)),
+    };
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let meta = match load_product_meta(&conn, &provider, item_id)? {
+        Some(meta) => meta,
+        None => return Ok(None),
+    };
+    
+    let mut stmt = conn.prepare(history_sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([item_id], |row| {
+            Ok(PurchaseHistoryEntry {
+                paid_at: row.get(0)?,
+                quantity: row.get(1)?,
+                unit_price: row.get(2)?,
+                line_amount: row.get(3)?,
+                merchant_name: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut purchase_history = Vec::new();
+    for row in rows {
+        purchase_history.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(Some(ProductMetaWithHistory { meta, purchase_history }))
+}
+
+// 상품 메타데이터 생성 또는 갱신 (태그/카테고리는 입력값으로 교체, 호출자가 트랜잭션 관리)
+// 동시에 같은 상품을 저장해도 실패하지 않도록 쓰기 잠금을 먼저 잡고(IMMEDIATE) 잠금 대기 시간을 둠
+const PRODUCT_META_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn save_product_meta_to_db(path: &Path, provider: &str, item_id: i64, input: &ProductMetaInput) -> Result<(), String> {
+    let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.busy_timeout(PRODUCT_META_BUSY_TIMEOUT)
+        .map_err(|e| e.to_string())?;
+    let tx = conn
+        .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    write_product_meta(&tx, provider, item_id, input)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn write_product_meta(tx: &Connection, provider: &str, item_id: i64, input: &ProductMetaInput) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    
+    // 조회 후 삽입하면 동시 저장 시 UNIQUE(provider, item_id) 충돌이 나므로 한 번의 UPSERT로 처리
+    let meta_id: String = tx
+        .query_row(
+            "INSERT INTO tbl_product_meta (id, provider, item_id, memo, url, rating, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+             ON CONFLICT(provider, item_id) DO UPDATE SET
+                memo = excluded.memo,
+                url = excluded.url,
+                rating = excluded.rating,
+                updated_at = excluded.updated_at
+             RETURNING id",
+            rusqlite::params![Uuid::new_v4().to_string(), provider, item_id, input.memo, input.url, input.rating, now],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    
+    // 태그 삭제 후 재생성
+    tx.execute("DELETE FROM tbl_product_tag WHERE meta_id = ?1", [&meta_id])
+        .map_err(|e| e.to_string())?;
+    
+    for tag in &input.tags {
+        let tag_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_product_tag (id, meta_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![tag_id, meta_id, tag, now],
+        )
+        .map_err(|e| e.to_string())?;
     }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&conn)?;
+    // 카테고리 관계 삭제 후 재생성
+    tx.execute("DELETE FROM tbl_product_category WHERE meta_id = ?1", [&meta_id])
+        .map_err(|e| e.to_string())?;
     
-    let password_hash = hash_password(&password);
-    let stored_hash: Option<String> = conn
-        .query_row(
-            "SELECT password_hash FROM tbl_ledger_account WHERE id = ?1",
-            [account_id],
-            |row| row.get(0),
+    for category_id in &input.category_ids {
+        let rel_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO tbl_product_category (id, meta_id, category_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![rel_id, meta_id, category_id, now],
         )
         .map_err(|e| e.to_string())?;
-    
-    Ok(stored_hash.map(|h| h == password_hash).unwrap_or(false))
-}
-
-#[tauri::command]
-fn update_ledger_password(
-    app_handle: AppHandle,
-    state: State<AppState>,
-    account_id: String,
-    password: String,
-) -> Result<(), String> {
-    let path = configured_db_path(&app_handle, &state)?
-        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
-    if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    check_and_reset_expired_passwords(&conn)?;
-    
-    let password_hash = hash_password(&password);
-    let expires_at = Utc::now() + chrono::Duration::days(30);
-    let now = Utc::now().to_rfc3339();
-    
-    conn.execute(
-        "UPDATE tbl_ledger_account 
-         SET password_hash = ?1, password_expires_at = ?2, updated_at = ?3 
-         WHERE id = ?4",
-        rusqlite::params![password_hash, expires_at.to_rfc3339(), now, account_id],
-    )
-    .map_err(|e| e.to_string())?;
     
     Ok(())
 }
 
 #[tauri::command]
-fn check_password_expiry(
-    app_handle: AppHandle,
-    state: State<AppState>,
-) -> Result<(), String> {
-    let path = configured_db_path(&app_handle, &state)?
-        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
-    if !path.exists() {
-        return Ok(());
-    }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    check_and_reset_expired_passwords(&conn)
-}
-
-#[tauri::command]
-fn delete_ledger_account(
+fn save_product_meta(
     app_handle: AppHandle,
     state: State<AppState>,
-    account_id: String,
-) -> Result<(), String> {
+    provider: String,
+    item_id: i64,
+    input: ProductMetaInput,
+) -> Result<ProductMeta, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    conn.execute("DELETE FROM tbl_ledger_account WHERE id = ?1", [account_id])
-        .map_err(|e| e.to_string())?;
+    save_product_meta_to_db(&path, &provider, item_id, &input)?;
     
-    Ok(())
+    // 저장된 데이터 반환
+    get_product_meta(app_handle, state, provider, item_id)?
+        .ok_or_else(|| "저장된 메타데이터를 찾을 수 없습니다.".to_string())
 }
 
+// 다른 provider(또는 다른 상품)로 메모/평점/태그/카테고리 복사
 #[tauri::command]
-fn create_ledger_entry(
+fn copy_product_meta(
     app_handle: AppHandle,
     state: State<AppState>,
-    account_id: String,
-    entry: LedgerEntryInput,
-) -> Result<String, String> {
+    from_provider: String,
+    from_item_id: i64,
+    to_provider: String,
+    to_item_id: i64,
+    overwrite: bool,
+) -> Result<ProductMeta, String> {
+    if from_provider == to_provider && from_item_id == to_item_id {
+        return Err("원본과 대상 상품이 같습니다.".to_string());
+    }
+    let (_, to_item_table) = payment_tables(&to_provider)?;
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -2196,396 +10750,237 @@ fn create_ledger_entry(
     let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&tx)?;
-    
-    let entry_id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    
-    // 항목 저장
-    tx.execute(
-        "INSERT INTO tbl_ledger_entry 
-         (id, account_id, type, amount, date, title, category, platform, url, merchant, payment_method, memo, color, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
-        rusqlite::params![
-            entry_id, account_id, entry.r#type, entry.amount, entry.date, entry.title,
-            entry.category, entry.platform, entry.url, entry.merchant, entry.payment_method,
-            entry.memo, entry.color, now, now
-        ],
-    )
-    .map_err(|e| e.to_string())?;
+    let source = load_product_meta(&tx, &from_provider, from_item_id)?
+        .ok_or_else(|| "원본 상품의 메타데이터가 없습니다.".to_string())?;
     
-    // 태그 저장
-    for tag in &entry.tags {
-        let tag_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![tag_id, entry_id, tag, now],
+    let target_exists: bool = tx
+        .query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", to_item_table),
+            [to_item_id],
+            |row| row.get(0),
         )
         .map_err(|e| e.to_string())?;
+    if !target_exists {
+        return Err("대상 상품을 찾을 수 없습니다.".to_string());
+    }
+    if !overwrite && load_product_meta(&tx, &to_provider, to_item_id)?.is_some() {
+        return Err("대상 상품에 이미 메타데이터가 있습니다.".to_string());
     }
     
-    // 히스토리 기록 (완전한 LedgerEntry 생성)
-    let full_entry = LedgerEntry {
-        id: entry_id.clone(),
-        account_id: account_id.clone(),
-        r#type: entry.r#type.clone(),
-        amount: entry.amount,
-        date: entry.date.clone(),
-        title: entry.title.clone(),
-        category: entry.category.clone(),
-        platform: entry.platform.clone(),
-        url: entry.url.clone(),
-        merchant: entry.merchant.clone(),
-        payment_method: entry.payment_method.clone(),
-        memo: entry.memo.clone(),
-        color: entry.color.clone(),
-        tags: entry.tags.clone(),
-        created_at: now.clone(),
-        updated_at: now.clone(),
+    let input = ProductMetaInput {
+        memo: source.memo,
+        url: source.url,
+        rating: source.rating,
+        tags: source.tags,
+        category_ids: source.categories.into_iter().map(|category| category.id).collect(),
     };
-    let snapshot_after = serde_json::to_string(&full_entry).map_err(|e| e.to_string())?;
-    let history_id = Uuid::new_v4().to_string();
-    tx.execute(
-        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_after, created_at) 
-         VALUES (?1, ?2, 'create', ?3, ?4)",
-        rusqlite::params![history_id, entry_id, snapshot_after, now],
-    )
-    .map_err(|e| e.to_string())?;
-    
+    write_product_meta(&tx, &to_provider, to_item_id, &input)?;
     tx.commit().map_err(|e| e.to_string())?;
-    Ok(entry_id)
+    
+    get_product_meta(app_handle, state, to_provider, to_item_id)?
+        .ok_or_else(|| "저장된 메타데이터를 찾을 수 없습니다.".to_string())
 }
 
 #[tauri::command]
-fn update_ledger_entry(
+fn delete_product_meta(
     app_handle: AppHandle,
     state: State<AppState>,
-    entry_id: String,
-    entry: LedgerEntryInput,
+    provider: String,
+    item_id: i64,
 ) -> Result<(), String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-    
-    check_and_reset_expired_passwords(&tx)?;
-    
-    // 기존 항목 조회 (히스토리용)
-    let (existing_account_id, existing_created_at): (String, String) = tx
-        .query_row(
-            "SELECT account_id, created_at FROM tbl_ledger_entry WHERE id = ?1",
-            [&entry_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|e| e.to_string())?;
-    
-    let snapshot_before: Option<String> = tx
-        .query_row(
-            "SELECT json_object(
-                'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
-                'date', date, 'title', title, 'category', category, 'platform', platform,
-                'url', url, 'merchant', merchant, 'payment_method', payment_method,
-                'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
-            ) FROM tbl_ledger_entry WHERE id = ?1",
-            [&entry_id],
-            |row| row.get(0),
-        )
-        .ok();
-    
-    let now = Utc::now().to_rfc3339();
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    // 항목 업데이트
-    tx.execute(
-        "UPDATE tbl_ledger_entry 
-         SET type = ?1, amount = ?2, date = ?3, title = ?4, category = ?5, platform = ?6,
-             url = ?7, merchant = ?8, payment_method = ?9, memo = ?10, color = ?11, updated_at = ?12
-         WHERE id = ?13",
-        rusqlite::params![
-            entry.r#type, entry.amount, entry.date, entry.title, entry.category,
-            entry.platform, entry.url, entry.merchant, entry.payment_method,
-            entry.memo, entry.color, now, entry_id
-        ],
+    // CASCADE로 태그, 카테고리 관계도 자동 삭제
+    conn.execute(
+        "DELETE FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
+        rusqlite::params![provider, item_id],
     )
     .map_err(|e| e.to_string())?;
     
-    // 태그 삭제 후 재생성
-    tx.execute("DELETE FROM tbl_ledger_tag WHERE entry_id = ?1", [&entry_id])
-        .map_err(|e| e.to_string())?;
-    
-    for tag in &entry.tags {
-        let tag_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO tbl_ledger_tag (id, entry_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![tag_id, entry_id, tag, now],
-        )
-        .map_err(|e| e.to_string())?;
-    }
-    
-    // 히스토리 기록 (완전한 LedgerEntry 생성)
-    let full_entry_after = LedgerEntry {
-        id: entry_id.clone(),
-        account_id: existing_account_id,
-        r#type: entry.r#type.clone(),
-        amount: entry.amount,
-        date: entry.date.clone(),
-        title: entry.title.clone(),
-        category: entry.category.clone(),
-        platform: entry.platform.clone(),
-        url: entry.url.clone(),
-        merchant: entry.merchant.clone(),
-        payment_method: entry.payment_method.clone(),
-        memo: entry.memo.clone(),
-        color: entry.color.clone(),
-        tags: entry.tags.clone(),
-        created_at: existing_created_at,
-        updated_at: now.clone(),
-    };
-    let snapshot_after = serde_json::to_string(&full_entry_after).map_err(|e| e.to_string())?;
-    let history_id = Uuid::new_v4().to_string();
-    tx.execute(
-        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, snapshot_after, created_at) 
-         VALUES (?1, ?2, 'update', ?3, ?4, ?5)",
-        rusqlite::params![history_id, entry_id, snapshot_before, snapshot_after, now],
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BulkAssignResult {
+    newly_assigned: u32,
+    already_assigned: u32,
+}
+
+// 상품 메타데이터 행 ID 조회 (없으면 빈 메타데이터 행 생성)
+fn ensure_product_meta_id(conn: &Connection, provider: &str, item_id: i64, now: &str) -> Result<String, String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tbl_product_meta (id, provider, item_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?4)",
+        rusqlite::params![Uuid::new_v4().to_string(), provider, item_id, now],
     )
     .map_err(|e| e.to_string())?;
-    
-    tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    conn.query_row(
+        "SELECT id FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
+        rusqlite::params![provider, item_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// 일괄 지정 대상 정리 (provider 검증, 중복 상품 ID 제거)
+fn bulk_assign_item_ids(provider: &str, mut item_ids: Vec<i64>) -> Result<Vec<i64>, String> {
+    payment_tables(provider)?;
+    item_ids.sort_unstable();
+    item_ids.dedup();
+    Ok(item_ids)
 }
 
+// 여러 상품에 카테고리 일괄 지정 (기존 카테고리는 유지)
 #[tauri::command]
-fn delete_ledger_entry(
+fn assign_category_to_products(
     app_handle: AppHandle,
     state: State<AppState>,
-    entry_id: String,
-) -> Result<(), String> {
+    provider: String,
+    item_ids: Vec<i64>,
+    category_id: String,
+) -> Result<BulkAssignResult, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
+    let item_ids = bulk_assign_item_ids(&provider, item_ids)?;
     let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&tx)?;
-    
-    // 삭제 전 스냅샷 저장
-    let snapshot_before: Option<String> = tx
+    let category_exists: bool = tx
         .query_row(
-            "SELECT json_object(
-                'id', id, 'account_id', account_id, 'type', type, 'amount', amount,
-                'date', date, 'title', title, 'category', category, 'platform', platform,
-                'url', url, 'merchant', merchant, 'payment_method', payment_method,
-                'memo', memo, 'color', color, 'created_at', created_at, 'updated_at', updated_at
-            ) FROM tbl_ledger_entry WHERE id = ?1",
-            [&entry_id],
+            "SELECT EXISTS(SELECT 1 FROM tbl_category WHERE id = ?1)",
+            [&category_id],
             |row| row.get(0),
         )
-        .ok();
+        .map_err(|e| e.to_string())?;
+    if !category_exists {
+        return Err("카테고리를 찾을 수 없습니다.".to_string());
+    }
     
     let now = Utc::now().to_rfc3339();
+    let mut result = BulkAssignResult { newly_assigned: 0, already_assigned: 0 };
     
-    // 히스토리 기록
-    let history_id = Uuid::new_v4().to_string();
-    tx.execute(
-        "INSERT INTO tbl_ledger_history (id, entry_id, action, snapshot_before, created_at) 
-         VALUES (?1, ?2, 'delete', ?3, ?4)",
-        rusqlite::params![history_id, entry_id, snapshot_before, now],
-    )
-    .map_err(|e| e.to_string())?;
-    
-    // 항목 삭제 (CASCADE로 태그도 자동 삭제)
-    tx.execute("DELETE FROM tbl_ledger_entry WHERE id = ?1", [entry_id])
-        .map_err(|e| e.to_string())?;
+    for item_id in &item_ids {
+        let meta_id = ensure_product_meta_id(&tx, &provider, *item_id, &now)?;
+        let inserted = tx
+            .execute(
+                "INSERT OR IGNORE INTO tbl_product_category (id, meta_id, category_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![Uuid::new_v4().to_string(), meta_id, category_id, now],
+            )
+            .map_err(|e| e.to_string())?;
+        if inserted > 0 {
+            result.newly_assigned += 1;
+        } else {
+            result.already_assigned += 1;
+        }
+    }
     
     tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(result)
 }
 
+// 여러 상품에 태그 일괄 추가 (기존 태그는 유지)
 #[tauri::command]
-fn list_ledger_entries(
+fn assign_tag_to_products(
     app_handle: AppHandle,
     state: State<AppState>,
-    account_id: String,
-    year_month: String,
-) -> Result<Vec<LedgerEntry>, String> {
+    provider: String,
+    item_ids: Vec<i64>,
+    tag: String,
+) -> Result<BulkAssignResult, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Ok(Vec::new());
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
     }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    check_and_reset_expired_passwords(&conn)?;
-    
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
-                    payment_method, memo, color, created_at, updated_at
-             FROM tbl_ledger_entry 
-             WHERE account_id = ?1 AND date LIKE ?2
-             ORDER BY date DESC, created_at DESC",
-        )
-        .map_err(|e| e.to_string())?;
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err("태그를 입력해주세요.".to_string());
+    }
+    let item_ids = bulk_assign_item_ids(&provider, item_ids)?;
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
     
-    let date_pattern = format!("{}%", year_month);
-    let rows = stmt
-        .query_map(rusqlite::params![account_id, date_pattern], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, i64>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, Option<String>>(7)?,
-                row.get::<_, Option<String>>(8)?,
-                row.get::<_, Option<String>>(9)?,
-                row.get::<_, Option<String>>(10)?,
-                row.get::<_, Option<String>>(11)?,
-                row.get::<_, Option<String>>(12)?,
-                row.get::<_, String>(13)?,
-                row.get::<_, String>(14)?,
-            ))
-        })
-        .map_err(|e| e.to_string())?;
+    let now = Utc::now().to_rfc3339();
+    let mut result = BulkAssignResult { newly_assigned: 0, already_assigned: 0 };
     
-    let mut entries = Vec::new();
-    for row_result in rows {
-        let (
-            id, account_id, r#type, amount, date, title, category, platform, url, merchant,
-            payment_method, memo, color, created_at, updated_at,
-        ) = row_result.map_err(|e| e.to_string())?;
-        
-        // 태그 조회
-        let mut tag_stmt = conn
-            .prepare("SELECT tag FROM tbl_ledger_tag WHERE entry_id = ?1 ORDER BY tag")
-            .map_err(|e| e.to_string())?;
-        let tag_rows = tag_stmt
-            .query_map([&id], |row| row.get::<_, String>(0))
+    for item_id in &item_ids {
+        let meta_id = ensure_product_meta_id(&tx, &provider, *item_id, &now)?;
+        let inserted = tx
+            .execute(
+                "INSERT OR IGNORE INTO tbl_product_tag (id, meta_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![Uuid::new_v4().to_string(), meta_id, tag, now],
+            )
             .map_err(|e| e.to_string())?;
-        
-        let mut tags = Vec::new();
-        for tag_result in tag_rows {
-            tags.push(tag_result.map_err(|e| e.to_string())?);
+        if inserted > 0 {
+            result.newly_assigned += 1;
+        } else {
+            result.already_assigned += 1;
         }
-        
-        entries.push(LedgerEntry {
-            id,
-            account_id,
-            r#type,
-            amount,
-            date,
-            title,
-            category,
-            platform,
-            url,
-            merchant,
-            payment_method,
-            memo,
-            color,
-            tags,
-            created_at,
-            updated_at,
-        });
     }
     
-    Ok(entries)
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
 }
 
 #[tauri::command]
-fn get_ledger_entry(
+fn search_tags(
     app_handle: AppHandle,
     state: State<AppState>,
-    entry_id: String,
-) -> Result<Option<LedgerEntry>, String> {
+    query: String,
+    limit: Option<i64>,
+    provider: Option<String>,
+) -> Result<Vec<String>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    check_and_reset_expired_passwords(&conn)?;
+    let search_term = format!("%{}%", query);
+    let result_limit = clamp_limit(limit, 20, MAX_LIST_LIMIT);
     
-    let result: Result<(String, String, String, i64, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, String, String), rusqlite::Error> = conn.query_row(
-        "SELECT id, account_id, type, amount, date, title, category, platform, url, merchant, 
-                payment_method, memo, color, created_at, updated_at
-         FROM tbl_ledger_entry WHERE id = ?1",
-        [entry_id],
-        |row| {
-            Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-                row.get(8)?,
-                row.get(9)?,
-                row.get(10)?,
-                row.get(11)?,
-                row.get(12)?,
-                row.get(13)?,
-                row.get(14)?,
-            ))
-        },
-    );
+    // provider 지정 시 해당 provider 상품의 태그만 조회
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT t.tag FROM tbl_product_tag t
+             INNER JOIN tbl_product_meta m ON m.id = t.meta_id
+             WHERE t.tag LIKE ?1 AND (?3 IS NULL OR m.provider = ?3)
+             ORDER BY t.tag 
+             LIMIT ?2"
+        )
+        .map_err(|e| e.to_string())?;
     
-    match result {
-        Ok((
-            id, account_id, r#type, amount, date, title, category, platform, url, merchant,
-            payment_method, memo, color, created_at, updated_at,
-        )) => {
-            // 태그 조회
-            let mut tag_stmt = conn
-                .prepare("SELECT tag FROM tbl_ledger_tag WHERE entry_id = ?1 ORDER BY tag")
-                .map_err(|e| e.to_string())?;
-            let tag_rows = tag_stmt
-                .query_map([&id], |row| row.get::<_, String>(0))
-                .map_err(|e| e.to_string())?;
-            
-            let mut tags = Vec::new();
-            for tag_result in tag_rows {
-                tags.push(tag_result.map_err(|e| e.to_string())?);
-            }
-            
-            Ok(Some(LedgerEntry {
-                id,
-                account_id,
-                r#type,
-                amount,
-                date,
-                title,
-                category,
-                platform,
-                url,
-                merchant,
-                payment_method,
-                memo,
-                color,
-                tags,
-                created_at,
-                updated_at,
-            }))
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
+    let rows = stmt
+        .query_map(rusqlite::params![search_term, result_limit, provider], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    
+    let mut tags = Vec::new();
+    for row in rows {
+        tags.push(row.map_err(|e| e.to_string())?);
     }
+    
+    Ok(tags)
 }
 
+// 현재 가계부 계정에서 사용된 태그만 자동완성 후보로 조회
 #[tauri::command]
-fn list_ledger_history(
+fn search_ledger_tags(
     app_handle: AppHandle,
     state: State<AppState>,
-    entry_id: String,
-) -> Result<Vec<LedgerHistory>, String> {
+    account_id: String,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<String>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -2594,361 +10989,418 @@ fn list_ledger_history(
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
     check_and_reset_expired_passwords(&conn)?;
+    ensure_ledger_unlocked(&conn, &state, &account_id)?;
+    
+    let search_term = format!("%{}%", query);
+    let result_limit = clamp_limit(limit, 20, MAX_LIST_LIMIT);
     
     let mut stmt = conn
         .prepare(
-            "SELECT id, entry_id, action, snapshot_before, snapshot_after, created_at
-             FROM tbl_ledger_history 
-             WHERE entry_id = ?1
-             ORDER BY created_at DESC",
+            "SELECT DISTINCT t.tag FROM tbl_ledger_tag t
+             INNER JOIN tbl_ledger_entry e ON t.entry_id = e.id
+             WHERE e.account_id = ?1 AND t.tag LIKE ?2
+             ORDER BY t.tag
+             LIMIT ?3",
         )
         .map_err(|e| e.to_string())?;
     
     let rows = stmt
-        .query_map([entry_id], |row| {
-            Ok(LedgerHistory {
-                id: row.get(0)?,
-                entry_id: row.get(1)?,
-                action: row.get(2)?,
-                snapshot_before: row.get(3)?,
-                snapshot_after: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })
+        .query_map(rusqlite::params![account_id, search_term, result_limit], |row| row.get(0))
         .map_err(|e| e.to_string())?;
     
-    let mut histories = Vec::new();
+    let mut tags = Vec::new();
     for row in rows {
-        histories.push(row.map_err(|e| e.to_string())?);
+        tags.push(row.map_err(|e| e.to_string())?);
     }
     
-    Ok(histories)
-}
-
-// ========== 상품 메타데이터 관련 구조체 및 함수 ==========
-
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct Category {
-    id: String,
-    name: String,
-    color: Option<String>,
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ProductMeta {
-    id: String,
-    provider: String,
-    item_id: i64,
-    memo: Option<String>,
-    url: Option<String>,
-    rating: Option<i32>,
-    tags: Vec<String>,
-    categories: Vec<Category>,
-    created_at: String,
-    updated_at: String,
+    Ok(tags)
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ProductMetaInput {
-    memo: Option<String>,
-    url: Option<String>,
-    rating: Option<i32>,
-    tags: Vec<String>,
-    category_ids: Vec<String>,
+struct TagSuggestion {
+    tag: String,
+    frequency: i64,
+    sources: Vec<String>,
 }
 
+// 상품 태그와 가계부 태그를 합쳐서 자동완성 후보 조회 (sources 미지정 시 둘 다)
 #[tauri::command]
-fn list_categories(
+fn search_all_tags(
     app_handle: AppHandle,
     state: State<AppState>,
-) -> Result<Vec<Category>, String> {
+    query: String,
+    sources: Option<Vec<String>>,
+    limit: Option<i64>,
+) -> Result<Vec<TagSuggestion>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
         return Ok(Vec::new());
     }
+    
+    // 같은 소스를 여러 번 지정해도 한 번만 조회 (중복 UNION은 빈도를 부풀림)
+    let mut sources = sources.unwrap_or_else(|| vec!["product".to_string(), "ledger".to_string()]);
+    sources.sort();
+    sources.dedup();
+    let mut selects = Vec::new();
+    for source in &sources {
+        match source.as_str() {
+            "product" => selects.push("SELECT tag, 'product' AS source FROM tbl_product_tag WHERE tag LIKE ?1"),
+            // 잠긴 계정의 태그는 제외 (?3: 열려 있는 계정 ID 목록 JSON)
+            "ledger" => selects.push(
+                "SELECT t.tag, 'ledger' AS source FROM tbl_ledger_tag t
+                 JOIN tbl_ledger_entry e ON e.id = t.entry_id
+                 WHERE t.tag LIKE ?1 AND e.account_id IN (SELECT value FROM json_each(?3))",
+            ),
+            _ => return Err(format!("지원하지 않는 태그 소스입니다: {}", source)),
+        }
+    }
+    if selects.is_empty() {
+        return Ok(Vec::new());
+    }
+    
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
+    let search_term = format!("%{}%", query);
+    let result_limit = clamp_limit(limit, 20, MAX_LIST_LIMIT);
+    let mut params = vec![
+        rusqlite::types::Value::Text(search_term),
+        rusqlite::types::Value::Integer(result_limit),
+    ];
+    if sources.iter().any(|s| s == "ledger") {
+        check_and_reset_expired_passwords(&conn)?;
+        params.push(rusqlite::types::Value::Text(open_ledger_account_ids_json(&conn, &state)?));
+    }
+    
     let mut stmt = conn
-        .prepare("SELECT id, name, color, created_at FROM tbl_category ORDER BY name")
+        .prepare(&format!(
+            "SELECT tag, COUNT(*) AS frequency, GROUP_CONCAT(DISTINCT source)
+             FROM ({})
+             GROUP BY tag
+             ORDER BY frequency DESC, tag
+             LIMIT ?2",
+            selects.join(" UNION ALL ")
+        ))
         .map_err(|e| e.to_string())?;
     
     let rows = stmt
-        .query_map([], |row| {
-            Ok(Category {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-                created_at: row.get(3)?,
+        .query_map(rusqlite::params_from_iter(params), |row| {
+            let sources: String = row.get(2)?;
+            let mut sources: Vec<String> = sources.split(',').map(|s| s.to_string()).collect();
+            sources.sort();
+            Ok(TagSuggestion {
+                tag: row.get(0)?,
+                frequency: row.get(1)?,
+                sources,
             })
         })
         .map_err(|e| e.to_string())?;
     
-    let mut categories = Vec::new();
+    let mut suggestions = Vec::new();
     for row in rows {
-        categories.push(row.map_err(|e| e.to_string())?);
+        suggestions.push(row.map_err(|e| e.to_string())?);
     }
     
-    Ok(categories)
+    Ok(suggestions)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PurchasedTagCount {
+    tag: String,
+    count: i64,
 }
 
+// 사용자가 실제로 구매한 상품에 붙은 태그와 상품 수 (많이 쓰인 순)
 #[tauri::command]
-fn create_category(
+fn list_purchased_item_tags(
     app_handle: AppHandle,
     state: State<AppState>,
-    name: String,
-    color: Option<String>,
-) -> Result<Category, String> {
+    user_id: String,
+    provider: String,
+) -> Result<Vec<PurchasedTagCount>, String> {
+    let (payment_table, item_table) = payment_tables(&provider)?;
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
+        return Ok(Vec::new());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    let category_id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT t.tag, COUNT(DISTINCT i.id) AS count
+             FROM tbl_product_tag t
+             JOIN tbl_product_meta m ON m.id = t.meta_id
+             JOIN {} i ON i.id = m.item_id
+             JOIN {} p ON p.id = i.payment_id
+             WHERE m.provider = ?1 AND p.user_id = ?2
+             GROUP BY t.tag
+             ORDER BY count DESC, t.tag",
+            item_table, payment_table
+        ))
+        .map_err(|e| e.to_string())?;
     
-    conn.execute(
-        "INSERT INTO tbl_category (id, name, color, created_at) VALUES (?1, ?2, ?3, ?4)",
-        rusqlite::params![category_id, name, color, now],
-    )
-    .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![provider, user_id], |row| {
+            Ok(PurchasedTagCount {
+                tag: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
     
-    Ok(Category {
-        id: category_id,
-        name,
-        color,
-        created_at: now,
+    let mut tags = Vec::new();
+    for row in rows {
+        tags.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    Ok(tags)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderTotal {
+    order_count: i64,
+    // 상품 행 수가 아닌 수량 합계
+    item_count: i64,
+    total_spent: i64,
+}
+
+#[derive(Serialize)]
+struct ProviderTotals {
+    naver: ProviderTotal,
+    coupang: ProviderTotal,
+}
+
+// provider별 유효 결제의 주문 수/상품 수량/총 지출 집계
+fn provider_total(conn: &Connection, provider: &str, user_id: &str) -> Result<ProviderTotal, String> {
+    let (payment_table, item_table) = payment_tables(provider)?;
+    // 네이버는 결제 목록과 같이 예약(BOOKING)/콘텐츠(CONTENTS) 결제 제외
+    let status_filter = if provider == "naver" {
+        "p.status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+           AND (p.service_type IS NULL OR p.service_type NOT IN ('BOOKING', 'CONTENTS'))"
+    } else {
+        "(p.status_code IS NULL OR p.status_code != 'CANCELED')"
+    };
+    let sql = format!(
+        "SELECT COUNT(*),
+                COALESCE(SUM((SELECT SUM(i.quantity) FROM {item} i WHERE i.payment_id = p.id)), 0),
+                COALESCE(SUM(p.total_amount), 0)
+         FROM {payment} p
+         WHERE p.user_id = ?1 AND {status}",
+        item = item_table,
+        payment = payment_table,
+        status = status_filter,
+    );
+    conn.query_row(&sql, [user_id], |row| {
+        Ok(ProviderTotal {
+            order_count: row.get(0)?,
+            item_count: row.get(1)?,
+            total_spent: row.get(2)?,
+        })
     })
+    .map_err(|e| e.to_string())
 }
 
+// 대시보드 상단 요약용 provider별 합계 (계정은 provider마다 따로 있으므로 각각 지정, 미지정/데이터 없음은 0)
 #[tauri::command]
-fn delete_category(
+fn get_provider_totals(
     app_handle: AppHandle,
     state: State<AppState>,
-    category_id: String,
-) -> Result<(), String> {
+    naver_user_id: Option<String>,
+    coupang_user_id: Option<String>,
+) -> Result<ProviderTotals, String> {
+    let empty = || ProviderTotal {
+        order_count: 0,
+        item_count: 0,
+        total_spent: 0,
+    };
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
+        return Ok(ProviderTotals {
+            naver: empty(),
+            coupang: empty(),
+        });
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    conn.execute("DELETE FROM tbl_category WHERE id = ?1", [category_id])
-        .map_err(|e| e.to_string())?;
-    
-    Ok(())
+    let total = |provider: &str, user_id: &Option<String>| match user_id {
+        Some(user_id) => provider_total(&conn, provider, user_id),
+        None => Ok(empty()),
+    };
+    Ok(ProviderTotals {
+        naver: total("naver", &naver_user_id)?,
+        coupang: total("coupang", &coupang_user_id)?,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PurchaseSummary {
+    provider: String,
+    payment_id: i64,
+    merchant_name: String,
+    product_name: Option<String>,
+    total_amount: i64,
+    // 쿠팡은 주문일
+    paid_at: String,
+}
+
+// 네이버/쿠팡 유효 결제를 공통 컬럼으로 합친 서브쿼리
+// ?1 user_id, ?2/?3 날짜 범위(YYYY-MM-DD, 양 끝 포함), ?4 provider (NULL이면 전체)
+const PURCHASE_UNION_SQL: &str = "
+    SELECT 'naver' AS provider, id, merchant_name, product_name, total_amount, paid_at
+    FROM tbl_naver_payment
+    WHERE user_id = ?1
+      AND (?4 IS NULL OR ?4 = 'naver')
+      AND status_code IN ('PURCHASE_CONFIRMED', 'PAYMENT_COMPLETED', 'DELIVERED', 'PURCHASE_CONFIRM_EXTENDED')
+      AND (?2 IS NULL OR substr(paid_at, 1, 10) >= ?2)
+      AND (?3 IS NULL OR substr(paid_at, 1, 10) <= ?3)
+    UNION ALL
+    SELECT 'coupang', id, merchant_name, product_name, total_amount, ordered_at
+    FROM tbl_coupang_payment
+    WHERE user_id = ?1
+      AND (?4 IS NULL OR ?4 = 'coupang')
+      AND (status_code IS NULL OR status_code != 'CANCELED')
+      AND (?2 IS NULL OR substr(ordered_at, 1, 10) >= ?2)
+      AND (?3 IS NULL OR substr(ordered_at, 1, 10) <= ?3)";
+
+// SELECT provider, id, merchant_name, product_name, total_amount, paid_at 순서의 행을 PurchaseSummary로 변환
+fn purchase_summary_from_row(row: &rusqlite::Row) -> rusqlite::Result<PurchaseSummary> {
+    Ok(PurchaseSummary {
+        provider: row.get(0)?,
+        payment_id: row.get(1)?,
+        merchant_name: row.get(2)?,
+        product_name: row.get(3)?,
+        total_amount: row.get(4)?,
+        paid_at: row.get(5)?,
+    })
 }
 
+// 금액이 가장 큰 결제 목록 (provider 미지정 시 네이버/쿠팡 통합)
 #[tauri::command]
-fn get_product_meta(
+fn get_top_purchases(
     app_handle: AppHandle,
     state: State<AppState>,
-    provider: String,
-    item_id: i64,
-) -> Result<Option<ProductMeta>, String> {
+    user_id: String,
+    provider: Option<String>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<PurchaseSummary>, String> {
+    if let Some(provider) = &provider {
+        payment_tables(provider)?;
+    }
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let result_limit = clamp_limit(limit, 10, MAX_LIST_LIMIT);
     
-    // 메타데이터 조회
-    let meta_result: Result<(String, String, i64, Option<String>, Option<String>, Option<i32>, String, String), rusqlite::Error> = conn.query_row(
-        "SELECT id, provider, item_id, memo, url, rating, created_at, updated_at
-         FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
-        rusqlite::params![provider, item_id],
-        |row| {
-            Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-            ))
-        },
-    );
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT provider, id, merchant_name, product_name, total_amount, paid_at
+             FROM ({})
+             ORDER BY total_amount DESC, paid_at DESC, provider, id
+             LIMIT ?5",
+            PURCHASE_UNION_SQL
+        ))
+        .map_err(|e| e.to_string())?;
     
-    match meta_result {
-        Ok((id, provider, item_id, memo, url, rating, created_at, updated_at)) => {
-            // 태그 조회
-            let mut tag_stmt = conn
-                .prepare("SELECT tag FROM tbl_product_tag WHERE meta_id = ?1 ORDER BY tag")
-                .map_err(|e| e.to_string())?;
-            let tag_rows = tag_stmt
-                .query_map([&id], |row| row.get::<_, String>(0))
-                .map_err(|e| e.to_string())?;
-            
-            let mut tags = Vec::new();
-            for tag_result in tag_rows {
-                tags.push(tag_result.map_err(|e| e.to_string())?);
-            }
-            
-            // 카테고리 조회
-            let mut cat_stmt = conn
-                .prepare(
-                    "SELECT c.id, c.name, c.color, c.created_at
-                     FROM tbl_category c
-                     INNER JOIN tbl_product_category pc ON c.id = pc.category_id
-                     WHERE pc.meta_id = ?1
-                     ORDER BY c.name"
-                )
-                .map_err(|e| e.to_string())?;
-            let cat_rows = cat_stmt
-                .query_map([&id], |row| {
-                    Ok(Category {
-                        id: row.get(0)?,
-                        name: row.get(1)?,
-                        color: row.get(2)?,
-                        created_at: row.get(3)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?;
-            
-            let mut categories = Vec::new();
-            for cat_result in cat_rows {
-                categories.push(cat_result.map_err(|e| e.to_string())?);
-            }
-            
-            Ok(Some(ProductMeta {
-                id,
-                provider,
-                item_id,
-                memo,
-                url,
-                rating,
-                tags,
-                categories,
-                created_at,
-                updated_at,
-            }))
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
+    let rows = stmt
+        .query_map(
+            rusqlite::params![user_id, from_date, to_date, provider, result_limit],
+            purchase_summary_from_row,
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let mut purchases = Vec::new();
+    for row in rows {
+        purchases.push(row.map_err(|e| e.to_string())?);
     }
+    Ok(purchases)
+}
+
+#[derive(Serialize)]
+struct PurchasePage {
+    items: Vec<PurchaseSummary>,
+    // 페이지와 무관한 전체 건수
+    total: i64,
 }
 
+// 네이버/쿠팡 결제를 날짜 내림차순 하나의 목록으로 페이지 조회
 #[tauri::command]
-fn save_product_meta(
+fn list_all_purchases(
     app_handle: AppHandle,
     state: State<AppState>,
-    provider: String,
-    item_id: i64,
-    input: ProductMetaInput,
-) -> Result<ProductMeta, String> {
+    user_id: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<PurchasePage, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
+        return Ok(PurchasePage { items: Vec::new(), total: 0 });
     }
-    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-    
-    let now = Utc::now().to_rfc3339();
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let limit = clamp_limit(limit, 100, MAX_LIST_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let provider: Option<String> = None;
     
-    // 기존 메타데이터 확인
-    let existing_id: Option<String> = tx
+    let total: i64 = conn
         .query_row(
-            "SELECT id FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
-            rusqlite::params![provider, item_id],
+            &format!("SELECT COUNT(*) FROM ({})", PURCHASE_UNION_SQL),
+            rusqlite::params![user_id, from_date, to_date, provider],
             |row| row.get(0),
         )
-        .ok();
-    
-    let meta_id = if let Some(id) = existing_id {
-        // 업데이트
-        tx.execute(
-            "UPDATE tbl_product_meta SET memo = ?1, url = ?2, rating = ?3, updated_at = ?4 WHERE id = ?5",
-            rusqlite::params![input.memo, input.url, input.rating, now, id],
-        )
-        .map_err(|e| e.to_string())?;
-        id
-    } else {
-        // 새로 생성
-        let new_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO tbl_product_meta (id, provider, item_id, memo, url, rating, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![new_id, provider, item_id, input.memo, input.url, input.rating, now, now],
-        )
-        .map_err(|e| e.to_string())?;
-        new_id
-    };
-    
-    // 태그 삭제 후 재생성
-    tx.execute("DELETE FROM tbl_product_tag WHERE meta_id = ?1", [&meta_id])
-        .map_err(|e| e.to_string())?;
-    
-    for tag in &input.tags {
-        let tag_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO tbl_product_tag (id, meta_id, tag, created_at) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![tag_id, meta_id, tag, now],
-        )
         .map_err(|e| e.to_string())?;
-    }
     
-    // 카테고리 관계 삭제 후 재생성
-    tx.execute("DELETE FROM tbl_product_category WHERE meta_id = ?1", [&meta_id])
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT provider, id, merchant_name, product_name, total_amount, paid_at
+             FROM ({})
+             ORDER BY paid_at DESC, provider, id DESC
+             LIMIT ?5 OFFSET ?6",
+            PURCHASE_UNION_SQL
+        ))
         .map_err(|e| e.to_string())?;
-    
-    for category_id in &input.category_ids {
-        let rel_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO tbl_product_category (id, meta_id, category_id, created_at) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![rel_id, meta_id, category_id, now],
+    let rows = stmt
+        .query_map(
+            rusqlite::params![user_id, from_date, to_date, provider, limit, offset],
+            purchase_summary_from_row,
         )
         .map_err(|e| e.to_string())?;
-    }
     
-    tx.commit().map_err(|e| e.to_string())?;
-    
-    // 저장된 데이터 반환
-    get_product_meta(app_handle, state, provider, item_id)?
-        .ok_or_else(|| "저장된 메타데이터를 찾을 수 없습니다.".to_string())
-}
-
-#[tauri::command]
-fn delete_product_meta(
-    app_handle: AppHandle,
-    state: State<AppState>,
-    provider: String,
-    item_id: i64,
-) -> Result<(), String> {
-    let path = configured_db_path(&app_handle, &state)?
-        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
-    if !path.exists() {
-        return Err("DB 파일이 존재하지 않습니다.".to_string());
-    }
-    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
-    
-    // CASCADE로 태그, 카테고리 관계도 자동 삭제
-    conn.execute(
-        "DELETE FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
-        rusqlite::params![provider, item_id],
-    )
-    .map_err(|e| e.to_string())?;
-    
-    Ok(())
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(PurchasePage { items, total })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedTag {
+    tag: String,
+    co_occurrence: i64,
 }
 
+// 같은 상품에 함께 붙은 횟수 기준으로 연관 태그 조회 (입력 태그 제외)
 #[tauri::command]
-fn search_tags(
+fn get_related_tags(
     app_handle: AppHandle,
     state: State<AppState>,
-    query: String,
+    tag: String,
     limit: Option<i64>,
-) -> Result<Vec<String>, String> {
+    provider: Option<String>,
+) -> Result<Vec<RelatedTag>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
     if !path.exists() {
@@ -2956,28 +11408,36 @@ fn search_tags(
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
-    let search_term = format!("%{}%", query);
-    let result_limit = limit.unwrap_or(20);
+    let result_limit = clamp_limit(limit, 10, MAX_LIST_LIMIT);
     
     let mut stmt = conn
         .prepare(
-            "SELECT DISTINCT tag FROM tbl_product_tag 
-             WHERE tag LIKE ?1 
-             ORDER BY tag 
-             LIMIT ?2"
+            "SELECT partner.tag, COUNT(*) AS co_occurrence
+             FROM tbl_product_tag base
+             INNER JOIN tbl_product_tag partner ON partner.meta_id = base.meta_id AND partner.tag != base.tag
+             INNER JOIN tbl_product_meta m ON m.id = base.meta_id
+             WHERE base.tag = ?1 AND (?3 IS NULL OR m.provider = ?3)
+             GROUP BY partner.tag
+             ORDER BY co_occurrence DESC, partner.tag
+             LIMIT ?2",
         )
         .map_err(|e| e.to_string())?;
     
     let rows = stmt
-        .query_map(rusqlite::params![search_term, result_limit], |row| row.get(0))
+        .query_map(rusqlite::params![tag, result_limit, provider], |row| {
+            Ok(RelatedTag {
+                tag: row.get(0)?,
+                co_occurrence: row.get(1)?,
+            })
+        })
         .map_err(|e| e.to_string())?;
     
-    let mut tags = Vec::new();
+    let mut related = Vec::new();
     for row in rows {
-        tags.push(row.map_err(|e| e.to_string())?);
+        related.push(row.map_err(|e| e.to_string())?);
     }
     
-    Ok(tags)
+    Ok(related)
 }
 
 /// 상품 메타데이터 요약 정보
@@ -2992,12 +11452,16 @@ struct ProductMetaSummary {
     rating: Option<i32>,
 }
 
-/// 특정 provider의 모든 상품 메타데이터 요약 조회
+/// 특정 provider의 모든 상품 메타데이터 요약 조회 (has_* 필터: true=있는 항목만, false=없는 항목만)
 #[tauri::command]
 fn list_product_meta_summaries(
     app_handle: AppHandle,
     state: State<AppState>,
     provider: String,
+    has_tags: Option<bool>,
+    has_categories: Option<bool>,
+    has_memo: Option<bool>,
+    min_rating: Option<i32>,
 ) -> Result<Vec<ProductMetaSummary>, String> {
     let path = configured_db_path(&app_handle, &state)?
         .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
@@ -3006,23 +11470,59 @@ fn list_product_meta_summaries(
     }
     let conn = Connection::open(&path).map_err(|e| e.to_string())?;
     
+    query_product_meta_summaries(&conn, provider, has_tags, has_categories, has_memo, min_rating)
+}
+
+// 존재 여부 플래그(None이면 미적용)와 최소 별점으로 필터링한 메타데이터 요약 조회
+fn query_product_meta_summaries(
+    conn: &Connection,
+    provider: String,
+    has_tags: Option<bool>,
+    has_categories: Option<bool>,
+    has_memo: Option<bool>,
+    min_rating: Option<i32>,
+) -> Result<Vec<ProductMetaSummary>, String> {
+    let mut conditions = vec!["m.provider = ?".to_string()];
+    let mut params: Vec<rusqlite::types::Value> = vec![provider.into()];
+    
+    let presence = |flag: bool, exists_sql: &str| {
+        if flag {
+            exists_sql.to_string()
+        } else {
+            format!("NOT {}", exists_sql)
+        }
+    };
+    if let Some(flag) = has_tags {
+        conditions.push(presence(flag, "EXISTS (SELECT 1 FROM tbl_product_tag WHERE meta_id = m.id)"));
+    }
+    if let Some(flag) = has_categories {
+        conditions.push(presence(flag, "EXISTS (SELECT 1 FROM tbl_product_category WHERE meta_id = m.id)"));
+    }
+    if let Some(flag) = has_memo {
+        conditions.push(presence(flag, "(m.memo IS NOT NULL AND m.memo != '')"));
+    }
+    if let Some(rating) = min_rating {
+        conditions.push("m.rating >= ?".to_string());
+        params.push(i64::from(rating).into());
+    }
+    
     // 메타데이터와 태그/카테고리 개수를 한 번에 조회
-    let mut stmt = conn
-        .prepare(
-            "SELECT 
-                m.item_id,
-                m.memo,
-                m.url,
-                m.rating,
-                (SELECT COUNT(*) FROM tbl_product_tag WHERE meta_id = m.id) as tag_count,
-                (SELECT COUNT(*) FROM tbl_product_category WHERE meta_id = m.id) as category_count
-             FROM tbl_product_meta m
-             WHERE m.provider = ?1"
-        )
-        .map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT 
+            m.item_id,
+            m.memo,
+            m.url,
+            m.rating,
+            (SELECT COUNT(*) FROM tbl_product_tag WHERE meta_id = m.id) as tag_count,
+            (SELECT COUNT(*) FROM tbl_product_category WHERE meta_id = m.id) as category_count
+         FROM tbl_product_meta m
+         WHERE {}",
+        conditions.join(" AND ")
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
     
     let rows = stmt
-        .query_map(rusqlite::params![provider], |row| {
+        .query_map(rusqlite::params_from_iter(params), |row| {
             let memo: Option<String> = row.get(1)?;
             let url: Option<String> = row.get(2)?;
             let rating: Option<i32> = row.get(3)?;
@@ -3048,8 +11548,271 @@ fn list_product_meta_summaries(
     Ok(summaries)
 }
 
+// 상품 메타데이터 내보내기/가져오기 파일 항목 (카테고리는 이름으로 저장)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductMetaExportItem {
+    provider: String,
+    item_id: i64,
+    memo: Option<String>,
+    url: Option<String>,
+    rating: Option<i32>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductMetaImportResult {
+    inserted: u32,
+    updated: u32,
+    skipped: u32,
+    categories_created: u32,
+}
+
+// 상품 메타데이터(메모/태그/카테고리 등)만 JSON 파일로 내보내기
+#[tauri::command]
+fn export_product_meta(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    provider: Option<String>,
+    target_path: String,
+) -> Result<u32, String> {
+    if let Some(provider) = &provider {
+        payment_tables(provider)?;
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, provider, item_id, memo, url, rating
+             FROM tbl_product_meta
+             WHERE ?1 IS NULL OR provider = ?1
+             ORDER BY provider, item_id",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let rows = stmt
+        .query_map(rusqlite::params![provider], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ProductMetaExportItem {
+                    provider: row.get(1)?,
+                    item_id: row.get(2)?,
+                    memo: row.get(3)?,
+                    url: row.get(4)?,
+                    rating: row.get(5)?,
+                    tags: Vec::new(),
+                    categories: Vec::new(),
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    
+    let mut metas = Vec::new();
+    for row in rows {
+        metas.push(row.map_err(|e| e.to_string())?);
+    }
+    
+    let mut tag_stmt = conn
+        .prepare("SELECT tag FROM tbl_product_tag WHERE meta_id = ?1 ORDER BY tag")
+        .map_err(|e| e.to_string())?;
+    let mut category_stmt = conn
+        .prepare(
+            "SELECT c.name FROM tbl_category c
+             INNER JOIN tbl_product_category pc ON c.id = pc.category_id
+             WHERE pc.meta_id = ?1
+             ORDER BY c.name, c.id",
+        )
+        .map_err(|e| e.to_string())?;
+    
+    let mut items = Vec::new();
+    for (meta_id, mut item) in metas {
+        item.tags = tag_stmt
+            .query_map([&meta_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?;
+        item.categories = category_stmt
+            .query_map([&meta_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?;
+        items.push(item);
+    }
+    
+    let target = PathBuf::from(target_path);
+    ensure_parent(&target)?;
+    let serialized = serde_json::to_vec_pretty(&items).map_err(|e| e.to_string())?;
+    fs::write(&target, serialized).map_err(|e| e.to_string())?;
+    
+    Ok(items.len() as u32)
+}
+
+// 내보낸 상품 메타데이터 파일 가져오기 (provider + item_id 기준 upsert)
+// strategy: "overwrite" (기존 값 덮어쓰기), "skip" (기존 항목 유지), "merge" (비어있는 값 채우고 태그/카테고리 추가)
+#[tauri::command]
+fn import_product_meta(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    source_path: String,
+    strategy: String,
+) -> Result<ProductMetaImportResult, String> {
+    if !matches!(strategy.as_str(), "overwrite" | "skip" | "merge") {
+        return Err(format!("지원하지 않는 가져오기 방식입니다: {}", strategy));
+    }
+    
+    let path = configured_db_path(&app_handle, &state)?
+        .ok_or_else(|| "DB가 설정되지 않았습니다.".to_string())?;
+    if !path.exists() {
+        return Err("DB 파일이 존재하지 않습니다.".to_string());
+    }
+    
+    let data = fs::read_to_string(&source_path).map_err(|e| e.to_string())?;
+    let items: Vec<ProductMetaExportItem> =
+        serde_json::from_str(&data).map_err(|e| format!("메타데이터 파일 형식이 올바르지 않습니다: {}", e))?;
+    for item in &items {
+        payment_tables(&item.provider)?;
+    }
+    
+    let mut conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    
+    let now = Utc::now().to_rfc3339();
+    let mut result = ProductMetaImportResult {
+        inserted: 0,
+        updated: 0,
+        skipped: 0,
+        categories_created: 0,
+    };
+    
+    for item in &items {
+        let existing_id: Option<String> = match tx.query_row(
+            "SELECT id FROM tbl_product_meta WHERE provider = ?1 AND item_id = ?2",
+            rusqlite::params![item.provider, item.item_id],
+            |row| row.get(0),
+        ) {
+            Ok(id) => Some(id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e.to_string()),
+        };
+        
+        let meta_id = match existing_id {
+            Some(_) if strategy == "skip" => {
+                result.skipped += 1;
+                continue;
+            }
+            Some(id) => {
+                if strategy == "overwrite" {
+                    tx.execute(
+                        "UPDATE tbl_product_meta SET memo = ?1, url = ?2, rating = ?3, updated_at = ?4 WHERE id = ?5",
+                        rusqlite::params![item.memo, item.url, item.rating, now, id],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    tx.execute("DELETE FROM tbl_product_tag WHERE meta_id = ?1", [&id])
+                        .map_err(|e| e.to_string())?;
+                    tx.execute("DELETE FROM tbl_product_category WHERE meta_id = ?1", [&id])
+                        .map_err(|e| e.to_string())?;
+                } else {
+                    tx.execute(
+                        "UPDATE tbl_product_meta
+                         SET memo = COALESCE(NULLIF(memo, ''), ?1),
+                             url = COALESCE(NULLIF(url, ''), ?2),
+                             rating = COALESCE(rating, ?3),
+                             updated_at = ?4
+                         WHERE id = ?5",
+                        rusqlite::params![item.memo, item.url, item.rating, now, id],
+                    )
+                    .map_err(|e| e.to_string())?;
+                }
+                result.updated += 1;
+                id
+            }
+            None => {
+                let new_id = Uuid::new_v4().to_string();
+                tx.execute(
+                    "INSERT INTO tbl_product_meta (id, provider, item_id, memo, url, rating, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    rusqlite::params![new_id, item.provider, item.item_id, item.memo, item.url, item.rating, now, now],
+                )
+                .map_err(|e| e.to_string())?;
+                result.inserted += 1;
+                new_id
+            }
+        };
+        
+        // 태그 추가 (merge 시 이미 있는 태그는 건너뜀)
+        for tag in &item.tags {
+            let tag_id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO tbl_product_tag (id, meta_id, tag, created_at)
+                 SELECT ?1, ?2, ?3, ?4
+                 WHERE NOT EXISTS (SELECT 1 FROM tbl_product_tag WHERE meta_id = ?2 AND tag = ?3)",
+                rusqlite::params![tag_id, meta_id, tag, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        
+        // 카테고리는 이름으로 찾고, 없으면 새로 생성
+        for name in &item.categories {
+            let category_id = match tx.query_row(
+                "SELECT id FROM tbl_category WHERE name = ?1",
+                [name],
+                |row| row.get(0),
+            ) {
+                Ok(id) => id,
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    let new_id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO tbl_category (id, name, color, created_at) VALUES (?1, ?2, NULL, ?3)",
+                        rusqlite::params![new_id, name, now],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    result.categories_created += 1;
+                    new_id
+                }
+                Err(e) => return Err(e.to_string()),
+            };
+            
+            let rel_id = Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO tbl_product_category (id, meta_id, category_id, created_at)
+                 SELECT ?1, ?2, ?3, ?4
+                 WHERE NOT EXISTS (SELECT 1 FROM tbl_product_category WHERE meta_id = ?2 AND category_id = ?3)",
+                rusqlite::params![rel_id, meta_id, category_id, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    
+    tx.commit().map_err(|e| e.to_string())?;
+    tracing::info!(
+        inserted = result.inserted,
+        updated = result.updated,
+        skipped = result.skipped,
+        "product meta import completed"
+    );
+    
+    Ok(result)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let (level_filter, level_handle) = reload::Layer::new(LevelFilter::INFO);
+    tracing_subscriber::registry()
+        .with(level_filter)
+        .with(fmt::layer())
+        .init();
+    let _ = LOG_LEVEL_HANDLE.set(level_handle);
+
     tauri::Builder::default()
         .manage(AppState::default())
         .plugin(tauri_plugin_http::init())
@@ -3057,49 +11820,844 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             greet,
+            set_log_level,
             proxy_request,
+            proxy_request_json,
+            get_default_request_headers,
+            set_default_request_headers,
+            download_to_file,
             get_db_status,
+            get_db_status_readonly,
+            get_health,
+            verify_schema,
             init_db,
             load_existing_db,
+            normalize_config,
             logout,
             has_users,
             list_users,
             save_account,
+            validate_curl,
             delete_user,
             update_user,
             get_user_credentials,
+            find_orphaned_credentials,
+            purge_orphaned_credentials,
             update_account_credentials,
             save_naver_payment,
             list_naver_payments,
+            list_naver_bookings,
+            get_naver_payment_by_external_id,
             get_last_naver_payment,
             list_coupang_payments,
+            export_naver_payments_json,
+            export_coupang_payments_json,
+            delete_naver_payments_batch,
+            delete_coupang_payments_batch,
             save_coupang_payment,
+            update_naver_payment_status,
+            update_coupang_payment_status,
             get_last_coupang_payment,
+            get_latest_payments_all_users,
+            get_naver_payments_by_merchant,
+            list_naver_payments_by_product_keyword,
+            count_naver_payments_by_product_keyword,
+            get_coupang_payments_by_merchant,
+            get_naver_payment_detail,
+            get_coupang_payment_detail,
+            get_coupang_item_savings,
+            get_naver_payment_items_by_product_name,
+            get_coupang_payment_items_by_product_name,
+            find_coupang_payment_by_item_info,
+            get_coupang_payment_items_by_brand,
+            count_coupang_payment_items_by_brand,
+            get_naver_tax_type_payments,
+            get_naver_tax_summary,
+            get_naver_plus_stats,
+            get_naver_benefit_comparison,
+            get_naver_cafe_payment_stats,
+            get_naver_pending_benefits,
+            get_naver_payment_count_by_status,
+            get_naver_country_breakdown,
+            get_spend_by_country,
+            get_naver_purchase_frequency,
+            get_naver_payment_category_guess,
+            create_merchant_rule,
+            update_merchant_rule,
+            delete_merchant_rule,
+            list_merchant_rules,
+            apply_merchant_rules_to_pending,
+            get_spending_by_time,
+            renumber_payment_items,
+            get_payment_raw,
+            get_item_price_history,
+            recalc_product_counts,
             search_products,
+            get_recent_activity,
             get_table_stats,
+            get_schema_sql,
+            get_db_schema,
             truncate_table,
+            reset_data,
             get_table_data,
             create_ledger_account,
             list_ledger_accounts,
             verify_ledger_password,
+            clear_auth_lockout,
+            unlock_ledger_account,
+            lock_ledger_account,
+            get_ledger_unlock_status,
+            check_password_strength,
             update_ledger_password,
+            update_ledger_account,
+            update_ledger_account_nickname,
             check_password_expiry,
             delete_ledger_account,
             create_ledger_entry,
+            payment_to_ledger,
             update_ledger_entry,
+            move_ledger_entry,
             delete_ledger_entry,
             list_ledger_entries,
+            list_ledger_entries_with_receipt,
+            list_untagged_ledger_entries,
+            list_uncategorized_ledger_entries,
+            get_ledger_url_count,
+            list_ledger_entries_by_tag,
             get_ledger_entry,
             list_ledger_history,
+            list_ledger_history_all,
+            get_ledger_history_diff,
+            prune_ledger_history,
+            create_ledger_rule,
+            list_ledger_rules,
+            delete_ledger_rule,
+            apply_ledger_rules,
+            backfill_entry_colors,
+            backup_ledger_account,
+            restore_ledger_account,
+            get_ledger_payment_method_breakdown,
+            get_ledger_tag_summary,
+            get_ledger_income_sources,
+            get_ledger_income_trend,
+            get_ledger_net_worth_timeline,
+            get_ledger_weekly_summary,
+            list_ledger_weekly_summaries,
+            compare_ledger_months,
+            export_ledger_summary_text,
+            get_ledger_category_monthly_chart,
+            get_ledger_merchant_trend,
+            forecast_next_month,
             list_categories,
             create_category,
+            set_category_parent,
+            get_category_tree,
             delete_category,
             get_product_meta,
+            get_product_meta_with_purchase_history,
             save_product_meta,
+            copy_product_meta,
             delete_product_meta,
+            assign_category_to_products,
+            assign_tag_to_products,
             search_tags,
-            list_product_meta_summaries
+            search_ledger_tags,
+            search_all_tags,
+            list_purchased_item_tags,
+            get_provider_totals,
+            get_top_purchases,
+            list_all_purchases,
+            get_related_tags,
+            list_product_meta_summaries,
+            export_product_meta,
+            import_product_meta
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn schema_report_ok_after_migrations() {
+        let conn = migrated_conn();
+        let report = schema_report(&conn).unwrap();
+        assert!(report.ok);
+        assert!(report.missing_tables.is_empty());
+        assert!(report.missing_columns.is_empty());
+        assert!(report.missing_indexes.is_empty());
+    }
+
+    #[test]
+    fn schema_report_finds_missing_table_column_and_index() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             DROP TABLE tbl_merchant_rule;
+             DROP INDEX idx_coupang_item_product_id;
+             ALTER TABLE tbl_ledger_account DROP COLUMN currency_symbol;",
+        )
+        .unwrap();
+
+        let report = schema_report(&conn).unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.missing_tables, vec!["tbl_merchant_rule".to_string()]);
+        assert_eq!(report.missing_columns.len(), 1);
+        assert_eq!(report.missing_columns[0].table, "tbl_ledger_account");
+        assert_eq!(report.missing_columns[0].columns, vec!["currency_symbol".to_string()]);
+        assert_eq!(report.missing_indexes, vec!["idx_coupang_item_product_id".to_string()]);
+    }
+
+    #[test]
+    fn save_product_meta_concurrent_saves_succeed() {
+        let path = std::env::temp_dir().join(format!("product-meta-concurrent-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        run_migrations(&path).unwrap();
+
+        let results: Vec<Result<(), String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let path = &path;
+                    scope.spawn(move || {
+                        let input = ProductMetaInput {
+                            memo: Some(format!("memo {}", i)),
+                            url: None,
+                            rating: None,
+                            tags: vec![format!("tag{}", i)],
+                            category_ids: vec![],
+                        };
+                        save_product_meta_to_db(path, "naver", 42, &input)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        for result in &results {
+            assert!(result.is_ok(), "{:?}", result);
+        }
+
+        let conn = Connection::open(&path).unwrap();
+        let metas: i64 = conn
+            .query_row("SELECT COUNT(*) FROM tbl_product_meta WHERE provider = 'naver' AND item_id = 42", [], |row| row.get(0))
+            .unwrap();
+        let tags: i64 = conn
+            .query_row("SELECT COUNT(*) FROM tbl_product_tag", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!((metas, tags), (1, 1));
+        drop(conn);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn clamp_limit_bounds() {
+        assert_eq!(clamp_limit(None, 20, 100), 20);
+        assert_eq!(clamp_limit(Some(-5), 20, 100), 20);
+        assert_eq!(clamp_limit(Some(0), 20, 100), 20);
+        assert_eq!(clamp_limit(Some(1), 20, 100), 1);
+        assert_eq!(clamp_limit(Some(100), 20, 100), 100);
+        assert_eq!(clamp_limit(Some(101), 20, 100), 100);
+        assert_eq!(clamp_limit(Some(i64::MAX), 20, MAX_LIST_LIMIT), MAX_LIST_LIMIT);
+    }
+
+    fn entry_input(entry_type: &str, amount: i64, date: &str) -> LedgerEntryInput {
+        LedgerEntryInput {
+            account_id: "acc".to_string(),
+            r#type: entry_type.to_string(),
+            amount,
+            date: date.to_string(),
+            title: "점심".to_string(),
+            category: "식비".to_string(),
+            platform: None,
+            url: None,
+            merchant: None,
+            payment_method: None,
+            memo: None,
+            color: None,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn validate_ledger_entry_input_cases() {
+        assert!(validate_ledger_entry_input(&entry_input("expense", 1000, "2025-01-31")).is_ok());
+        assert!(validate_ledger_entry_input(&entry_input("income", 1, "2025-12-01")).is_ok());
+
+        // 유형
+        assert!(validate_ledger_entry_input(&entry_input("transfer", 1000, "2025-01-31")).is_err());
+        assert!(validate_ledger_entry_input(&entry_input("Expense", 1000, "2025-01-31")).is_err());
+        // 금액
+        assert!(validate_ledger_entry_input(&entry_input("expense", 0, "2025-01-31")).is_err());
+        assert!(validate_ledger_entry_input(&entry_input("expense", -500, "2025-01-31")).is_err());
+        // 날짜 형식
+        for date in ["2025-1-31", "2025/01/31", "20250131", "2025-01-31T00:00:00", "", "２０２５-01-31"] {
+            assert!(validate_ledger_entry_input(&entry_input("expense", 1000, date)).is_err(), "{}", date);
+        }
+    }
+
+    fn form(pairs: &[(&str, &str)]) -> String {
+        let fields: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        form_urlencode(&fields)
+    }
+
+    #[test]
+    fn form_urlencode_cases() {
+        assert_eq!(form(&[]), "");
+        assert_eq!(form(&[("q", "hello world")]), "q=hello+world");
+        assert_eq!(form(&[("a&b", "c=d&e")]), "a%26b=c%3Dd%26e");
+        assert_eq!(form(&[("name", "가 나")]), "name=%EA%B0%80+%EB%82%98");
+        assert_eq!(form(&[("safe", "A-z_0.9*"), ("plus", "1+1")]), "plus=1%2B1&safe=A-z_0.9*");
+        // 키 순서와 무관하게 정렬된 결과
+        assert_eq!(form(&[("b", "2"), ("a", "1")]), "a=1&b=2");
+    }
+
+    #[test]
+    fn readonly_db_status_changes_nothing() {
+        let path = std::env::temp_dir().join(format!("readonly-status-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        run_migrations(&path).unwrap();
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("DROP TABLE tbl_ledger_rule; PRAGMA user_version = 0;").unwrap();
+        }
+        let snapshot = || {
+            let conn = Connection::open(&path).unwrap();
+            let mut stmt = conn.prepare("SELECT type, name, COALESCE(sql, '') FROM sqlite_master ORDER BY name").unwrap();
+            let rows: Vec<(String, String, String)> = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .map(|row| row.unwrap())
+                .collect();
+            (rows, fs::read(&path).unwrap())
+        };
+        let before = snapshot();
+
+        let status = readonly_db_status(&path).unwrap();
+        assert_eq!(status.schema_version, Some(0));
+        assert!(status.migration_pending);
+        assert!(!status.status.tables.contains(&"tbl_ledger_rule".to_string()));
+        assert_eq!(snapshot(), before);
+
+        run_migrations(&path).unwrap();
+        let status = readonly_db_status(&path).unwrap();
+        assert_eq!(status.schema_version, Some(SCHEMA_VERSION));
+        assert!(!status.migration_pending);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn relocate_ledger_entry_moves_to_target_month_list() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '개인'), ('b', '가족');
+             INSERT INTO tbl_ledger_entry (id, account_id, type, amount, date, title, category, source_provider, source_payment_id)
+             VALUES ('e1', 'a', 'expense', 12000, '2024-03-05', '점심', '식비', 'naver', 7),
+                    ('e2', 'b', 'expense', 9000, '2024-03-06', '점심', '식비', 'naver', 8);
+             INSERT INTO tbl_ledger_tag (id, entry_id, tag) VALUES ('t1', 'e1', '회사');",
+        )
+        .unwrap();
+        let entry = load_ledger_month_entries(&conn, "a", "2024-03").unwrap().remove(0);
+        let created_at = entry.created_at.clone();
+
+        relocate_ledger_entry(&conn, entry, "b").unwrap();
+
+        assert!(load_ledger_month_entries(&conn, "a", "2024-03").unwrap().is_empty());
+        let moved: Vec<LedgerEntry> = load_ledger_month_entries(&conn, "b", "2024-03")
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.id == "e1")
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].created_at, created_at);
+        assert_eq!(moved[0].tags, vec!["회사".to_string()]);
+        let history: i64 = conn
+            .query_row("SELECT COUNT(*) FROM tbl_ledger_history WHERE entry_id = 'e1' AND action = 'update'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(history, 1);
+
+        // 같은 결제에서 변환된 항목이 이미 있는 계정으로는 이동 불가
+        conn.execute("UPDATE tbl_ledger_entry SET account_id = 'a', source_payment_id = 8 WHERE id = 'e1'", []).unwrap();
+        let entry = load_ledger_month_entries(&conn, "a", "2024-03").unwrap().remove(0);
+        let err = relocate_ledger_entry(&conn, entry, "b").unwrap_err();
+        assert!(err.contains("이미 있습니다"));
+    }
+
+    #[test]
+    fn idempotency_key_is_scoped_to_account() {
+        let conn = migrated_conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO tbl_idempotency_key (key, scope, account_id, result_id, created_at)
+             VALUES ('k1', 'ledger_entry', 'a', 'e1', ?1)",
+            [&now],
+        )
+        .unwrap();
+
+        assert_eq!(find_idempotent_result(&conn, "ledger_entry", "a", "k1").unwrap(), Some("e1".to_string()));
+        assert_eq!(find_idempotent_result(&conn, "ledger_entry", "b", "k1").unwrap(), None);
+        // 다른 계정은 같은 키로 별도 결과를 저장할 수 있음
+        conn.execute(
+            "INSERT INTO tbl_idempotency_key (key, scope, account_id, result_id, created_at)
+             VALUES ('k1', 'ledger_entry', 'b', 'e2', ?1)",
+            [&now],
+        )
+        .unwrap();
+        assert_eq!(find_idempotent_result(&conn, "ledger_entry", "b", "k1").unwrap(), Some("e2".to_string()));
+    }
+
+    #[test]
+    fn idempotency_key_migration_keeps_keys_with_entry_account() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "DROP TABLE tbl_idempotency_key;
+             CREATE TABLE tbl_idempotency_key (
+                 key TEXT PRIMARY KEY,
+                 scope TEXT NOT NULL,
+                 result_id TEXT NOT NULL,
+                 created_at TEXT NOT NULL DEFAULT (datetime('now'))
+             );
+             INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '개인');
+             INSERT INTO tbl_ledger_entry (id, account_id, type, amount, date, title, category)
+             VALUES ('e1', 'a', 'expense', 1000, '2024-03-05', '점심', '식비');
+             INSERT INTO tbl_idempotency_key (key, scope, result_id) VALUES ('k1', 'ledger_entry', 'e1'), ('k2', 'ledger_entry', 'gone');",
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        assert!(schema_report(&conn).unwrap().ok);
+        let keys: Vec<(String, String)> = conn
+            .prepare("SELECT key, account_id FROM tbl_idempotency_key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(keys, vec![("k1".to_string(), "a".to_string())]);
+        let created_at: String = conn
+            .query_row("SELECT created_at FROM tbl_idempotency_key WHERE key = 'k1'", [], |row| row.get(0))
+            .unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(&created_at).is_ok(), "{}", created_at);
+    }
+
+    #[test]
+    fn idempotency_key_migration_expires_old_keys() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "DROP TABLE tbl_idempotency_key;
+             CREATE TABLE tbl_idempotency_key (
+                 key TEXT PRIMARY KEY,
+                 scope TEXT NOT NULL,
+                 result_id TEXT NOT NULL,
+                 created_at TEXT NOT NULL DEFAULT (datetime('now'))
+             );
+             INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '개인');
+             INSERT INTO tbl_ledger_entry (id, account_id, type, amount, date, title, category)
+             VALUES ('e1', 'a', 'expense', 1000, '2024-03-05', '점심', 'food'),
+                    ('e2', 'a', 'expense', 1000, '2024-03-05', '점심', 'food');
+             INSERT INTO tbl_idempotency_key (key, scope, result_id, created_at)
+             VALUES ('old', 'ledger_entry', 'e1', datetime('now', '-2 days')),
+                    ('fresh', 'ledger_entry', 'e2', datetime('now', '-1 hours'));",
+        )
+        .unwrap();
+
+        apply_migrations(&conn).unwrap();
+
+        assert_eq!(find_idempotent_result(&conn, "ledger_entry", "a", "old").unwrap(), None);
+        assert_eq!(find_idempotent_result(&conn, "ledger_entry", "a", "fresh").unwrap(), Some("e2".to_string()));
+    }
+
+    #[test]
+    fn insert_ledger_entry_repeats_key_without_duplicates() {
+        let conn = migrated_conn();
+        conn.execute_batch("INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '개인'), ('b', '가족');")
+            .unwrap();
+        let count = |account_id: &str| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM tbl_ledger_entry WHERE account_id = ?1", [account_id], |row| row.get(0))
+                .unwrap()
+        };
+
+        let first = insert_ledger_entry(&conn, "a", entry_input("expense", 1000, "2024-03-05"), Some("retry")).unwrap();
+        let again = insert_ledger_entry(&conn, "a", entry_input("expense", 1000, "2024-03-05"), Some("retry")).unwrap();
+        assert_eq!(first, again);
+        assert_eq!(count("a"), 1);
+
+        // 다른 계정에서 같은 키는 별개의 요청
+        let other = insert_ledger_entry(&conn, "b", entry_input("expense", 1000, "2024-03-05"), Some("retry")).unwrap();
+        assert_ne!(other, first);
+        assert_eq!((count("a"), count("b")), (1, 1));
+        assert_eq!(
+            insert_ledger_entry(&conn, "b", entry_input("expense", 1000, "2024-03-05"), Some("retry")).unwrap(),
+            other
+        );
+        assert_eq!(count("b"), 1);
+
+        // 키가 없으면 매번 새 항목
+        insert_ledger_entry(&conn, "a", entry_input("expense", 1000, "2024-03-05"), None).unwrap();
+        assert_eq!(count("a"), 2);
+    }
+
+    fn insert_naver_payment(conn: &Connection, pay_id: &str, product_count: Option<i64>, line_nos: &[i64]) -> i64 {
+        conn.execute(
+            "INSERT OR IGNORE INTO tbl_user (id, provider, alias, curl) VALUES ('u1', 'naver', '테스트', 'curl')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tbl_naver_payment (user_id, pay_id, paid_at, merchant_name, total_amount, product_count)
+             VALUES ('u1', ?1, '2024-03-05T10:00:00+09:00', '가게', 10000, ?2)",
+            rusqlite::params![pay_id, product_count],
+        )
+        .unwrap();
+        let payment_id = conn.last_insert_rowid();
+        for line_no in line_nos {
+            conn.execute(
+                "INSERT INTO tbl_naver_payment_item (payment_id, line_no, product_name) VALUES (?1, ?2, ?3)",
+                rusqlite::params![payment_id, line_no, format!("상품 {}", line_no)],
+            )
+            .unwrap();
+        }
+        payment_id
+    }
+
+    fn item_lines(conn: &Connection, payment_id: i64) -> Vec<(i64, String)> {
+        conn.prepare("SELECT line_no, product_name FROM tbl_naver_payment_item WHERE payment_id = ?1 ORDER BY line_no")
+            .unwrap()
+            .query_map([payment_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn renumber_item_lines_closes_gaps() {
+        let conn = migrated_conn();
+        let payment_id = insert_naver_payment(&conn, "p1", Some(3), &[2, 5, 9]);
+        let other_id = insert_naver_payment(&conn, "p2", Some(2), &[4, 7]);
+
+        assert_eq!(renumber_item_lines(&conn, "tbl_naver_payment_item", payment_id).unwrap(), 3);
+        assert_eq!(
+            item_lines(&conn, payment_id),
+            vec![(1, "상품 2".to_string()), (2, "상품 5".to_string()), (3, "상품 9".to_string())]
+        );
+        // 다른 결제의 라인은 그대로
+        assert_eq!(item_lines(&conn, other_id), vec![(4, "상품 4".to_string()), (7, "상품 7".to_string())]);
+        // 이미 연속이면 바뀌는 라인 없음
+        assert_eq!(renumber_item_lines(&conn, "tbl_naver_payment_item", payment_id).unwrap(), 0);
+
+        // 1이 이미 있어도 UNIQUE(payment_id, line_no) 충돌 없이 재번호
+        let partial_id = insert_naver_payment(&conn, "p3", Some(3), &[1, 3, 4]);
+        assert_eq!(renumber_item_lines(&conn, "tbl_naver_payment_item", partial_id).unwrap(), 2);
+        assert_eq!(
+            item_lines(&conn, partial_id).into_iter().map(|(line_no, _)| line_no).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn update_product_counts_fixes_drift() {
+        let conn = migrated_conn();
+        let too_high = insert_naver_payment(&conn, "p1", Some(5), &[1, 2]);
+        let missing = insert_naver_payment(&conn, "p2", None, &[1]);
+        let correct = insert_naver_payment(&conn, "p3", Some(3), &[1, 2, 3]);
+        let empty = insert_naver_payment(&conn, "p4", Some(1), &[]);
+
+        let changed = update_product_counts(&conn, "tbl_naver_payment", "tbl_naver_payment_item", "u1").unwrap();
+        assert_eq!(changed, 3);
+        let count = |id: i64| -> Option<i64> {
+            conn.query_row("SELECT product_count FROM tbl_naver_payment WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(too_high), Some(2));
+        assert_eq!(count(missing), Some(1));
+        assert_eq!(count(correct), Some(3));
+        assert_eq!(count(empty), Some(0));
+
+        assert_eq!(update_product_counts(&conn, "tbl_naver_payment", "tbl_naver_payment_item", "u1").unwrap(), 0);
+        assert_eq!(update_product_counts(&conn, "tbl_naver_payment", "tbl_naver_payment_item", "other").unwrap(), 0);
+    }
+
+    #[test]
+    fn product_meta_summary_filters() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "INSERT INTO tbl_product_meta (id, provider, item_id, memo, rating) VALUES
+                ('m1', 'naver', 1, '메모', 8),
+                ('m2', 'naver', 2, NULL, 3),
+                ('m3', 'naver', 3, '', NULL),
+                ('m4', 'naver', 4, '메모', 10),
+                ('m5', 'coupang', 5, '메모', 9);
+             INSERT INTO tbl_product_tag (id, meta_id, tag) VALUES ('t1', 'm1', '재구매'), ('t2', 'm2', '선물'), ('t3', 'm5', '재구매');
+             INSERT INTO tbl_product_category (id, meta_id, category_id) VALUES ('c1', 'm1', 'cat_food'), ('c2', 'm3', 'cat_etc');",
+        )
+        .unwrap();
+        let ids = |has_tags, has_categories, has_memo, min_rating| -> Vec<i64> {
+            let mut ids: Vec<i64> =
+                query_product_meta_summaries(&conn, "naver".to_string(), has_tags, has_categories, has_memo, min_rating)
+                    .unwrap()
+                    .into_iter()
+                    .map(|summary| summary.item_id)
+                    .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(ids(None, None, None, None), vec![1, 2, 3, 4]);
+        assert_eq!(ids(Some(true), None, None, None), vec![1, 2]);
+        assert_eq!(ids(None, Some(false), None, None), vec![2, 4]);
+        assert_eq!(ids(None, None, Some(false), None), vec![2, 3]);
+        assert_eq!(ids(None, None, None, Some(8)), vec![1, 4]);
+        assert_eq!(ids(Some(true), Some(true), None, None), vec![1]);
+        assert_eq!(ids(Some(false), Some(false), Some(true), None), vec![4]);
+        assert_eq!(ids(Some(true), None, Some(true), Some(9)), Vec::<i64>::new());
+
+        let summary = query_product_meta_summaries(&conn, "naver".to_string(), Some(true), Some(true), None, None)
+            .unwrap()
+            .remove(0);
+        assert!(summary.has_tags && summary.has_categories && summary.has_memo && !summary.has_url);
+        assert_eq!(summary.rating, Some(8));
+    }
+
+    // /start 요청은 /final로 302, 나머지는 200을 돌려주는 로컬 HTTP 서버
+    fn spawn_redirect_fixture() -> String {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let response = if request_line.starts_with("GET /start ") {
+                    "HTTP/1.1 302 Found\r\nLocation: /final\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\ndone"
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        base
+    }
+
+    #[test]
+    fn proxy_request_redirect_options() {
+        let base = spawn_redirect_fixture();
+        let url = format!("{}/start", base);
+        let send = |follow_redirects, max_redirects| {
+            perform_proxy_request(&url, "GET", HashMap::new(), None, None, follow_redirects, max_redirects, None)
+        };
+
+        let response = send(Some(false), None).unwrap();
+        assert_eq!(response.status, 302);
+        assert_eq!(response.redirect_count, 0);
+        assert!(response
+            .response_headers
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case("location: /final")));
+
+        let response = send(None, None).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "done");
+        assert_eq!(response.redirect_count, 1);
+        assert_eq!(response.final_url, Some(format!("{}/final", base)));
+
+        // 리다이렉트 허용 횟수를 넘으면 오류
+        assert!(send(Some(true), Some(0)).is_err());
+    }
+
+    #[test]
+    fn split_curl_args_quoting() {
+        let args = |curl: &str| split_curl_args(curl).unwrap();
+
+        assert_eq!(args("curl  https://a.com\t-X GET"), vec!["curl", "https://a.com", "-X", "GET"]);
+        // 작은따옴표 안은 그대로, 인접한 인용 구간은 한 인자로 이어짐
+        assert_eq!(args(r#"curl -H 'Cookie: a=1; b="2"' 'x'"y"z"#), vec!["curl", "-H", r#"Cookie: a=1; b="2""#, "xyz"]);
+        assert_eq!(args("curl '' next"), vec!["curl", "", "next"]);
+        // 큰따옴표 안에서는 \" \\ \$ \` 만 이스케이프
+        assert_eq!(args(r#"curl -d "{\"k\":\"v\\\" \$HOME \n}""#), vec!["curl", "-d", r#"{"k":"v\" $HOME \n}"#]);
+        // 따옴표 밖의 백슬래시는 다음 문자를 그대로, 줄 끝이면 이어쓰기
+        assert_eq!(args("curl a\\ b \\\n  -X\\\r\n POST"), vec!["curl", "a b", "-X", "POST"]);
+        // $'...'는 \n \t \r 과 \' 를 해석
+        assert_eq!(args(r"curl --data-raw $'a\nb\tc\'d\\e'"), vec!["curl", "--data-raw", "a\nb\tc'd\\e"]);
+        assert_eq!(args("curl $HOME"), vec!["curl", "$HOME"]);
+
+        assert!(split_curl_args("curl 'open").is_err());
+        assert!(split_curl_args("curl \"open").is_err());
+        assert!(split_curl_args(r"curl $'open\'").is_err());
+    }
+
+    #[test]
+    fn parse_curl_extracts_request() {
+        let parsed = parse_curl(
+            "curl -A 'Agent/1.0' -b 'NID=1; a=b' -H 'x-CUSTOM-header:  v ' -e https://ref.com https://a.com/api",
+        )
+        .unwrap();
+        assert_eq!(parsed.url.as_deref(), Some("https://a.com/api"));
+        assert_eq!(parsed.method, "GET");
+        assert_eq!(parsed.headers.get("User-Agent").map(String::as_str), Some("Agent/1.0"));
+        assert_eq!(parsed.headers.get("Cookie").map(String::as_str), Some("NID=1; a=b"));
+        assert_eq!(parsed.headers.get("X-Custom-Header").map(String::as_str), Some("v"));
+        assert_eq!(parsed.headers.get("Referer").map(String::as_str), Some("https://ref.com"));
+
+        // 쿠키 파일 경로(-b file)는 헤더로 쓰지 않음, 바디가 있으면 POST
+        let parsed = parse_curl("curl -b cookies.txt --data a=1 -d b=2 --url=https://a.com").unwrap();
+        assert!(!parsed.headers.contains_key("Cookie"));
+        assert_eq!(parsed.method, "POST");
+        assert_eq!(parsed.body.as_deref(), Some("a=1&b=2"));
+
+        assert_eq!(normalize_header_name(" content-TYPE "), "Content-Type");
+        assert!(parse_curl("wget https://a.com").is_err());
+        assert!(parse_curl("curl -H").is_err());
+    }
+
+    #[test]
+    fn check_curl_rejects_missing_url_and_bad_scheme() {
+        assert_eq!(check_curl("curl -H 'A: b'").err().as_deref(), Some("cURL에 URL이 없습니다."));
+        assert!(check_curl("curl ftp://a.com").err().unwrap().contains("ftp"));
+        assert!(check_curl("curl file:///etc/passwd").is_err());
+
+        let validation = check_curl("curl https://a.com -H 'cookie: c=1' -H 'user-agent: UA'").unwrap();
+        assert!(validation.warnings.is_empty());
+        assert_eq!(validation.headers.get("Cookie").map(String::as_str), Some("c=1"));
+        // http, 스킴 없음, User-Agent/인증 헤더 누락은 경고만
+        let validation = check_curl("curl a.com").unwrap();
+        assert_eq!(validation.warnings.len(), 3);
+        let validation = check_curl("curl http://a.com -H 'Authorization: t' -A UA").unwrap();
+        assert_eq!(validation.warnings, vec!["암호화되지 않은 http URL입니다.".to_string()]);
+    }
+
+    #[test]
+    fn unique_ledger_nickname_adds_suffix() {
+        let conn = migrated_conn();
+        assert_eq!(unique_ledger_nickname(&conn, "가족").unwrap(), "가족");
+        conn.execute_batch("INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '가족'), ('b', '가족 (2)');")
+            .unwrap();
+        assert_eq!(unique_ledger_nickname(&conn, "가족").unwrap(), "가족 (3)");
+        assert!(ensure_ledger_nickname_available(&conn, "가족 (3)", "").is_ok());
+    }
+
+    #[test]
+    fn encode_request_body_modes() {
+        let encode = |body_type: Option<&str>, body: Option<&str>, fields: Option<&[(&str, &str)]>, headers: &[(&str, &str)]| {
+            let mut headers: HashMap<String, String> =
+                headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            let fields = fields.map(|fields| fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+            encode_request_body(body_type, body.map(str::to_string), fields, &mut headers).map(|body| (body, headers))
+        };
+
+        // raw(기본값)는 바디를 그대로 두고 Content-Type을 추가하지 않음
+        let (body, headers) = encode(None, Some("a b"), None, &[]).unwrap();
+        assert_eq!(body.as_deref(), Some("a b"));
+        assert!(headers.is_empty());
+        let (body, headers) = encode(Some("raw"), None, None, &[]).unwrap();
+        assert_eq!((body, headers.len()), (None, 0));
+
+        let (body, headers) = encode(Some("json"), Some(r#"{"a":1}"#), None, &[]).unwrap();
+        assert_eq!(body.as_deref(), Some(r#"{"a":1}"#));
+        assert_eq!(headers.get("Content-Type").map(String::as_str), Some("application/json"));
+        // 요청별 Content-Type 헤더가 있으면 덮어쓰지 않음
+        let (_, headers) = encode(Some("json"), Some("[]"), None, &[("content-type", "application/vnd.api+json")]).unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers.get("content-type").map(String::as_str), Some("application/vnd.api+json"));
+        assert!(encode(Some("json"), Some("{a:1}"), None, &[]).unwrap_err().starts_with("JSON 바디 형식이 올바르지 않습니다"));
+
+        let (body, headers) = encode(Some("form"), None, Some(&[("q", "a b"), ("k", "&")]), &[]).unwrap();
+        assert_eq!(body.as_deref(), Some("k=%26&q=a+b"));
+        assert_eq!(
+            headers.get("Content-Type").map(String::as_str),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert!(encode(Some("form"), Some("x=1"), Some(&[]), &[]).is_err());
+        assert!(encode(Some("form"), None, None, &[]).is_err());
+
+        assert_eq!(encode(Some("xml"), None, None, &[]).unwrap_err(), "지원하지 않는 body_type입니다: xml");
+    }
+
+    #[test]
+    fn incomplete_entries_use_ledger_category_ids() {
+        let conn = migrated_conn();
+        conn.execute_batch(
+            "INSERT INTO tbl_ledger_account (id, nickname) VALUES ('a', '개인');
+             INSERT INTO tbl_category (id, name, color) VALUES ('c1', '카페', '#000000');
+             INSERT INTO tbl_ledger_entry (id, account_id, type, amount, date, title, category) VALUES
+                ('e1', 'a', 'expense', 1, '2024-03-01', '점심', 'food'),
+                ('e2', 'a', 'income', 2, '2024-03-02', '월급', 'salary'),
+                ('e3', 'a', 'expense', 3, '2024-03-03', '커피', '카페'),
+                ('e4', 'a', 'expense', 4, '2024-03-04', '?', '모름'),
+                ('e5', 'a', 'expense', 5, '2024-03-05', '?', 'Food');
+             INSERT INTO tbl_ledger_tag (id, entry_id, tag) VALUES ('t1', 'e5', 'x');",
+        )
+        .unwrap();
+        let ids = |untagged, uncategorized| -> Vec<String> {
+            list_incomplete_ledger_entries(&conn, "a", untagged, uncategorized)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect()
+        };
+
+        assert_eq!(ids(false, true), vec!["e5", "e4"]);
+        assert_eq!(ids(true, true), vec!["e4"]);
+        assert_eq!(ids(true, false), vec!["e4", "e3", "e2", "e1"]);
+    }
+
+    #[test]
+    fn bulk_assign_item_ids_dedupes_and_checks_provider() {
+        assert_eq!(bulk_assign_item_ids("naver", vec![3, 1, 3, 2, 1]).unwrap(), vec![1, 2, 3]);
+        assert_eq!(bulk_assign_item_ids("coupang", vec![]).unwrap(), Vec::<i64>::new());
+        assert!(bulk_assign_item_ids("gmarket", vec![1]).is_err());
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // RFC 4231 테스트 벡터 1, 2, 6 (6은 블록 크기보다 긴 키)
+    #[test]
+    fn hmac_sha256_rfc4231_vectors() {
+        assert_eq!(
+            hex(&hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn cea_authorization_fixed_output() {
+        let header = cea_authorization(
+            "get",
+            "https://api-gateway.coupang.com/v2/providers/openapi/apis/api/v4/vendors/A001/ordersheets?createdAtFrom=2025-01-01&status=ACCEPT#frag",
+            "access-key",
+            "secret-key",
+            "250101T000000Z",
+        )
+        .unwrap();
+        assert_eq!(
+            header,
+            "CEA algorithm=HmacSHA256, access-key=access-key, signed-date=250101T000000Z, \
+             signature=91c8476e1748bb84f25448057ffe61373ac620da6d5e9339727e806f67bb0737"
+        );
+        assert!(cea_authorization("GET", "not a url", "a", "s", "250101T000000Z").is_err());
+    }
+}
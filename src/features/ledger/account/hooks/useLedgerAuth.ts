@@ -1,8 +1,21 @@
-import { useState, useCallback } from "react";
-import { verifyLedgerPassword, checkPasswordExpiry, updateLedgerPassword } from "../../shared";
+import { useState, useCallback, useEffect, useRef } from "react";
+import { unlockLedgerAccount, checkPasswordExpiry, updateLedgerPassword, getLedgerUnlockStatus } from "../../shared";
 
-export function useLedgerAuth() {
+// 백엔드 잠금 해제 세션 만료 확인 주기
+const UNLOCK_STATUS_POLL_MS = 30 * 1000;
+
+// 백엔드가 잠긴 계정 접근을 거부할 때의 오류 메시지
+const LOCKED_ERROR_MESSAGE = "가계부 계정이 잠겨 있습니다";
+
+export function isLedgerLockedError(err: unknown): boolean {
+  const message = err instanceof Error ? err.message : String(err);
+  return message.includes(LOCKED_ERROR_MESSAGE);
+}
+
+export function useLedgerAuth(onLocked?: (accountId: string) => void) {
   const [isAuthenticated, setIsAuthenticated] = useState<Record<string, boolean>>({});
+  const onLockedRef = useRef(onLocked);
+  onLockedRef.current = onLocked;
 
   const checkExpiry = useCallback(async () => {
     await checkPasswordExpiry();
@@ -10,7 +23,7 @@ export function useLedgerAuth() {
 
   const verifyPassword = useCallback(
     async (accountId: string, password: string): Promise<boolean> => {
-      const isValid = await verifyLedgerPassword(accountId, password);
+      const isValid = await unlockLedgerAccount(accountId, password);
       if (isValid) {
         setIsAuthenticated((prev) => ({ ...prev, [accountId]: true }));
       }
@@ -37,12 +50,50 @@ export function useLedgerAuth() {
     [isAuthenticated]
   );
 
+  const markLocked = useCallback((accountId: string) => {
+    setIsAuthenticated((prev) => ({ ...prev, [accountId]: false }));
+    onLockedRef.current?.(accountId);
+  }, []);
+
+  // 가계부 API 호출 오류가 잠금 때문이면 인증 상태를 초기화 (true 반환)
+  const handleLockedError = useCallback(
+    (accountId: string, err: unknown): boolean => {
+      if (!isLedgerLockedError(err)) {
+        return false;
+      }
+      markLocked(accountId);
+      return true;
+    },
+    [markLocked]
+  );
+
+  // 일정 시간 접근이 없으면 백엔드가 계정을 다시 잠그므로 주기적으로 확인
+  useEffect(() => {
+    const authenticatedIds = Object.keys(isAuthenticated).filter((id) => isAuthenticated[id]);
+    if (authenticatedIds.length === 0) {
+      return;
+    }
+    const interval = setInterval(() => {
+      authenticatedIds.forEach((accountId) => {
+        getLedgerUnlockStatus(accountId)
+          .then((unlocked) => {
+            if (!unlocked) {
+              markLocked(accountId);
+            }
+          })
+          .catch(console.error);
+      });
+    }, UNLOCK_STATUS_POLL_MS);
+
+    return () => clearInterval(interval);
+  }, [isAuthenticated, markLocked]);
+
   return {
     verifyPassword,
     updatePassword,
     setAuthenticated,
     isAccountAuthenticated,
+    handleLockedError,
     checkExpiry,
   };
 }
-
@@ -3,6 +3,7 @@ import { useNavigate } from "react-router-dom";
 import { Lock, User } from "lucide-react";
 import { RetroButton, RetroInput } from "@shared/ui";
 import { useCreateLedgerAccount } from "../hooks";
+import { checkPasswordStrength } from "../../shared";
 
 interface LedgerAccountOnboardingProps {
   onComplete?: (accountId: string) => void;
@@ -30,14 +31,16 @@ export const LedgerAccountOnboarding = ({
     }
 
     if (usePassword) {
-      if (!password || password.length < 4) {
-        setError("패스워드는 최소 4자 이상이어야 합니다.");
-        return;
-      }
       if (password !== confirmPassword) {
         setError("패스워드가 일치하지 않습니다.");
         return;
       }
+      // 백엔드와 같은 패스워드 강도 정책 적용
+      const strength = await checkPasswordStrength(password);
+      if (!strength.isStrongEnough) {
+        setError(`패스워드가 너무 약합니다: ${strength.issues.join(", ")}`);
+        return;
+      }
     }
 
     try {
@@ -120,7 +123,7 @@ export const LedgerAccountOnboarding = ({
                           type={showPassword ? "text" : "password"}
                           value={password}
                           onChange={(e) => setPassword(e.target.value)}
-                          placeholder="최소 4자 이상"
+                          placeholder="8자 이상, 대/소문자·숫자·특수문자 중 3가지 이상"
                           className="w-full pr-10"
                         />
                         <button
@@ -18,13 +18,21 @@ export const LedgerAccountSelector = ({
   onSettings,
 }: LedgerAccountSelectorProps) => {
   const { data: accounts, isLoading } = useLedgerAccounts();
-  const { isAccountAuthenticated, setAuthenticated } = useLedgerAuth();
   const [showPasswordDialog, setShowPasswordDialog] = useState(false);
   const [passwordAccount, setPasswordAccount] = useState<LedgerAccount | null>(null);
   const [showDropdown, setShowDropdown] = useState(false);
 
   const selectedAccount = accounts?.find((a) => a.id === selectedAccountId);
 
+  // 선택된 계정의 잠금 해제 세션이 만료되면 패스워드를 다시 요청
+  const { isAccountAuthenticated, setAuthenticated } = useLedgerAuth((accountId) => {
+    const account = accounts?.find((a) => a.id === accountId);
+    if (account && account.id === selectedAccountId) {
+      setPasswordAccount(account);
+      setShowPasswordDialog(true);
+    }
+  });
+
   const handleAccountClick = (account: LedgerAccount) => {
     if (account.passwordHash && !isAccountAuthenticated(account.id)) {
       setPasswordAccount(account);
@@ -2,6 +2,7 @@ import { useState } from "react";
 import { X, Lock, Eye, EyeOff } from "lucide-react";
 import { RetroButton, RetroInput } from "@shared/ui";
 import { useLedgerAuth } from "../hooks";
+import { checkPasswordStrength } from "../../shared";
 
 interface LedgerPasswordDialogProps {
   accountId: string;
@@ -32,14 +33,16 @@ export const LedgerPasswordDialog = ({
     setError("");
 
     if (onChangePassword) {
-      if (!newPassword || newPassword.length < 4) {
-        setError("새 패스워드는 최소 4자 이상이어야 합니다.");
-        return;
-      }
       if (newPassword !== confirmPassword) {
         setError("새 패스워드가 일치하지 않습니다.");
         return;
       }
+      // 백엔드와 같은 패스워드 강도 정책 적용
+      const strength = await checkPasswordStrength(newPassword);
+      if (!strength.isStrongEnough) {
+        setError(`새 패스워드가 너무 약합니다: ${strength.issues.join(", ")}`);
+        return;
+      }
       setLoading(true);
       try {
         await updatePassword(accountId, newPassword);
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { LedgerAccount, LedgerEntry, LedgerHistory, LedgerEntryInput } from "./types";
+import type { LedgerAccount, LedgerAccountSummary, LedgerEntry, LedgerHistory, LedgerHistoryWithEntry, LedgerHistoryDiff, LedgerEntryInput, LedgerRule, LedgerRuleInput, MerchantRule, PasswordStrength } from "./types";
 
 // 가계부 계정 관리
 export async function createLedgerAccount(
@@ -9,7 +9,7 @@ export async function createLedgerAccount(
   return invoke("create_ledger_account", { nickname, password });
 }
 
-export async function listLedgerAccounts(): Promise<LedgerAccount[]> {
+export async function listLedgerAccounts(): Promise<LedgerAccountSummary[]> {
   return invoke("list_ledger_accounts");
 }
 
@@ -20,6 +20,32 @@ export async function verifyLedgerPassword(
   return invoke("verify_ledger_password", { accountId, password });
 }
 
+// 패스워드 확인 후 백엔드 잠금 해제 세션 시작
+export async function unlockLedgerAccount(
+  accountId: string,
+  password: string
+): Promise<boolean> {
+  return invoke("unlock_ledger_account", { accountId, password });
+}
+
+// 패스워드 확인 실패 기록 초기화 (잠금 해제된 계정만 가능)
+export async function clearAuthLockout(accountId: string): Promise<void> {
+  return invoke("clear_auth_lockout", { accountId });
+}
+
+export async function lockLedgerAccount(accountId: string): Promise<void> {
+  return invoke("lock_ledger_account", { accountId });
+}
+
+// 잠금 해제 세션 유효 여부 (세션을 연장하지 않음)
+export async function getLedgerUnlockStatus(accountId: string): Promise<boolean> {
+  return invoke("get_ledger_unlock_status", { accountId });
+}
+
+export async function checkPasswordStrength(password: string): Promise<PasswordStrength> {
+  return invoke("check_password_strength", { password });
+}
+
 export async function updateLedgerPassword(
   accountId: string,
   password: string
@@ -27,6 +53,23 @@ export async function updateLedgerPassword(
   return invoke("update_ledger_password", { accountId, password });
 }
 
+// 계정 이름/표시 통화 기호 변경 (지정한 값만 변경)
+export async function updateLedgerAccount(
+  accountId: string,
+  nickname?: string,
+  currencySymbol?: string
+): Promise<LedgerAccount> {
+  return invoke("update_ledger_account", { accountId, nickname, currencySymbol });
+}
+
+// 닉네임만 변경 (다른 계정과 대소문자 무시 중복 시 오류)
+export async function updateLedgerAccountNickname(
+  accountId: string,
+  nickname: string
+): Promise<LedgerAccount> {
+  return invoke("update_ledger_account_nickname", { accountId, nickname });
+}
+
 export async function checkPasswordExpiry(): Promise<void> {
   return invoke("check_password_expiry");
 }
@@ -50,6 +93,14 @@ export async function updateLedgerEntry(
   return invoke("update_ledger_entry", { entryId, entry });
 }
 
+// 항목을 다른 계정으로 이동 (태그/히스토리 유지)
+export async function moveLedgerEntry(
+  entryId: string,
+  targetAccountId: string
+): Promise<void> {
+  return invoke("move_ledger_entry", { entryId, targetAccountId });
+}
+
 export async function deleteLedgerEntry(entryId: string): Promise<void> {
   return invoke("delete_ledger_entry", { entryId });
 }
@@ -61,6 +112,33 @@ export async function listLedgerEntries(
   return invoke("list_ledger_entries", { accountId, yearMonth });
 }
 
+// 영수증 URL이 있는 항목 (yearMonth 미지정 시 전체 기간)
+export async function listLedgerEntriesWithReceipt(
+  accountId: string,
+  yearMonth?: string
+): Promise<LedgerEntry[]> {
+  return invoke("list_ledger_entries_with_receipt", { accountId, yearMonth });
+}
+
+// 정리가 필요한 항목 (also* 지정 시 두 조건을 모두 만족하는 항목만)
+export async function listUntaggedLedgerEntries(
+  accountId: string,
+  alsoUncategorized?: boolean
+): Promise<LedgerEntry[]> {
+  return invoke("list_untagged_ledger_entries", { accountId, alsoUncategorized });
+}
+
+export async function listUncategorizedLedgerEntries(
+  accountId: string,
+  alsoUntagged?: boolean
+): Promise<LedgerEntry[]> {
+  return invoke("list_uncategorized_ledger_entries", { accountId, alsoUntagged });
+}
+
+export async function getLedgerUrlCount(accountId: string): Promise<number> {
+  return invoke("get_ledger_url_count", { accountId });
+}
+
 export async function getLedgerEntry(entryId: string): Promise<LedgerEntry | null> {
   return invoke("get_ledger_entry", { entryId });
 }
@@ -70,6 +148,110 @@ export async function listLedgerHistory(entryId: string): Promise<LedgerHistory[
   return invoke("list_ledger_history", { entryId });
 }
 
+export async function getLedgerHistoryDiff(historyId: string): Promise<LedgerHistoryDiff> {
+  return invoke("get_ledger_history_diff", { historyId });
+}
+
+export async function listLedgerHistoryAll(
+  accountId: string,
+  limit?: number,
+  offset?: number
+): Promise<LedgerHistoryWithEntry[]> {
+  return invoke("list_ledger_history_all", { accountId, limit, offset });
+}
+
+// 계정 히스토리 정리 (항목별 최근 N개 보관 / 기준일 이전 삭제), 삭제된 행 수 반환
+export async function pruneLedgerHistory(
+  accountId: string,
+  keepPerEntry?: number,
+  beforeDate?: string
+): Promise<number> {
+  return invoke("prune_ledger_history", { accountId, keepPerEntry, beforeDate });
+}
+
+// 결제 내역을 가계부 지출 항목으로 변환 (이미 변환된 결제면 기존 항목 ID 반환)
+export async function paymentToLedger(
+  accountId: string,
+  provider: "naver" | "coupang",
+  paymentId: number
+): Promise<string> {
+  return invoke("payment_to_ledger", { accountId, provider, paymentId });
+}
+
+// 계정 백업/복원 (JSON 문자열, 백업에는 패스워드가 포함되지 않음)
+export async function backupLedgerAccount(accountId: string): Promise<string> {
+  return invoke("backup_ledger_account", { accountId });
+}
+
+// 새 계정으로 복원할 때 password를 지정하면 해당 패스워드로 보호
+export async function restoreLedgerAccount(
+  jsonContent: string,
+  targetAccountId?: string,
+  password?: string
+): Promise<string> {
+  return invoke("restore_ledger_account", { jsonContent, targetAccountId, password });
+}
+
+// 월간 요약 텍스트 (복사/공유용)
+export async function exportLedgerSummaryText(accountId: string, yearMonth: string): Promise<string> {
+  return invoke("export_ledger_summary_text", { accountId, yearMonth });
+}
+
+// 자동 분류 규칙
+export async function createLedgerRule(accountId: string, rule: LedgerRuleInput): Promise<LedgerRule> {
+  return invoke("create_ledger_rule", { accountId, rule });
+}
+
+export async function listLedgerRules(accountId: string): Promise<LedgerRule[]> {
+  return invoke("list_ledger_rules", { accountId });
+}
+
+export async function deleteLedgerRule(ruleId: string): Promise<void> {
+  return invoke("delete_ledger_rule", { ruleId });
+}
+
+export async function applyLedgerRules(accountId: string, yearMonth: string): Promise<number> {
+  return invoke("apply_ledger_rules", { accountId, yearMonth });
+}
+
+// 가맹점 카테고리 규칙
+export async function createMerchantRule(
+  pattern: string,
+  matchType: MerchantRule["matchType"],
+  category: string,
+  priority?: number
+): Promise<MerchantRule> {
+  return invoke("create_merchant_rule", { pattern, matchType, category, priority });
+}
+
+export async function updateMerchantRule(
+  ruleId: string,
+  pattern: string,
+  matchType: MerchantRule["matchType"],
+  category: string,
+  priority?: number
+): Promise<MerchantRule> {
+  return invoke("update_merchant_rule", { ruleId, pattern, matchType, category, priority });
+}
+
+export async function deleteMerchantRule(ruleId: string): Promise<void> {
+  return invoke("delete_merchant_rule", { ruleId });
+}
+
+export async function listMerchantRules(): Promise<MerchantRule[]> {
+  return invoke("list_merchant_rules");
+}
+
+// 결제 사용자의 해당 월 미분류 항목에 가맹점 규칙 적용, 변경된 항목 수 반환
+export async function applyMerchantRulesToPending(userId: string, yearMonth: string): Promise<number> {
+  return invoke("apply_merchant_rules_to_pending", { userId, yearMonth });
+}
+
+// 색상이 비어 있는 항목에 카테고리 색상 채우기, 갱신된 항목 수 반환
+export async function backfillEntryColors(accountId: string): Promise<number> {
+  return invoke("backfill_entry_colors", { accountId });
+}
+
 
 
 
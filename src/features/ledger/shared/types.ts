@@ -6,6 +6,17 @@ export interface LedgerAccount {
   passwordExpiresAt?: string;
   createdAt: string;
   updatedAt: string;
+  currencySymbol: string;
+}
+
+// 가계부 계정 목록 항목 (수입/지출 요약 포함)
+export interface LedgerAccountSummary extends LedgerAccount {
+  // 잠긴 계정은 null
+  totalIncome: number | null;
+  totalExpense: number | null;
+  netBalance: number | null;
+  entryCount: number;
+  lastEntryDate?: string;
 }
 
 // 가계부 항목 타입
@@ -38,6 +49,62 @@ export interface LedgerHistory {
   createdAt: string;
 }
 
+// 계정 전체 히스토리 타입 (항목 정보 포함)
+export interface LedgerHistoryWithEntry extends LedgerHistory {
+  entryTitle?: string;
+  entryCategory?: string;
+  entryDate?: string;
+}
+
+// 히스토리 변경 필드 (생성 시 before, 삭제 시 after가 null)
+export interface LedgerFieldChange {
+  field: string;
+  before: unknown;
+  after: unknown;
+}
+
+// 히스토리 변경 내역 (rawFallback이면 스냅샷 파싱 실패로 원본 스냅샷만 제공)
+export interface LedgerHistoryDiff {
+  historyId: string;
+  action: "create" | "update" | "delete";
+  changes: LedgerFieldChange[];
+  rawFallback: boolean;
+  snapshotBefore?: string;
+  snapshotAfter?: string;
+}
+
+// 가계부 자동 분류 규칙 타입
+export interface LedgerRule {
+  id: string;
+  accountId: string;
+  matchField: "merchant" | "title";
+  matchType: "exact" | "contains";
+  matchValue: string;
+  setCategory?: string;
+  setTags: string[];
+  createdAt: string;
+}
+
+export type LedgerRuleInput = Omit<LedgerRule, "id" | "accountId" | "createdAt">;
+
+// 가맹점 카테고리 규칙 (결제에서 변환한 항목 자동 분류용)
+export interface MerchantRule {
+  id: string;
+  pattern: string;
+  matchType: "exact" | "prefix" | "contains";
+  category: string;
+  priority: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
+// 패스워드 강도 검사 결과
+export interface PasswordStrength {
+  score: number;
+  isStrongEnough: boolean;
+  issues: string[];
+}
+
 // 가계부 항목 생성/수정용 타입 (id 제외)
 export type LedgerEntryInput = Omit<LedgerEntry, "id" | "createdAt" | "updatedAt">;
 
@@ -7,8 +7,8 @@ interface UseCategoriesReturn {
   loading: boolean;
   error: string | null;
   refresh: () => Promise<void>;
-  createCategory: (name: string, color?: string) => Promise<Category>;
-  deleteCategory: (categoryId: string) => Promise<void>;
+  createCategory: (name: string, color?: string, parentId?: string) => Promise<Category>;
+  deleteCategory: (categoryId: string, cascadeChildren?: boolean) => Promise<void>;
 }
 
 /**
@@ -36,16 +36,23 @@ export const useCategories = (): UseCategoriesReturn => {
     loadCategories();
   }, [loadCategories]);
 
-  const createCategory = useCallback(async (name: string, color?: string): Promise<Category> => {
-    const category = await invoke<Category>("create_category", { name, color });
-    setCategories((prev) => [...prev, category].sort((a, b) => a.name.localeCompare(b.name)));
-    return category;
-  }, []);
+  const createCategory = useCallback(
+    async (name: string, color?: string, parentId?: string): Promise<Category> => {
+      const category = await invoke<Category>("create_category", { name, color, parentId });
+      setCategories((prev) => [...prev, category].sort((a, b) => a.name.localeCompare(b.name)));
+      return category;
+    },
+    []
+  );
 
-  const deleteCategory = useCallback(async (categoryId: string): Promise<void> => {
-    await invoke("delete_category", { categoryId });
-    setCategories((prev) => prev.filter((c) => c.id !== categoryId));
-  }, []);
+  // 하위 카테고리가 이동/삭제될 수 있으므로 삭제 후 목록을 다시 불러옴
+  const deleteCategory = useCallback(
+    async (categoryId: string, cascadeChildren?: boolean): Promise<void> => {
+      await invoke("delete_category", { categoryId, cascadeChildren });
+      await loadCategories();
+    },
+    [loadCategories]
+  );
 
   return {
     categories,
@@ -10,6 +10,7 @@ interface UseProductMetaReturn {
   refresh: () => Promise<void>;
   save: (input: ProductMetaInput) => Promise<ProductMeta>;
   remove: () => Promise<void>;
+  copyTo: (toProvider: AccountProvider, toItemId: number, overwrite: boolean) => Promise<ProductMeta>;
 }
 
 /**
@@ -82,6 +83,30 @@ export const useProductMeta = (
     }
   }, [provider, itemId]);
 
+  // 현재 상품의 메타데이터를 다른 상품(다른 provider 포함)으로 복사
+  const copyTo = useCallback(
+    async (toProvider: AccountProvider, toItemId: number, overwrite: boolean): Promise<ProductMeta> => {
+      setSaving(true);
+      setError(null);
+      try {
+        return await invoke<ProductMeta>("copy_product_meta", {
+          fromProvider: provider,
+          fromItemId: itemId,
+          toProvider,
+          toItemId,
+          overwrite,
+        });
+      } catch (err) {
+        const errorMessage = err instanceof Error ? err.message : String(err);
+        setError(errorMessage);
+        throw new Error(errorMessage);
+      } finally {
+        setSaving(false);
+      }
+    },
+    [provider, itemId]
+  );
+
   return {
     meta,
     loading,
@@ -90,6 +115,7 @@ export const useProductMeta = (
     refresh: loadMeta,
     save,
     remove,
+    copyTo,
   };
 };
 
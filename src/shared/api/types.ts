@@ -4,6 +4,45 @@ export type ProxyResponse = {
   final_url?: string | null;
   response_headers?: string[] | null;
   request_headers?: string[] | null;
+  redirect_count?: number;
+  body_was_lossy?: boolean;
+  // verbose 요청일 때만 포함 (밀리초)
+  timing?: ProxyTiming | null;
+};
+
+export type ProxyTiming = {
+  namelookup_ms: number;
+  connect_ms: number;
+  pretransfer_ms: number;
+  starttransfer_ms: number;
+  total_ms: number;
+};
+
+// proxy_request 호출 인자 (headers 외에는 모두 선택)
+export type ProxyRequestArgs = {
+  url: string;
+  method: string;
+  headers: Record<string, string>;
+  body?: string | null;
+  followRedirects?: boolean;
+  maxRedirects?: number;
+  userAgent?: string;
+  // 기본 "raw", "json"은 바디 검증 후 JSON Content-Type, "form"은 formFields를 x-www-form-urlencoded로 전송
+  bodyType?: "raw" | "json" | "form" | null;
+  formFields?: Record<string, string> | null;
+  // signKey/signSecret을 함께 지정하면 쿠팡 CEA 서명으로 Authorization 헤더를 대체
+  // signKey: access key, signSecret: HMAC 비밀 키, signAlgo: 생략 시 "hmac-sha256" (현재 유일한 값)
+  signKey?: string | null;
+  signSecret?: string | null;
+  signAlgo?: "hmac-sha256" | null;
+  // true면 응답에 timing 포함
+  verbose?: boolean;
+};
+
+// proxy_request_json 응답 (json_error: 바디가 JSON이 아닐 때의 파싱 오류)
+export type JsonProxyResponse = ProxyResponse & {
+  json_body?: unknown;
+  json_error?: string | null;
 };
 
 export type DbStatus = {
@@ -14,6 +53,72 @@ export type DbStatus = {
   tables: string[];
 };
 
+// get_db_status_readonly 결과 (마이그레이션 없이 조회)
+export type ReadonlyDbStatus = DbStatus & {
+  schemaVersion?: number | null;
+  migrationPending: boolean;
+};
+
+// normalize_config 결과 (relocateNeeded: 저장된 DB 파일을 찾을 수 없음)
+export type ConfigNormalization = {
+  status: "notConfigured" | "unchanged" | "normalized" | "relocateNeeded";
+  originalPath?: string | null;
+  normalizedPath?: string | null;
+};
+
+export type HealthStatus = {
+  dbConfigured: boolean;
+  dbPath?: string | null;
+  dbExists: boolean;
+  schemaVersion?: number | null;
+  integrityOk?: boolean | null;
+  userCount?: number | null;
+  journalMode?: string | null;
+  foreignKeysOn?: boolean | null;
+  dbError?: string | null;
+  appDataDir?: string | null;
+  configPath?: string | null;
+  configExists: boolean;
+  configParsed: boolean;
+  configError?: string | null;
+};
+
+// get_db_schema 결과 (스키마 뷰어)
+export type ColumnInfo = {
+  name: string;
+  dataType: string;
+  notNull: boolean;
+  defaultValue?: string | null;
+  isPk: boolean;
+};
+
+export type IndexInfo = {
+  name: string;
+  unique: boolean;
+  columns: string[];
+};
+
+export type TableSchema = {
+  name: string;
+  sql: string;
+  columns: ColumnInfo[];
+  indexes: IndexInfo[];
+};
+
+// verify_schema 결과 (코드 기준 스키마 대비 누락 항목)
+export type SchemaReport = {
+  ok: boolean;
+  missingTables: string[];
+  missingColumns: { table: string; columns: string[] }[];
+  missingIndexes: string[];
+};
+
+// reset_data 결과 (테이블별 삭제된 행 수)
+export type TableResetCount = {
+  tableName: string;
+  deleted: number;
+};
+
 export type HasUsersResponse = {
   hasUsers: boolean;
 };
@@ -35,6 +140,15 @@ export type AccountProvider = "naver" | "coupang";
 
 export type CredentialMap = Record<string, string>;
 
+// validate_curl 결과 (warnings는 저장을 막지 않는 경고)
+export type CurlValidation = {
+  url: string;
+  method: string;
+  headers: Record<string, string>;
+  body?: string | null;
+  warnings: string[];
+};
+
 export type PaymentItem = {
   id: number;
   lineNo: number;
@@ -108,6 +222,13 @@ export type CoupangPaymentListItem = {
   items: CoupangPaymentItem[];
 };
 
+// get_item_price_history 결과 (결제 이력을 보관하지 않으면 빈 목록)
+export type ItemPricePoint = {
+  recordedAt: string;
+  combinedUnitPrice?: number | null;
+  lineAmount?: number | null;
+};
+
 export type SearchResultItem = {
   id: number;
   provider: string;
@@ -131,9 +252,14 @@ export type Category = {
   id: string;
   name: string;
   color?: string | null;
+  parentId?: string | null;
   createdAt: string;
 };
 
+export type CategoryNode = Category & {
+  children: CategoryNode[];
+};
+
 export type ProductMeta = {
   id: string;
   provider: AccountProvider;
@@ -147,6 +273,18 @@ export type ProductMeta = {
   updatedAt: string;
 };
 
+export type PurchaseHistoryEntry = {
+  paidAt: string;
+  quantity: number;
+  unitPrice?: number | null;
+  lineAmount?: number | null;
+  merchantName: string;
+};
+
+export type ProductMetaWithHistory = ProductMeta & {
+  purchaseHistory: PurchaseHistoryEntry[];
+};
+
 export type ProductMetaInput = {
   memo?: string | null;
   url?: string | null;